@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+ALTER TABLE sale_product
+  DROP COLUMN product_name,
+  DROP COLUMN unit_price;
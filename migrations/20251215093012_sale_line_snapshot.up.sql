@@ -0,0 +1,10 @@
+-- Add up migration script here
+
+-- unit_price is in minor units, both columns keep the product as it was sold
+ALTER TABLE sale_product
+  ADD COLUMN product_name VARCHAR(100) NOT NULL DEFAULT '',
+  ADD COLUMN unit_price BIGINT UNSIGNED NOT NULL DEFAULT 0;
+
+UPDATE sale_product sp
+JOIN products p ON p.sku = sp.product_sku
+SET sp.product_name = p.name, sp.unit_price = p.price;
@@ -0,0 +1,6 @@
+-- Add down migration script here
+
+ALTER TABLE sales
+  DROP COLUMN status,
+  DROP COLUMN cancel_reason,
+  DROP COLUMN cancelled_at;
@@ -0,0 +1,6 @@
+-- Add up migration script here
+
+ALTER TABLE sales
+  ADD COLUMN status ENUM('active', 'cancelled') NOT NULL DEFAULT 'active',
+  ADD COLUMN cancel_reason VARCHAR(255),
+  ADD COLUMN cancelled_at TIMESTAMP NULL DEFAULT NULL;
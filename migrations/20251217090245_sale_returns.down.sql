@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP TABLE IF EXISTS sale_return_product;
+DROP TABLE IF EXISTS sale_returns;
@@ -0,0 +1,21 @@
+-- Add up migration script here
+
+CREATE TABLE sale_returns (
+  id INT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+  sale_id INT UNSIGNED NOT NULL,
+  reason VARCHAR(255),
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY (sale_id) REFERENCES sales(id)
+);
+
+-- unit_price is in minor units, the price refunded for each unit
+CREATE TABLE sale_return_product (
+  return_id INT UNSIGNED NOT NULL,
+  product_sku VARCHAR(50) NOT NULL,
+  quantity INT UNSIGNED NOT NULL,
+  unit_price BIGINT UNSIGNED NOT NULL,
+  FOREIGN KEY (return_id) REFERENCES sale_returns(id),
+  FOREIGN KEY (product_sku) REFERENCES products(sku)
+);
+
+CREATE INDEX idx_return_id_product_sku ON sale_return_product(return_id, product_sku);
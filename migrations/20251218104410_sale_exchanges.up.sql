@@ -0,0 +1,15 @@
+-- Add up migration script here
+
+-- price_difference is in minor units, positive when the customer owes it
+-- and negative when it is owed to the customer
+CREATE TABLE sale_exchanges (
+  id INT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+  original_sale_id INT UNSIGNED NOT NULL,
+  return_id INT UNSIGNED NOT NULL,
+  replacement_sale_id INT UNSIGNED NOT NULL UNIQUE,
+  price_difference BIGINT NOT NULL,
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY (original_sale_id) REFERENCES sales(id),
+  FOREIGN KEY (return_id) REFERENCES sale_returns(id),
+  FOREIGN KEY (replacement_sale_id) REFERENCES sales(id)
+);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP TABLE IF EXISTS sale_discounts;
+DROP TABLE IF EXISTS promotions;
@@ -0,0 +1,34 @@
+-- Add up migration script here
+
+-- value is in basis points for percentage promotions (1550 = 15.5%)
+-- and in minor units for fixed amount ones
+CREATE TABLE promotions (
+  id INT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+  name VARCHAR(100) NOT NULL,
+  code VARCHAR(50) UNIQUE,
+  scope ENUM('line', 'sale') NOT NULL,
+  kind ENUM('percentage', 'fixed_amount', 'buy_x_pay_y') NOT NULL,
+  value BIGINT UNSIGNED NOT NULL DEFAULT 0,
+  buy_quantity INT UNSIGNED,
+  pay_quantity INT UNSIGNED,
+  flag VARCHAR(50),
+  starts_at TIMESTAMP NULL DEFAULT NULL,
+  ends_at TIMESTAMP NULL DEFAULT NULL,
+  active BOOLEAN NOT NULL DEFAULT TRUE,
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- amount is in minor units, product_sku is null for discounts over the whole sale
+CREATE TABLE sale_discounts (
+  id INT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+  sale_id INT UNSIGNED NOT NULL,
+  promotion_id INT UNSIGNED,
+  product_sku VARCHAR(50),
+  description VARCHAR(255) NOT NULL,
+  amount BIGINT UNSIGNED NOT NULL,
+  FOREIGN KEY (sale_id) REFERENCES sales(id),
+  FOREIGN KEY (promotion_id) REFERENCES promotions(id),
+  FOREIGN KEY (product_sku) REFERENCES products(sku)
+);
+
+CREATE INDEX idx_sale_discounts_sale_id ON sale_discounts(sale_id);
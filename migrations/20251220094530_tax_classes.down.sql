@@ -0,0 +1,9 @@
+-- Add down migration script here
+
+ALTER TABLE sale_product
+  DROP COLUMN tax_amount,
+  DROP COLUMN base_amount,
+  DROP COLUMN tax_rate,
+  DROP COLUMN tax_class;
+
+ALTER TABLE products DROP COLUMN tax_class;
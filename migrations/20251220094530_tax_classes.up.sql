@@ -0,0 +1,17 @@
+-- Add up migration script here
+
+ALTER TABLE products
+  ADD COLUMN tax_class ENUM('general', 'reduced', 'exempt') NOT NULL DEFAULT 'general';
+
+-- tax_rate is in basis points (1900 = 19%), amounts are in minor units with IVA split out
+-- of what was paid for the line, all of them kept as they were when sold
+ALTER TABLE sale_product
+  ADD COLUMN tax_class ENUM('general', 'reduced', 'exempt') NOT NULL DEFAULT 'general',
+  ADD COLUMN tax_rate INT UNSIGNED NOT NULL DEFAULT 1900,
+  ADD COLUMN base_amount BIGINT UNSIGNED NOT NULL DEFAULT 0,
+  ADD COLUMN tax_amount BIGINT UNSIGNED NOT NULL DEFAULT 0;
+
+-- Existing lines were sold at the general rate, their discounts are not taken into account
+UPDATE sale_product
+SET tax_amount = ROUND(unit_price * quantity * tax_rate / (10000 + tax_rate)),
+    base_amount = unit_price * quantity - ROUND(unit_price * quantity * tax_rate / (10000 + tax_rate));
@@ -0,0 +1,15 @@
+-- Add up migration script here
+
+-- amounts are in minor units, tendered and change_amount only apply to cash payments
+CREATE TABLE sale_payments (
+  id INT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+  sale_id INT UNSIGNED NOT NULL,
+  method ENUM('cash', 'card', 'bank_transfer', 'nequi', 'daviplata', 'store_credit') NOT NULL,
+  amount BIGINT UNSIGNED NOT NULL,
+  tendered BIGINT UNSIGNED,
+  change_amount BIGINT UNSIGNED NOT NULL DEFAULT 0,
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY (sale_id) REFERENCES sales(id)
+);
+
+CREATE INDEX idx_sale_payments_sale_id ON sale_payments(sale_id);
@@ -0,0 +1,11 @@
+-- Add up migration script here
+
+-- response is the serialized output returned to the first request made with the key
+CREATE TABLE idempotency_keys (
+  idempotency_key VARCHAR(255) PRIMARY KEY,
+  request_hash CHAR(64) NOT NULL,
+  response TEXT NOT NULL,
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_idempotency_keys_created_at ON idempotency_keys(created_at);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP TABLE IF EXISTS draft_sale_items;
+DROP TABLE IF EXISTS draft_sales;
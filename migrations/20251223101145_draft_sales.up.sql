@@ -0,0 +1,24 @@
+-- Add up migration script here
+
+-- Carts built over several requests, sale_id is set once checked out
+CREATE TABLE draft_sales (
+  id INT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+  customer_cc VARCHAR(20) NOT NULL,
+  status ENUM('open', 'parked', 'checked_out') NOT NULL DEFAULT 'open',
+  sale_id INT UNSIGNED,
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+  updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  FOREIGN KEY (customer_cc) REFERENCES customers(cc),
+  FOREIGN KEY (sale_id) REFERENCES sales(id)
+);
+
+CREATE INDEX idx_draft_sales_status ON draft_sales(status);
+
+CREATE TABLE draft_sale_items (
+  draft_sale_id INT UNSIGNED NOT NULL,
+  product_sku VARCHAR(50) NOT NULL,
+  quantity INT UNSIGNED NOT NULL,
+  PRIMARY KEY (draft_sale_id, product_sku),
+  FOREIGN KEY (draft_sale_id) REFERENCES draft_sales(id),
+  FOREIGN KEY (product_sku) REFERENCES products(sku)
+);
@@ -0,0 +1,8 @@
+-- Add down migration script here
+
+ALTER TABLE sales
+  DROP INDEX idx_sales_invoice,
+  DROP COLUMN invoice_number,
+  DROP COLUMN invoice_prefix;
+
+DROP TABLE IF EXISTS invoice_series;
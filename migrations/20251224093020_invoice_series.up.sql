@@ -0,0 +1,22 @@
+-- Add up migration script here
+
+-- Authorized invoice ranges, only one series numbers new sales at a time
+CREATE TABLE invoice_series (
+  id INT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+  prefix VARCHAR(4) UNIQUE NOT NULL,
+  range_from BIGINT UNSIGNED NOT NULL,
+  range_to BIGINT UNSIGNED NOT NULL,
+  next_number BIGINT UNSIGNED NOT NULL,
+  warning_threshold BIGINT UNSIGNED NOT NULL DEFAULT 0,
+  active BOOLEAN NOT NULL DEFAULT FALSE,
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Default series so sales keep being registered, replace it with the authorized one
+INSERT INTO invoice_series (prefix, range_from, range_to, next_number, warning_threshold, active)
+VALUES ('FV', 1, 1000000, 1, 1000, TRUE);
+
+ALTER TABLE sales
+  ADD COLUMN invoice_prefix VARCHAR(4),
+  ADD COLUMN invoice_number BIGINT UNSIGNED,
+  ADD UNIQUE INDEX idx_sales_invoice (invoice_prefix, invoice_number);
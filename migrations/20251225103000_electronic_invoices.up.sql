@@ -0,0 +1,13 @@
+-- Add up migration script here
+
+-- UBL 2.1 document generated for a sale, kept as sent to the tax authority
+CREATE TABLE electronic_invoices (
+  sale_id INT UNSIGNED PRIMARY KEY,
+  invoice_number VARCHAR(24) NOT NULL,
+  cufe CHAR(96) NOT NULL,
+  xml MEDIUMTEXT NOT NULL,
+  status ENUM('generated', 'submitted') NOT NULL DEFAULT 'generated',
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+  submitted_at TIMESTAMP NULL,
+  FOREIGN KEY (sale_id) REFERENCES sales(id)
+);
@@ -0,0 +1,20 @@
+-- Add up migration script here
+
+-- Ledger of every change made to the stock of a product
+CREATE TABLE stock_movements (
+  id INT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+  product_id INT UNSIGNED NOT NULL,
+  delta INT NOT NULL,
+  resulting_stock INT UNSIGNED NOT NULL,
+  reason ENUM('sale', 'return', 'cancellation', 'adjustment', 'receipt') NOT NULL,
+  reference_id INT UNSIGNED NULL,
+  user VARCHAR(100) NOT NULL,
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY (product_id) REFERENCES products(id)
+);
+
+CREATE INDEX idx_stock_movements_product_id ON stock_movements(product_id, created_at);
+
+-- Opening balance so the ledger of every product adds up to its current stock
+INSERT INTO stock_movements (product_id, delta, resulting_stock, reason, user)
+SELECT id, stock, stock, 'adjustment', 'migration' FROM products WHERE stock > 0;
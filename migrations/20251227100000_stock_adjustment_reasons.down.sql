@@ -0,0 +1,3 @@
+-- Add down migration script here
+
+ALTER TABLE stock_movements DROP COLUMN adjustment_reason;
@@ -0,0 +1,5 @@
+-- Add up migration script here
+
+-- Cause of manual adjustments, NULL for the rest of movements
+ALTER TABLE stock_movements
+  ADD COLUMN adjustment_reason ENUM('damage', 'theft', 'recount', 'gift') NULL AFTER reason;
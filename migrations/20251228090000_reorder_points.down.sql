@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+ALTER TABLE products
+  DROP COLUMN reorder_quantity,
+  DROP COLUMN reorder_point;
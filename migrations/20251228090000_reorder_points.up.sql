@@ -0,0 +1,6 @@
+-- Add up migration script here
+
+-- Stock at or below which a product has to be reordered, and how many units to order
+ALTER TABLE products
+  ADD COLUMN reorder_point INT UNSIGNED NULL AFTER stock,
+  ADD COLUMN reorder_quantity INT UNSIGNED NULL AFTER reorder_point;
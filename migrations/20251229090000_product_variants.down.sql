@@ -0,0 +1,13 @@
+-- Add down migration script here
+
+UPDATE products SET parent_id = NULL WHERE parent_id IS NOT NULL;
+DELETE FROM products WHERE sku = 'TSHIRT' AND has_variants;
+
+ALTER TABLE products
+  DROP FOREIGN KEY fk_products_parent,
+  DROP INDEX uq_products_variant,
+  DROP COLUMN has_variants,
+  DROP COLUMN price_override,
+  DROP COLUMN color,
+  DROP COLUMN size,
+  DROP COLUMN parent_id;
@@ -0,0 +1,25 @@
+-- Add up migration script here
+
+-- Variants are products of their own, sold and stocked apart, grouped under a parent product
+ALTER TABLE products
+  ADD COLUMN parent_id INT UNSIGNED NULL AFTER reorder_quantity,
+  ADD COLUMN size VARCHAR(20) NULL AFTER parent_id,
+  ADD COLUMN color VARCHAR(30) NULL AFTER size,
+  ADD COLUMN price_override BOOLEAN NOT NULL DEFAULT FALSE AFTER color,
+  ADD COLUMN has_variants BOOLEAN NOT NULL DEFAULT FALSE AFTER price_override,
+  ADD CONSTRAINT fk_products_parent FOREIGN KEY (parent_id) REFERENCES products(id),
+  ADD CONSTRAINT uq_products_variant UNIQUE (parent_id, size, color);
+
+-- The seeded t-shirts become color variants of a single parent
+INSERT INTO products (sku, name, price, stock, has_variants)
+SELECT 'TSHIRT', 'Camiseta', 420000, 0, TRUE
+FROM DUAL
+WHERE EXISTS (SELECT 1 FROM products WHERE sku IN ('TSHIRT-XD', 'TSHIRT-XD-2'))
+  AND NOT EXISTS (SELECT 1 FROM products WHERE sku = 'TSHIRT');
+
+UPDATE products v
+JOIN products p ON p.sku = 'TSHIRT'
+SET v.parent_id = p.id,
+    v.color = CASE v.sku WHEN 'TSHIRT-XD' THEN 'Amarilla' ELSE 'Azul' END,
+    v.price_override = v.price <> p.price
+WHERE v.sku IN ('TSHIRT-XD', 'TSHIRT-XD-2');
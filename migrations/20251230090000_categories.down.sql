@@ -0,0 +1,14 @@
+-- Add down migration script here
+
+ALTER TABLE products ADD COLUMN flags VARCHAR(255) NULL AFTER has_variants;
+
+UPDATE products p
+SET p.flags = (
+  SELECT LEFT(GROUP_CONCAT(c.name ORDER BY c.name SEPARATOR ','), 255)
+  FROM product_categories pc
+  JOIN categories c ON c.id = pc.category_id
+  WHERE pc.product_id = p.id
+);
+
+DROP TABLE IF EXISTS product_categories;
+DROP TABLE IF EXISTS categories;
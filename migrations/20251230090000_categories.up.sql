@@ -0,0 +1,43 @@
+-- Add up migration script here
+
+-- Categories replace the comma separated flags of the products
+CREATE TABLE categories (
+  id INT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+  name VARCHAR(100) UNIQUE NOT NULL,
+  parent_id INT UNSIGNED NULL,
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+  updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  FOREIGN KEY (parent_id) REFERENCES categories(id)
+);
+
+CREATE TABLE product_categories (
+  product_id INT UNSIGNED NOT NULL,
+  category_id INT UNSIGNED NOT NULL,
+  PRIMARY KEY (product_id, category_id),
+  FOREIGN KEY (product_id) REFERENCES products(id),
+  FOREIGN KEY (category_id) REFERENCES categories(id)
+);
+
+CREATE INDEX idx_product_categories_category_id ON product_categories(category_id);
+
+-- Every flag in use becomes a top level category with the products that had it
+CREATE TEMPORARY TABLE product_flags AS
+SELECT p.id AS product_id, TRIM(f.flag) AS flag
+FROM products p,
+JSON_TABLE(
+  CONCAT('[', REPLACE(JSON_QUOTE(p.flags), ',', '","'), ']'),
+  '$[*]' COLUMNS (flag VARCHAR(255) PATH '$')
+) AS f
+WHERE p.flags IS NOT NULL AND TRIM(f.flag) <> '';
+
+INSERT INTO categories (name)
+SELECT DISTINCT LEFT(flag, 100) FROM product_flags;
+
+INSERT IGNORE INTO product_categories (product_id, category_id)
+SELECT pf.product_id, c.id
+FROM product_flags pf
+JOIN categories c ON c.name = LEFT(pf.flag, 100);
+
+DROP TEMPORARY TABLE product_flags;
+
+ALTER TABLE products DROP COLUMN flags;
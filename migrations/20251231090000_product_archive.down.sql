@@ -0,0 +1,6 @@
+-- Add down migration script here
+
+DROP INDEX idx_products_archived_at ON products;
+
+ALTER TABLE products
+  DROP COLUMN archived_at;
@@ -0,0 +1,7 @@
+-- Add up migration script here
+
+-- Archived products are hidden from listings and new sales, their sales keep referencing them
+ALTER TABLE products
+  ADD COLUMN archived_at TIMESTAMP NULL AFTER description;
+
+CREATE INDEX idx_products_archived_at ON products(archived_at);
@@ -0,0 +1,10 @@
+-- Add down migration script here
+
+ALTER TABLE promotions ADD COLUMN flag VARCHAR(50) NULL AFTER pay_quantity;
+
+UPDATE promotions p
+JOIN categories c ON c.id = p.category_id
+SET p.flag = LEFT(c.name, 50);
+
+ALTER TABLE promotions DROP FOREIGN KEY fk_promotions_category_id;
+ALTER TABLE promotions DROP COLUMN category_id;
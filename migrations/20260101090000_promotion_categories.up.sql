@@ -0,0 +1,18 @@
+-- Add up migration script here
+
+-- Promotions target a category by id so renaming it keeps them attached
+ALTER TABLE promotions ADD COLUMN category_id INT UNSIGNED NULL AFTER pay_quantity;
+
+-- Flags no product used yet become top level categories, so no promotion loses its target
+INSERT INTO categories (name)
+SELECT DISTINCT p.flag FROM promotions p
+WHERE p.flag IS NOT NULL AND NOT EXISTS (SELECT 1 FROM categories c WHERE c.name = p.flag);
+
+UPDATE promotions p
+JOIN categories c ON c.name = p.flag
+SET p.category_id = c.id;
+
+ALTER TABLE promotions
+  ADD CONSTRAINT fk_promotions_category_id FOREIGN KEY (category_id) REFERENCES categories(id);
+
+ALTER TABLE promotions DROP COLUMN flag;
@@ -0,0 +1,27 @@
+use crate::domain::repositories::InvoiceSeriesRepository;
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use std::sync::Arc;
+
+/// Make the series the one new sales take their invoice numbers from
+pub struct ActivateInvoiceSeriesCommand {
+    pub invoice_series_id: u32,
+}
+impl Input for ActivateInvoiceSeriesCommand {
+    type Output = ();
+}
+
+pub struct ActivateInvoiceSeriesCommandHandler {
+    pub invoice_series_repository: Arc<dyn InvoiceSeriesRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<ActivateInvoiceSeriesCommand> for ActivateInvoiceSeriesCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<ActivateInvoiceSeriesCommand>,
+    ) -> Result<<ActivateInvoiceSeriesCommand as Input>::Output, SharedError> {
+        self.invoice_series_repository
+            .activate(input.invoice_series_id)
+            .await
+    }
+}
@@ -0,0 +1,73 @@
+use crate::domain::repositories::{DraftSaleRepository, ProductRepository};
+use crate::domain::unit_of_work::{UnitOfWork, run_in_transaction};
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use std::sync::Arc;
+
+pub struct AddDraftItemCommand {
+    pub draft_sale_id: u32,
+    pub sku: String,
+    pub quantity: u32,
+}
+impl Input for AddDraftItemCommand {
+    type Output = ();
+}
+
+pub struct AddDraftItemCommandHandler {
+    pub product_repository: Arc<dyn ProductRepository + Send + Sync>,
+    pub draft_sale_repository: Arc<dyn DraftSaleRepository + Send + Sync>,
+    pub unit_of_work: Arc<dyn UnitOfWork + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<AddDraftItemCommand> for AddDraftItemCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<AddDraftItemCommand>,
+    ) -> Result<<AddDraftItemCommand as Input>::Output, SharedError> {
+        // Stock is only checked on checkout, here the product just has to exist and be active
+        match self.product_repository.get_by_sku(&input.sku).await {
+            Some(product) if product.is_archived() => {
+                return Err(SharedError::new(
+                    format!("Product with SKU {} is archived", input.sku).as_str(),
+                    400,
+                ));
+            }
+            Some(_) => {}
+            None => {
+                return Err(SharedError::new(
+                    format!("Product with SKU {} not found", input.sku).as_str(),
+                    404,
+                ));
+            }
+        }
+        run_in_transaction(
+            self.unit_of_work.as_ref(),
+            &(self, input.as_ref()),
+            |tx, (handler, input)| {
+                Box::pin(async move {
+                    handler
+                        .draft_sale_repository
+                        .lock(tx, input.draft_sale_id)
+                        .await?;
+                    let mut draft = match handler
+                        .draft_sale_repository
+                        .get_by_id(input.draft_sale_id)
+                        .await
+                    {
+                        Some(draft) => draft,
+                        None => {
+                            return Err(SharedError::new(
+                                format!("Draft sale with ID {} not found", input.draft_sale_id)
+                                    .as_str(),
+                                404,
+                            ));
+                        }
+                    };
+                    draft.add_item(&input.sku, input.quantity)?;
+                    handler.draft_sale_repository.save(tx, &draft).await
+                })
+            },
+        )
+        .await
+    }
+}
@@ -0,0 +1,106 @@
+use super::log_low_stock;
+use crate::application::services::Logger;
+use crate::domain::entities::{StockAdjustmentReason, StockChange};
+use crate::domain::repositories::ProductRepository;
+use crate::domain::unit_of_work::{UnitOfWork, run_in_transaction};
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use std::sync::Arc;
+
+/// Manual correction of the stock of a product, the only way to change it outside sales,
+/// returns and receipts
+pub struct AdjustStockCommand {
+    pub sku: String,
+    /// Units added, negative to take them out
+    pub delta: i64,
+    /// "damage", "theft", "recount" or "gift"
+    pub reason: String,
+    /// User making the adjustment, recorded in the stock movements
+    pub user: String,
+}
+pub struct AdjustStockOutput {
+    pub product_id: u32,
+    pub stock: u32,
+}
+impl Input for AdjustStockCommand {
+    type Output = AdjustStockOutput;
+}
+
+pub struct AdjustStockCommandHandler {
+    pub product_repository: Arc<dyn ProductRepository + Send + Sync>,
+    pub logger: Arc<dyn Logger + Send + Sync>,
+    pub unit_of_work: Arc<dyn UnitOfWork + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<AdjustStockCommand> for AdjustStockCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<AdjustStockCommand>,
+    ) -> Result<<AdjustStockCommand as Input>::Output, SharedError> {
+        let reason = StockAdjustmentReason::parse(&input.reason)?;
+        let quantity = match u32::try_from(input.delta.unsigned_abs()) {
+            Ok(quantity) if quantity > 0 => quantity,
+            Ok(_) => return Err(SharedError::new("Stock adjustment cannot be 0", 400)),
+            Err(_) => return Err(SharedError::new("Stock adjustment is too large", 400)),
+        };
+        let product = match self.product_repository.get_by_sku(&input.sku).await {
+            Some(product) => product,
+            None => {
+                return Err(SharedError::new(
+                    &format!("Product with SKU {} not found", input.sku),
+                    404,
+                ));
+            }
+        };
+        if product.has_variants() {
+            return Err(SharedError::new(
+                &format!(
+                    "Product with SKU {} has variants, adjust the stock of one of them",
+                    product.sku()
+                ),
+                400,
+            ));
+        }
+        if input.delta < 0 && quantity > product.stock() {
+            return Err(SharedError::new(
+                &format!(
+                    "Adjustment of {} would leave the stock of {} negative, it has {}",
+                    input.delta,
+                    product.sku(),
+                    product.stock()
+                ),
+                400,
+            ));
+        }
+        let change = StockChange::adjustment(reason, product.id(), &input.user);
+        // The stock is checked again by the update, it may have changed since it was read
+        let stock = run_in_transaction(
+            self.unit_of_work.as_ref(),
+            &(self, &product, &change, input.delta),
+            |tx, (handler, product, change, delta)| {
+                Box::pin(async move {
+                    if *delta < 0 {
+                        handler
+                            .product_repository
+                            .decrement_stock(tx, product, quantity, change)
+                            .await
+                    } else {
+                        handler
+                            .product_repository
+                            .increment_stock(tx, product, quantity, change)
+                            .await
+                    }
+                })
+            },
+        )
+        .await?;
+        if input.delta < 0 {
+            let alert = product.low_stock_alert(stock + quantity, stock, change.reason);
+            log_low_stock(self.logger.as_ref(), alert.as_slice());
+        }
+        Ok(AdjustStockOutput {
+            product_id: product.id(),
+            stock,
+        })
+    }
+}
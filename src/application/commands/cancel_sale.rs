@@ -0,0 +1,63 @@
+use crate::domain::entities::{StockChange, StockMovementReason};
+use crate::domain::repositories::{ProductRepository, SaleRepository};
+use crate::domain::unit_of_work::{UnitOfWork, run_in_transaction};
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use std::sync::Arc;
+
+pub struct CancelSaleCommand {
+    pub sale_id: u32,
+    pub reason: String,
+    /// User cancelling the sale, recorded in the stock movements
+    pub user: String,
+}
+impl Input for CancelSaleCommand {
+    type Output = ();
+}
+
+pub struct CancelSaleCommandHandler {
+    pub product_repository: Arc<dyn ProductRepository + Send + Sync>,
+    pub sale_repository: Arc<dyn SaleRepository + Send + Sync>,
+    pub unit_of_work: Arc<dyn UnitOfWork + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<CancelSaleCommand> for CancelSaleCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<CancelSaleCommand>,
+    ) -> Result<<CancelSaleCommand as Input>::Output, SharedError> {
+        if input.reason.trim().is_empty() {
+            return Err(SharedError::new("A reason is required to cancel a sale", 400));
+        }
+        // The cancellation and the stock restoration are committed or rolled back together
+        run_in_transaction(
+            self.unit_of_work.as_ref(),
+            &(self, input.as_ref()),
+            |tx, (handler, input)| {
+                Box::pin(async move {
+                    // Sale is read once locked so concurrent returns are taken into account
+                    let mut sale = handler
+                        .sale_repository
+                        .get_by_id_for_update(tx, &input.sale_id)
+                        .await?;
+                    sale.cancel(input.reason.trim().to_string())?;
+                    handler.sale_repository.save_cancellation(tx, &sale).await?;
+                    // Units already returned went back to stock with their return
+                    let change = StockChange::new(
+                        StockMovementReason::Cancellation,
+                        Some(sale.id()),
+                        &input.user,
+                    );
+                    for (product, quantity) in sale.kept_products() {
+                        handler
+                            .product_repository
+                            .increment_stock(tx, product, quantity, &change)
+                            .await?;
+                    }
+                    Ok(())
+                })
+            },
+        )
+        .await
+    }
+}
@@ -0,0 +1,112 @@
+use super::register_sale::{PaymentInput, RegisterSaleOutput, SaleRegistration};
+use super::send_sale_receipt::ReceiptEmailer;
+use crate::application::services::Logger;
+use crate::domain::repositories::{
+    DraftSaleRepository, InvoiceSeriesRepository, ProductRepository, PromotionRepository,
+    SaleRepository,
+};
+use crate::domain::unit_of_work::{UnitOfWork, run_in_transaction};
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use std::sync::Arc;
+
+/// Turn an open draft into a sale, following the same rules as registering it directly
+pub struct CheckoutDraftSaleCommand {
+    pub draft_sale_id: u32,
+    pub coupon_codes: Vec<String>,
+    pub payments: Vec<PaymentInput>,
+    /// User checking the draft out, recorded in the stock movements
+    pub user: String,
+}
+impl Input for CheckoutDraftSaleCommand {
+    type Output = RegisterSaleOutput;
+}
+
+pub struct CheckoutDraftSaleCommandHandler {
+    pub product_repository: Arc<dyn ProductRepository + Send + Sync>,
+    pub sale_repository: Arc<dyn SaleRepository + Send + Sync>,
+    pub promotion_repository: Arc<dyn PromotionRepository + Send + Sync>,
+    pub invoice_series_repository: Arc<dyn InvoiceSeriesRepository + Send + Sync>,
+    pub draft_sale_repository: Arc<dyn DraftSaleRepository + Send + Sync>,
+    pub receipt_emailer: Arc<ReceiptEmailer>,
+    pub logger: Arc<dyn Logger + Send + Sync>,
+    pub unit_of_work: Arc<dyn UnitOfWork + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<CheckoutDraftSaleCommand> for CheckoutDraftSaleCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<CheckoutDraftSaleCommand>,
+    ) -> Result<<CheckoutDraftSaleCommand as Input>::Output, SharedError> {
+        let draft = match self
+            .draft_sale_repository
+            .get_by_id(input.draft_sale_id)
+            .await
+        {
+            Some(draft) => draft,
+            None => {
+                return Err(SharedError::new(
+                    format!("Draft sale with ID {} not found", input.draft_sale_id).as_str(),
+                    404,
+                ));
+            }
+        };
+        draft.check_can_check_out()?;
+        let registration = SaleRegistration::prepare(
+            self.product_repository.as_ref(),
+            self.promotion_repository.as_ref(),
+            draft.customer().clone(),
+            &draft.skus_quantity(),
+            &input.coupon_codes,
+            &input.payments,
+        )
+        .await?;
+        // The sale is registered and the draft closed together
+        let (output, alerts) = run_in_transaction(
+            self.unit_of_work.as_ref(),
+            &(self, &registration, &draft, input.user.as_str()),
+            |tx, (handler, registration, draft, user)| {
+                Box::pin(async move {
+                    // The draft is read again once locked, it may have changed since prepared
+                    handler.draft_sale_repository.lock(tx, draft.id()).await?;
+                    let mut current =
+                        match handler.draft_sale_repository.get_by_id(draft.id()).await {
+                            Some(current) => current,
+                            None => {
+                                return Err(SharedError::new(
+                                    format!("Draft sale with ID {} not found", draft.id()).as_str(),
+                                    404,
+                                ));
+                            }
+                        };
+                    if current.skus_quantity() != draft.skus_quantity() {
+                        return Err(SharedError::new(
+                            format!(
+                                "Draft sale with ID {} changed during checkout, try again",
+                                draft.id()
+                            )
+                            .as_str(),
+                            409,
+                        ));
+                    }
+                    let (sale, alerts) = registration
+                        .save(
+                            tx,
+                            handler.product_repository.as_ref(),
+                            handler.sale_repository.as_ref(),
+                            handler.invoice_series_repository.as_ref(),
+                            user,
+                        )
+                        .await?;
+                    current.check_out(sale.id())?;
+                    handler.draft_sale_repository.save(tx, &current).await?;
+                    Ok((RegisterSaleOutput::try_from(&sale)?, alerts))
+                })
+            },
+        )
+        .await?;
+        alerts.log(self.logger.as_ref());
+        self.receipt_emailer.send_in_background(output.sale_id);
+        Ok(output)
+    }
+}
@@ -0,0 +1,25 @@
+use crate::domain::repositories::CategoryRepository;
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use std::sync::Arc;
+
+/// Delete a category without subcategories nor products assigned
+pub struct DeleteCategoryCommand {
+    pub category_id: u32,
+}
+impl Input for DeleteCategoryCommand {
+    type Output = ();
+}
+
+pub struct DeleteCategoryCommandHandler {
+    pub category_repository: Arc<dyn CategoryRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<DeleteCategoryCommand> for DeleteCategoryCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<DeleteCategoryCommand>,
+    ) -> Result<<DeleteCategoryCommand as Input>::Output, SharedError> {
+        self.category_repository.delete(input.category_id).await
+    }
+}
@@ -0,0 +1,56 @@
+use crate::domain::entities::Category;
+use crate::domain::repositories::CategoryRepository;
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use std::sync::Arc;
+
+/// Rename a category and move it under another parent, or to the top level without one
+pub struct EditCategoryCommand {
+    pub category_id: u32,
+    pub name: String,
+    pub parent_id: Option<u32>,
+}
+impl Input for EditCategoryCommand {
+    type Output = ();
+}
+
+pub struct EditCategoryCommandHandler {
+    pub category_repository: Arc<dyn CategoryRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<EditCategoryCommand> for EditCategoryCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<EditCategoryCommand>,
+    ) -> Result<<EditCategoryCommand as Input>::Output, SharedError> {
+        let name = Category::validate_name(&input.name)?;
+        let categories = self.category_repository.get_all().await;
+        let mut category = match categories.iter().find(|c| c.id() == input.category_id) {
+            Some(category) => category.clone(),
+            None => {
+                return Err(SharedError::new(
+                    format!("Category with ID {} not found", input.category_id).as_str(),
+                    404,
+                ));
+            }
+        };
+        if let Some(parent_id) = input.parent_id {
+            if !categories.iter().any(|c| c.id() == parent_id) {
+                return Err(SharedError::new(
+                    format!("Category with ID {} not found", parent_id).as_str(),
+                    404,
+                ));
+            }
+            // Moving a category under itself or one of its subcategories would make a cycle
+            if Category::subtree_ids(&categories, category.id()).contains(&parent_id) {
+                return Err(SharedError::new(
+                    "A category cannot be moved under itself or one of its subcategories",
+                    400,
+                ));
+            }
+        }
+        category.set_name(name);
+        category.set_parent_id(input.parent_id);
+        self.category_repository.save(&category).await
+    }
+}
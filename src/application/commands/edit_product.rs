@@ -1,5 +1,7 @@
-use crate::domain::repositories::{ProductRepository};
-use crate::domain::value_objects::Url;
+use crate::domain::entities::TaxClass;
+use super::find_categories;
+use crate::domain::repositories::{CategoryRepository, ProductRepository};
+use crate::domain::value_objects::{Money, Url};
 use crate::shared::SharedError;
 use crate::shared::input_handler::{Input, InputHandler};
 use std::sync::Arc;
@@ -9,16 +11,19 @@ pub struct EditProductCommand {
     pub product_id: u32,
     pub name: Option<String>,
     pub description: Option<String>,
-    pub stock: Option<u32>,
-    pub price: Option<f64>,
+    pub price: Option<Money>,
+    pub tax_class: Option<String>,
+    pub reorder_point: Option<u32>,
+    pub reorder_quantity: Option<u32>,
     pub img_url: Option<String>,
-    pub flags: Option<Vec<String>>,
+    pub category_ids: Option<Vec<u32>>,
 }
 impl Input for EditProductCommand {
     type Output = ();
 }
 pub struct EditProductCommandHandler {
     pub product_repository: Arc<dyn ProductRepository + Send + Sync>,
+    pub category_repository: Arc<dyn CategoryRepository + Send + Sync>,
 }
 #[async_trait::async_trait]
 impl InputHandler<EditProductCommand> for EditProductCommandHandler {
@@ -42,17 +47,40 @@ impl InputHandler<EditProductCommand> for EditProductCommandHandler {
         if let Some(name) = &input.name {
             product.set_name(name.clone());
         }
-        if let Some(stock) = input.stock {
-            product.set_stock(stock);
-        }
         if let Some(description) = &input.description {
             product.set_description(Some(description.clone()));
         }
         if let Some(price) = input.price {
+            if price.is_negative() {
+                return Err(SharedError::new("Product price cannot be negative", 400));
+            }
             product.set_price(price);
         }
-        if let Some(flags) = &input.flags {
-            product.set_flags(flags.iter().map(|s| s.to_string()).collect());
+        if let Some(tax_class) = &input.tax_class {
+            if product.variant().is_some() {
+                return Err(SharedError::new(
+                    "Variants take the tax class of their parent product, edit it instead",
+                    400,
+                ));
+            }
+            product.set_tax_class(TaxClass::parse(tax_class)?);
+        }
+        if let Some(reorder_point) = input.reorder_point {
+            product.set_reorder_point(reorder_point);
+        }
+        if let Some(reorder_quantity) = input.reorder_quantity {
+            product.set_reorder_quantity(reorder_quantity)?;
+        }
+        if let Some(category_ids) = &input.category_ids {
+            if product.variant().is_some() {
+                return Err(SharedError::new(
+                    "Variants take the categories of their parent product, edit it instead",
+                    400,
+                ));
+            }
+            product.set_categories(
+                find_categories(self.category_repository.as_ref(), category_ids).await?,
+            );
         }
         self.product_repository.save(&product).await
     }
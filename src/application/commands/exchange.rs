@@ -0,0 +1,149 @@
+use super::register_sale::{PaymentInput, SaleRegistration, build_payments};
+use crate::application::services::Logger;
+use crate::domain::entities::{Payment, PaymentMethod, StockChange, StockMovementReason};
+use crate::domain::repositories::{
+    InvoiceSeriesRepository, ProductRepository, PromotionRepository, SaleRepository,
+};
+use crate::domain::unit_of_work::{UnitOfWork, run_in_transaction};
+use crate::domain::value_objects::Money;
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use std::sync::Arc;
+
+pub struct ExchangeCommand {
+    pub sale_id: u32,
+    /// (SKU, quantity) returned from the sale
+    pub returned_skus_quantity: Vec<(String, u32)>,
+    /// (SKU, quantity) given to the customer in their place
+    pub replacement_skus_quantity: Vec<(String, u32)>,
+    pub reason: Option<String>,
+    /// Payments of what the replacement costs over the refunded amount
+    pub payments: Vec<PaymentInput>,
+    /// User making the exchange, recorded in the stock movements
+    pub user: String,
+}
+pub struct ExchangeOutput {
+    pub exchange_id: u32,
+    pub original_sale_id: u32,
+    pub return_id: u32,
+    pub replacement_sale_id: u32,
+    pub refunded_amount: Money,
+    pub replacement_amount: Money,
+    /// Positive when the customer owes it, negative when it is owed to the customer
+    pub price_difference: Money,
+    pub created_at: String,
+}
+impl Input for ExchangeCommand {
+    type Output = ExchangeOutput;
+}
+
+pub struct ExchangeCommandHandler {
+    pub product_repository: Arc<dyn ProductRepository + Send + Sync>,
+    pub sale_repository: Arc<dyn SaleRepository + Send + Sync>,
+    pub promotion_repository: Arc<dyn PromotionRepository + Send + Sync>,
+    pub invoice_series_repository: Arc<dyn InvoiceSeriesRepository + Send + Sync>,
+    pub logger: Arc<dyn Logger + Send + Sync>,
+    pub unit_of_work: Arc<dyn UnitOfWork + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<ExchangeCommand> for ExchangeCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<ExchangeCommand>,
+    ) -> Result<<ExchangeCommand as Input>::Output, SharedError> {
+        let sale = match self.sale_repository.get_by_id(&input.sale_id).await {
+            Some(sale) => sale,
+            None => {
+                return Err(SharedError::new(
+                    format!("Sale with ID {} not found", input.sale_id).as_str(),
+                    404,
+                ));
+            }
+        };
+        // Replacement sales get the automatic promotions like any other sale
+        let replacement = SaleRegistration::prepare_unpaid(
+            self.product_repository.as_ref(),
+            self.promotion_repository.as_ref(),
+            sale.customer().clone(),
+            &input.replacement_skus_quantity,
+            &[],
+        )
+        .await?;
+        let extra_payments = build_payments(&input.payments)?;
+        // Return, replacement sale and stock changes are committed or rolled back together
+        let (output, alerts) = run_in_transaction(
+            self.unit_of_work.as_ref(),
+            &(self, input.as_ref(), &replacement, &extra_payments),
+            |tx, (handler, input, replacement, extra_payments)| {
+                Box::pin(async move {
+                    // Sale is read once locked so concurrent returns see each other
+                    let sale = handler
+                        .sale_repository
+                        .get_by_id_for_update(tx, &input.sale_id)
+                        .await?;
+                    let return_lines = sale.return_lines(&input.returned_skus_quantity)?;
+                    let reason = input.reason.as_deref().unwrap_or("Exchange");
+                    let sale_return = handler
+                        .sale_repository
+                        .create_return(tx, &sale, return_lines.clone(), Some(reason))
+                        .await?;
+                    let change = StockChange::new(
+                        StockMovementReason::Return,
+                        Some(sale_return.id()),
+                        &input.user,
+                    );
+                    for line in return_lines.iter() {
+                        if let Some(sale_line) = sale
+                            .lines()
+                            .iter()
+                            .find(|l| l.product().sku() == line.product_sku())
+                        {
+                            handler
+                                .product_repository
+                                .increment_stock(tx, sale_line.product(), line.quantity(), &change)
+                                .await?;
+                        }
+                    }
+                    // The refund pays for the replacement as store credit, the rest is paid apart
+                    let mut replacement = (*replacement).clone();
+                    let credit = sale_return
+                        .refunded_amount()?
+                        .min(replacement.total_to_pay()?);
+                    let mut payments = Vec::new();
+                    if credit.is_positive() {
+                        payments.push(Payment::new(PaymentMethod::StoreCredit, credit, None)?);
+                    }
+                    payments.extend(extra_payments.iter().cloned());
+                    replacement.pay(payments)?;
+                    let (replacement_sale, alerts) = replacement
+                        .save(
+                            tx,
+                            handler.product_repository.as_ref(),
+                            handler.sale_repository.as_ref(),
+                            handler.invoice_series_repository.as_ref(),
+                            &input.user,
+                        )
+                        .await?;
+                    let exchange = handler
+                        .sale_repository
+                        .create_exchange(tx, &sale, &sale_return, &replacement_sale)
+                        .await?;
+                    let output = ExchangeOutput {
+                        exchange_id: exchange.id(),
+                        original_sale_id: exchange.original_sale_id(),
+                        return_id: exchange.return_id(),
+                        replacement_sale_id: exchange.replacement_sale_id(),
+                        refunded_amount: sale_return.refunded_amount()?,
+                        replacement_amount: replacement_sale.total_amount()?,
+                        price_difference: exchange.price_difference(),
+                        created_at: exchange.created_at().to_rfc3339(),
+                    };
+                    Ok((output, alerts))
+                })
+            },
+        )
+        .await?;
+        alerts.log(self.logger.as_ref());
+        Ok(output)
+    }
+}
@@ -0,0 +1,79 @@
+use crate::application::dtos::ElectronicInvoiceDTO;
+use crate::application::services::{ElectronicInvoiceBuilder, ElectronicInvoiceSubmitter};
+use crate::domain::entities::ElectronicInvoice;
+use crate::domain::repositories::{ElectronicInvoiceRepository, SaleRepository};
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use chrono::Utc;
+use std::sync::Arc;
+
+/// Generate the electronic invoice of a sale and submit it, retrying the submission of a
+/// document generated before
+pub struct GenerateElectronicInvoiceCommand {
+    pub sale_id: u32,
+}
+impl Input for GenerateElectronicInvoiceCommand {
+    type Output = ElectronicInvoiceDTO;
+}
+
+pub struct GenerateElectronicInvoiceCommandHandler {
+    pub sale_repository: Arc<dyn SaleRepository + Send + Sync>,
+    pub electronic_invoice_repository: Arc<dyn ElectronicInvoiceRepository + Send + Sync>,
+    pub electronic_invoice_builder: Arc<dyn ElectronicInvoiceBuilder + Send + Sync>,
+    pub electronic_invoice_submitter: Arc<dyn ElectronicInvoiceSubmitter + Send + Sync>,
+}
+impl GenerateElectronicInvoiceCommandHandler {
+    /// Build and store the document of the sale, keeping the one stored by a concurrent request
+    async fn generate(&self, sale_id: u32) -> Result<ElectronicInvoice, SharedError> {
+        let sale = match self.sale_repository.get_by_id(&sale_id).await {
+            Some(sale) => sale,
+            None => {
+                return Err(SharedError::new(
+                    &format!("Sale with ID {} not found", sale_id),
+                    404,
+                ));
+            }
+        };
+        if sale.is_cancelled() {
+            return Err(SharedError::new(
+                &format!("Sale with ID {} is cancelled", sale_id),
+                409,
+            ));
+        }
+        let invoice = self.electronic_invoice_builder.build(&sale)?;
+        match self.electronic_invoice_repository.create(&invoice).await {
+            Ok(_) => Ok(invoice),
+            Err(e) if e.code == 409 => self
+                .electronic_invoice_repository
+                .get_by_sale_id(sale_id)
+                .await
+                .ok_or(e),
+            Err(e) => Err(e),
+        }
+    }
+}
+#[async_trait::async_trait]
+impl InputHandler<GenerateElectronicInvoiceCommand> for GenerateElectronicInvoiceCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<GenerateElectronicInvoiceCommand>,
+    ) -> Result<<GenerateElectronicInvoiceCommand as Input>::Output, SharedError> {
+        // A stored document is never rebuilt, its CUFE may already be known to the DIAN
+        let mut invoice = match self
+            .electronic_invoice_repository
+            .get_by_sale_id(input.sale_id)
+            .await
+        {
+            Some(invoice) => invoice,
+            None => self.generate(input.sale_id).await?,
+        };
+        if !invoice.is_submitted() {
+            self.electronic_invoice_submitter.submit(&invoice).await?;
+            invoice.mark_submitted(Utc::now());
+            self.electronic_invoice_repository
+                .save_status(&invoice)
+                .await?;
+        }
+        Ok(ElectronicInvoiceDTO::from(invoice))
+    }
+}
@@ -0,0 +1,218 @@
+use crate::application::dtos::ProductDTO;
+use crate::domain::entities::{StockChange, StockMovementReason};
+use crate::domain::repositories::ProductRepository;
+use crate::domain::unit_of_work::{UnitOfWork, run_in_transaction};
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use std::sync::Arc;
+
+/// (size, color) of a variant, at least one of them set
+type VariantAttributes = (Option<String>, Option<String>);
+
+/// Create the variants of a product for every size and color combination, skipping the
+/// combinations it already has
+pub struct GenerateProductVariantsCommand {
+    pub sku: String,
+    pub sizes: Vec<String>,
+    pub colors: Vec<String>,
+    /// Units each new variant starts with, loaded as a receipt
+    pub stock: Option<u32>,
+    /// User generating the variants, recorded in the stock movements
+    pub user: String,
+}
+impl Input for GenerateProductVariantsCommand {
+    type Output = Vec<ProductDTO>;
+}
+
+pub struct GenerateProductVariantsCommandHandler {
+    pub product_repository: Arc<dyn ProductRepository + Send + Sync>,
+    pub unit_of_work: Arc<dyn UnitOfWork + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<GenerateProductVariantsCommand> for GenerateProductVariantsCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<GenerateProductVariantsCommand>,
+    ) -> Result<<GenerateProductVariantsCommand as Input>::Output, SharedError> {
+        let parent = match self.product_repository.get_by_sku(&input.sku).await {
+            Some(product) => product,
+            None => {
+                return Err(SharedError::new(
+                    &format!("Product with SKU {} not found", input.sku),
+                    404,
+                ));
+            }
+        };
+        if parent.variant().is_some() {
+            return Err(SharedError::new(
+                &format!("Product with SKU {} is already a variant", parent.sku()),
+                400,
+            ));
+        }
+        // Stock belongs to the variants once the product has them
+        if parent.stock() > 0 {
+            return Err(SharedError::new(
+                &format!(
+                    "Product with SKU {} has {} units in stock, adjust them to 0 before adding variants",
+                    parent.sku(),
+                    parent.stock()
+                ),
+                409,
+            ));
+        }
+        let existing = self.product_repository.get_variants(parent.id()).await;
+        let variants: Vec<VariantAttributes> = attribute_matrix(&input.sizes, &input.colors)?
+            .into_iter()
+            .filter(|(size, color)| {
+                !existing.iter().any(|product| {
+                    product.variant().as_ref().is_some_and(|variant| {
+                        same_value(variant.size(), size) && same_value(variant.color(), color)
+                    })
+                })
+            })
+            .collect();
+        let mut new_variants = Vec::new();
+        for (size, color) in variants {
+            let attributes: Vec<&String> = [&size, &color].into_iter().flatten().collect();
+            let sku = format!(
+                "{}-{}",
+                parent.sku(),
+                attributes
+                    .iter()
+                    .map(|value| sku_segment(value))
+                    .collect::<Vec<_>>()
+                    .join("-")
+            );
+            let name = format!(
+                "{} {}",
+                parent.name(),
+                attributes
+                    .iter()
+                    .map(|value| value.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            );
+            if sku.len() > 50 || name.chars().count() > 100 {
+                return Err(SharedError::new(
+                    &format!("Variant SKU {} or its name is too long", sku),
+                    400,
+                ));
+            }
+            new_variants.push((sku, name, size, color));
+        }
+        let created_ids = run_in_transaction(
+            self.unit_of_work.as_ref(),
+            &(self, &parent, &new_variants, input.as_ref()),
+            |tx, (handler, parent, new_variants, input)| {
+                Box::pin(async move {
+                    let mut created_ids = Vec::new();
+                    for (sku, name, size, color) in new_variants.iter() {
+                        let variant = handler
+                            .product_repository
+                            .create_variant(tx, parent, sku, name, size.as_ref(), color.as_ref())
+                            .await?;
+                        let stock = input.stock.unwrap_or(0);
+                        if stock > 0 {
+                            let change = StockChange::new(
+                                StockMovementReason::Receipt,
+                                Some(variant.id()),
+                                &input.user,
+                            );
+                            handler
+                                .product_repository
+                                .increment_stock(tx, &variant, stock, &change)
+                                .await?;
+                        }
+                        created_ids.push(variant.id());
+                    }
+                    Ok(created_ids)
+                })
+            },
+        )
+        .await?;
+        // Read once committed so the variants show the stock loaded into them
+        Ok(self
+            .product_repository
+            .get_variants(parent.id())
+            .await
+            .into_iter()
+            .filter(|variant| created_ids.contains(&variant.id()))
+            .map(ProductDTO::from)
+            .collect())
+    }
+}
+
+/// Every (size, color) combination of the given values, trimmed and without repeats. One of
+/// the lists may be empty to vary the products by a single attribute.
+fn attribute_matrix(
+    sizes: &[String],
+    colors: &[String],
+) -> Result<Vec<VariantAttributes>, SharedError> {
+    let sizes = attribute_values(sizes, "size", 20)?;
+    let colors = attribute_values(colors, "color", 30)?;
+    if sizes.is_empty() && colors.is_empty() {
+        return Err(SharedError::new(
+            "At least one size or color is required to generate variants",
+            400,
+        ));
+    }
+    let sizes: Vec<Option<String>> = match sizes.is_empty() {
+        true => vec![None],
+        false => sizes.into_iter().map(Some).collect(),
+    };
+    let colors: Vec<Option<String>> = match colors.is_empty() {
+        true => vec![None],
+        false => colors.into_iter().map(Some).collect(),
+    };
+    Ok(sizes
+        .iter()
+        .flat_map(|size| {
+            colors
+                .iter()
+                .map(move |color| (size.clone(), color.clone()))
+        })
+        .collect())
+}
+
+fn attribute_values(
+    values: &[String],
+    attribute: &str,
+    max_len: usize,
+) -> Result<Vec<String>, SharedError> {
+    let mut unique: Vec<String> = Vec::new();
+    for value in values {
+        let value = value.trim();
+        if value.is_empty() || value.chars().count() > max_len {
+            return Err(SharedError::new(
+                &format!(
+                    "Variant {} must have between 1 and {} characters",
+                    attribute, max_len
+                ),
+                400,
+            ));
+        }
+        if !unique.iter().any(|u| u.eq_ignore_ascii_case(value)) {
+            unique.push(value.to_string());
+        }
+    }
+    Ok(unique)
+}
+
+/// Attribute values compared as the database does, ignoring case
+fn same_value(a: &Option<String>, b: &Option<String>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Attribute value as a SKU segment, "Azul Claro" becomes "AZUL-CLARO"
+fn sku_segment(value: &str) -> String {
+    value
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_uppercase()
+}
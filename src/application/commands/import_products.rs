@@ -0,0 +1,541 @@
+use crate::domain::entities::{Category, Product, StockChange, StockMovementReason, TaxClass};
+use crate::domain::repositories::{CategoryRepository, ProductRepository};
+use crate::domain::unit_of_work::{UnitOfWork, run_in_transaction};
+use crate::domain::value_objects::{Money, Url};
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use std::sync::Arc;
+
+const MAX_ROWS: usize = 1000;
+const REQUIRED_COLUMNS: [&str; 3] = ["sku", "name", "price"];
+/// "flags" is still read as the categories of files exported before categories existed
+const OPTIONAL_COLUMNS: [&str; 6] = [
+    "stock",
+    "categories",
+    "flags",
+    "img_url",
+    "description",
+    "tax_class",
+];
+
+/// Register or update many products at once from a CSV with a header row. Every row is
+/// validated and written on its own, the rows with errors are reported and left out.
+pub struct ImportProductsCommand {
+    pub csv: String,
+    /// "create" to only register new products, failing the rows of existing SKUs, or "upsert"
+    /// to update those. Create when not given.
+    pub mode: Option<String>,
+    /// Validate every row and report what would be done without writing anything
+    pub dry_run: bool,
+    /// User importing the products, recorded in the stock movements
+    pub user: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImportMode {
+    Create,
+    Upsert,
+}
+impl ImportMode {
+    fn parse(value: &str) -> Result<Self, SharedError> {
+        match value {
+            "create" => Ok(ImportMode::Create),
+            "upsert" => Ok(ImportMode::Upsert),
+            _ => Err(SharedError::new(
+                &format!("Invalid import mode {}", value),
+                400,
+            )),
+        }
+    }
+}
+
+/// Outcome of a row, what would have happened on a dry run
+#[derive(Debug, Clone)]
+pub struct ImportRowResult {
+    /// Row of the file, the header being row 1
+    pub row: u32,
+    pub sku: String,
+    /// "created", "updated" or "failed"
+    pub status: String,
+    pub errors: Vec<String>,
+}
+pub struct ImportProductsOutput {
+    pub dry_run: bool,
+    pub created: u32,
+    pub updated: u32,
+    pub failed: u32,
+    pub rows: Vec<ImportRowResult>,
+}
+impl Input for ImportProductsCommand {
+    type Output = ImportProductsOutput;
+}
+
+/// Values of a row, None for the empty cells which leave an existing product untouched
+struct ImportRow {
+    sku: String,
+    name: Option<String>,
+    price: Option<Money>,
+    tax_class: Option<TaxClass>,
+    stock: Option<u32>,
+    categories: Option<Vec<Category>>,
+    img_url: Option<Url>,
+    description: Option<String>,
+}
+
+pub struct ImportProductsCommandHandler {
+    pub product_repository: Arc<dyn ProductRepository + Send + Sync>,
+    pub category_repository: Arc<dyn CategoryRepository + Send + Sync>,
+    pub unit_of_work: Arc<dyn UnitOfWork + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<ImportProductsCommand> for ImportProductsCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<ImportProductsCommand>,
+    ) -> Result<<ImportProductsCommand as Input>::Output, SharedError> {
+        let mode = match &input.mode {
+            Some(mode) => ImportMode::parse(mode)?,
+            None => ImportMode::Create,
+        };
+        let records = parse_csv(&input.csv)?;
+        let (header, records) = match records.split_first() {
+            Some(split) => split,
+            None => return Err(SharedError::new("The CSV file is empty", 400)),
+        };
+        let columns: Vec<String> = header
+            .iter()
+            .map(|column| column.trim().to_lowercase())
+            .collect();
+        if let Some(column) = columns.iter().find(|column| {
+            !REQUIRED_COLUMNS.contains(&column.as_str())
+                && !OPTIONAL_COLUMNS.contains(&column.as_str())
+        }) {
+            return Err(SharedError::new(
+                format!("Unknown CSV column {}", column).as_str(),
+                400,
+            ));
+        }
+        if let Some(column) = REQUIRED_COLUMNS
+            .iter()
+            .find(|column| !columns.iter().any(|c| c == *column))
+        {
+            return Err(SharedError::new(
+                format!("Missing CSV column {}", column).as_str(),
+                400,
+            ));
+        }
+        if records.len() > MAX_ROWS {
+            return Err(SharedError::new(
+                format!("A CSV import cannot have more than {} rows", MAX_ROWS).as_str(),
+                400,
+            ));
+        }
+        let categories = self.category_repository.get_all().await;
+        let skus: Vec<&str> = records
+            .iter()
+            .filter_map(|record| cell(&columns, record, "sku"))
+            .collect();
+        let existing_products = match skus.is_empty() {
+            true => Vec::new(),
+            false => self.product_repository.get_many_by_skus(skus).await?,
+        };
+
+        let mut output = ImportProductsOutput {
+            dry_run: input.dry_run,
+            created: 0,
+            updated: 0,
+            failed: 0,
+            rows: Vec::new(),
+        };
+        let mut seen_skus: Vec<String> = Vec::new();
+        for (index, record) in records.iter().enumerate() {
+            if record.iter().all(|value| value.trim().is_empty()) {
+                continue;
+            }
+            let row_number = index as u32 + 2;
+            let (row, mut errors) = read_row(&columns, record, &categories);
+            // SKUs are matched ignoring case, as the database collation compares them
+            let sku_key = row.sku.to_lowercase();
+            let existing = existing_products
+                .iter()
+                .find(|p| p.sku().to_lowercase() == sku_key);
+            if !row.sku.is_empty() {
+                if seen_skus.contains(&sku_key) {
+                    errors.push(format!("SKU {} is repeated in the file", row.sku));
+                }
+                seen_skus.push(sku_key);
+            }
+            match existing {
+                Some(_) if mode == ImportMode::Create => {
+                    errors.push(format!("Product with SKU {} already exists", row.sku));
+                }
+                Some(product) => errors.extend(check_update(product, &row)),
+                None => {
+                    if row.name.is_none() {
+                        errors.push("Name is required for a new product".to_string());
+                    }
+                    if row.price.is_none() {
+                        errors.push("Price is required for a new product".to_string());
+                    }
+                }
+            }
+            if errors.is_empty() && !input.dry_run {
+                let result = match existing {
+                    Some(product) => self.update(product.clone(), &row).await,
+                    None => self.create(&row, &input.user).await,
+                };
+                if let Err(err) = result {
+                    errors.push(err.message);
+                }
+            }
+            let status = match (errors.is_empty(), existing) {
+                (false, _) => {
+                    output.failed += 1;
+                    "failed"
+                }
+                (true, Some(_)) => {
+                    output.updated += 1;
+                    "updated"
+                }
+                (true, None) => {
+                    output.created += 1;
+                    "created"
+                }
+            };
+            output.rows.push(ImportRowResult {
+                row: row_number,
+                sku: row.sku,
+                status: status.to_string(),
+                errors,
+            });
+        }
+        Ok(output)
+    }
+}
+
+impl ImportProductsCommandHandler {
+    /// Register the product with its initial stock loaded as a receipt, as a single product
+    /// registration does
+    async fn create(&self, row: &ImportRow, user: &str) -> Result<(), SharedError> {
+        run_in_transaction(
+            self.unit_of_work.as_ref(),
+            &(self, row, user),
+            |tx, (handler, row, user)| {
+                Box::pin(async move {
+                    let product = handler
+                        .product_repository
+                        .create(
+                            tx,
+                            &row.sku,
+                            row.name.as_deref().unwrap_or_default(),
+                            row.price.unwrap_or_default(),
+                            row.tax_class.unwrap_or(TaxClass::General),
+                            None,
+                            None,
+                            row.categories.as_deref().unwrap_or_default(),
+                            row.img_url.as_ref(),
+                            row.description.as_ref(),
+                        )
+                        .await?;
+                    let stock = row.stock.unwrap_or(0);
+                    if stock > 0 {
+                        let change = StockChange::new(
+                            StockMovementReason::Receipt,
+                            Some(product.id()),
+                            user,
+                        );
+                        handler
+                            .product_repository
+                            .increment_stock(tx, &product, stock, &change)
+                            .await?;
+                    }
+                    Ok(())
+                })
+            },
+        )
+        .await
+    }
+    async fn update(&self, mut product: Product, row: &ImportRow) -> Result<(), SharedError> {
+        if let Some(name) = &row.name {
+            product.set_name(name.clone());
+        }
+        if let Some(price) = row.price {
+            product.set_price(price);
+        }
+        if let Some(tax_class) = row.tax_class {
+            product.set_tax_class(tax_class);
+        }
+        if let Some(categories) = &row.categories {
+            product.set_categories(categories.clone());
+        }
+        if let Some(img_url) = &row.img_url {
+            product.set_img_url(Some(img_url.clone()));
+        }
+        if let Some(description) = &row.description {
+            product.set_description(Some(description.clone()));
+        }
+        self.product_repository.save(&product).await
+    }
+}
+
+/// Trimmed value of the column in the record, None when empty or missing
+fn cell<'a>(columns: &[String], record: &'a [String], column: &str) -> Option<&'a str> {
+    columns
+        .iter()
+        .position(|c| c == column)
+        .and_then(|index| record.get(index))
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+}
+
+/// Values of the record with the errors found reading them
+fn read_row(
+    columns: &[String],
+    record: &[String],
+    categories: &[Category],
+) -> (ImportRow, Vec<String>) {
+    let mut errors = Vec::new();
+    if record.len() != columns.len() {
+        errors.push(format!(
+            "Expected {} values but found {}",
+            columns.len(),
+            record.len()
+        ));
+    }
+    let sku = cell(columns, record, "sku").unwrap_or_default().to_string();
+    if sku.is_empty() {
+        errors.push("SKU is required".to_string());
+    } else if sku.chars().count() > 50 {
+        errors.push("SKU cannot be longer than 50 characters".to_string());
+    }
+    let name = cell(columns, record, "name").map(str::to_string);
+    if name.as_ref().is_some_and(|name| name.chars().count() > 100) {
+        errors.push("Name cannot be longer than 100 characters".to_string());
+    }
+    let price = match cell(columns, record, "price").map(Money::parse) {
+        Some(Ok(price)) if price.is_negative() => {
+            errors.push("Product price cannot be negative".to_string());
+            None
+        }
+        Some(Ok(price)) => Some(price),
+        Some(Err(err)) => {
+            errors.push(err.message);
+            None
+        }
+        None => None,
+    };
+    let tax_class = match cell(columns, record, "tax_class").map(TaxClass::parse) {
+        Some(Ok(tax_class)) => Some(tax_class),
+        Some(Err(err)) => {
+            errors.push(err.message);
+            None
+        }
+        None => None,
+    };
+    let stock = match cell(columns, record, "stock").map(|stock| (stock, stock.parse::<u32>())) {
+        Some((_, Ok(stock))) => Some(stock),
+        Some((stock, Err(_))) => {
+            errors.push(format!("Invalid stock {}", stock));
+            None
+        }
+        None => None,
+    };
+    let img_url = match cell(columns, record, "img_url").map(|url| Url::new(url.to_string())) {
+        Some(Ok(url)) => Some(url),
+        Some(Err(err)) => {
+            errors.push(err.message);
+            None
+        }
+        None => None,
+    };
+    // Category names separated by commas, the cell has to be quoted to hold them
+    let category_names =
+        cell(columns, record, "categories").or_else(|| cell(columns, record, "flags"));
+    let categories = category_names.map(|names| {
+        let mut found = Vec::new();
+        for name in names
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            match categories
+                .iter()
+                .find(|category| category.name().to_lowercase() == name.to_lowercase())
+            {
+                Some(category) => found.push(category.clone()),
+                None => errors.push(format!("Category {} not found", name)),
+            }
+        }
+        found
+    });
+    let row = ImportRow {
+        sku,
+        name,
+        price,
+        tax_class,
+        stock,
+        categories,
+        img_url,
+        description: cell(columns, record, "description").map(str::to_string),
+    };
+    (row, errors)
+}
+
+/// Errors of applying the row over the existing product, following the product edition rules
+fn check_update(product: &Product, row: &ImportRow) -> Vec<String> {
+    let mut errors = Vec::new();
+    if row.stock.is_some_and(|stock| stock != product.stock()) {
+        errors.push(
+            "Stock of an existing product is only changed through stock adjustments".to_string(),
+        );
+    }
+    if product.variant().is_some() {
+        if row
+            .tax_class
+            .is_some_and(|tax_class| tax_class != product.tax_class())
+        {
+            errors.push(
+                "Variants take the tax class of their parent product, edit it instead".to_string(),
+            );
+        }
+        let same_categories = row.categories.as_ref().is_none_or(|categories| {
+            categories.len() == product.categories().len()
+                && categories
+                    .iter()
+                    .all(|c| product.categories().iter().any(|pc| pc.id() == c.id()))
+        });
+        if !same_categories {
+            errors.push(
+                "Variants take the categories of their parent product, edit it instead".to_string(),
+            );
+        }
+    }
+    errors
+}
+
+/// Records of a CSV text, quoted values may hold commas, quotes doubled and line breaks
+fn parse_csv(text: &str) -> Result<Vec<Vec<String>>, SharedError> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut value = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    value.push('"');
+                    chars.next();
+                }
+                '"' => quoted = false,
+                _ => value.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if value.is_empty() => quoted = true,
+            ',' => record.push(std::mem::take(&mut value)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut value));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => value.push(c),
+        }
+    }
+    if quoted {
+        return Err(SharedError::new(
+            "The CSV file has a quoted value that is never closed",
+            400,
+        ));
+    }
+    if !value.is_empty() || !record.is_empty() {
+        record.push(value);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records(text: &str) -> Vec<Vec<String>> {
+        parse_csv(text).unwrap()
+    }
+
+    #[test]
+    fn parse_csv_splits_records_and_values() {
+        assert_eq!(
+            records("sku,name\nA1,Shirt\nA2,Jeans"),
+            vec![
+                vec!["sku", "name"],
+                vec!["A1", "Shirt"],
+                vec!["A2", "Jeans"]
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_csv_keeps_commas_inside_quotes() {
+        assert_eq!(
+            records("sku,categories\nA1,\"Men, Shirts\""),
+            vec![vec!["sku", "categories"], vec!["A1", "Men, Shirts"]]
+        );
+    }
+
+    #[test]
+    fn parse_csv_unescapes_doubled_quotes() {
+        assert_eq!(
+            records("name\n\"The \"\"classic\"\" shirt\""),
+            vec![vec!["name"], vec!["The \"classic\" shirt"]]
+        );
+    }
+
+    #[test]
+    fn parse_csv_accepts_crlf_line_breaks() {
+        assert_eq!(
+            records("sku,name\r\nA1,Shirt\r\n"),
+            vec![vec!["sku", "name"], vec!["A1", "Shirt"]]
+        );
+    }
+
+    #[test]
+    fn parse_csv_skips_the_byte_order_mark() {
+        assert_eq!(
+            records("\u{feff}sku,name\nA1,Shirt"),
+            vec![vec!["sku", "name"], vec!["A1", "Shirt"]]
+        );
+    }
+
+    #[test]
+    fn parse_csv_keeps_line_breaks_inside_quotes() {
+        assert_eq!(
+            records("sku,description\nA1,\"First line\nSecond line\"\nA2,Plain"),
+            vec![
+                vec!["sku", "description"],
+                vec!["A1", "First line\nSecond line"],
+                vec!["A2", "Plain"],
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_csv_keeps_empty_values() {
+        assert_eq!(
+            records("sku,name,price\nA1,,"),
+            vec![vec!["sku", "name", "price"], vec!["A1", "", ""]]
+        );
+    }
+
+    #[test]
+    fn parse_csv_rejects_unclosed_quotes() {
+        let err = parse_csv("sku,name\nA1,\"Shirt\nA2,Jeans").unwrap_err();
+        assert_eq!(err.code, 400);
+    }
+
+    #[test]
+    fn parse_csv_of_empty_text_has_no_records() {
+        assert!(records("").is_empty());
+    }
+}
@@ -3,9 +3,65 @@ mod register_customer;
 mod edit_customer;
 mod register_product;
 mod edit_product;
+mod adjust_stock;
+mod generate_product_variants;
+mod set_product_archived;
+mod import_products;
+mod register_category;
+mod edit_category;
+mod delete_category;
+mod cancel_sale;
+mod register_return;
+mod exchange;
+mod register_promotion;
+mod set_promotion_active;
+mod open_draft_sale;
+mod add_draft_item;
+mod remove_draft_item;
+mod set_draft_sale_parked;
+mod checkout_draft_sale;
+mod register_invoice_series;
+mod activate_invoice_series;
+mod generate_electronic_invoice;
+mod send_sale_receipt;
 
-pub use register_sale::{RegisterSaleCommand, RegisterSaleCommandHandler};
+pub use register_sale::{PaymentInput, RegisterSaleCommand, RegisterSaleCommandHandler};
 pub use register_customer::{RegisterCustomerCommand, RegisterCustomerCommandHandler};
 pub use register_product::{RegisterProductCommand, RegisterProductCommandHandler};
 pub use edit_customer::{EditCustomerCommand, EditCustomerCommandHandler};
 pub use edit_product::{EditProductCommand, EditProductCommandHandler};
+pub use adjust_stock::{AdjustStockCommand, AdjustStockCommandHandler};
+pub use generate_product_variants::{
+    GenerateProductVariantsCommand, GenerateProductVariantsCommandHandler,
+};
+pub use set_product_archived::{SetProductArchivedCommand, SetProductArchivedCommandHandler};
+pub use import_products::{
+    ImportProductsCommand, ImportProductsCommandHandler, ImportRowResult,
+};
+pub use register_category::{RegisterCategoryCommand, RegisterCategoryCommandHandler};
+pub use edit_category::{EditCategoryCommand, EditCategoryCommandHandler};
+pub use delete_category::{DeleteCategoryCommand, DeleteCategoryCommandHandler};
+pub use cancel_sale::{CancelSaleCommand, CancelSaleCommandHandler};
+pub use register_return::{RegisterReturnCommand, RegisterReturnCommandHandler};
+pub use exchange::{ExchangeCommand, ExchangeCommandHandler};
+pub use register_promotion::{RegisterPromotionCommand, RegisterPromotionCommandHandler};
+pub use set_promotion_active::{SetPromotionActiveCommand, SetPromotionActiveCommandHandler};
+pub use open_draft_sale::{OpenDraftSaleCommand, OpenDraftSaleCommandHandler};
+pub use add_draft_item::{AddDraftItemCommand, AddDraftItemCommandHandler};
+pub use remove_draft_item::{RemoveDraftItemCommand, RemoveDraftItemCommandHandler};
+pub use set_draft_sale_parked::{SetDraftSaleParkedCommand, SetDraftSaleParkedCommandHandler};
+pub use checkout_draft_sale::{CheckoutDraftSaleCommand, CheckoutDraftSaleCommandHandler};
+pub use register_invoice_series::{
+    RegisterInvoiceSeriesCommand, RegisterInvoiceSeriesCommandHandler,
+};
+pub use activate_invoice_series::{
+    ActivateInvoiceSeriesCommand, ActivateInvoiceSeriesCommandHandler,
+};
+pub use generate_electronic_invoice::{
+    GenerateElectronicInvoiceCommand, GenerateElectronicInvoiceCommandHandler,
+};
+pub use send_sale_receipt::{ReceiptEmailer, SendSaleReceiptCommand, SendSaleReceiptCommandHandler};
+pub(crate) use register_product::find_categories;
+pub(crate) use register_sale::{
+    apply_promotions, build_payments, check_sale_products, log_low_stock, total_to_pay,
+};
@@ -0,0 +1,42 @@
+use crate::domain::repositories::{CustomerRepository, DraftSaleRepository};
+use crate::domain::value_objects::CC;
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use std::sync::Arc;
+
+pub struct OpenDraftSaleCommand {
+    pub customer_cc: String,
+}
+pub struct OpenDraftSaleOutput {
+    pub draft_sale_id: u32,
+}
+impl Input for OpenDraftSaleCommand {
+    type Output = OpenDraftSaleOutput;
+}
+
+pub struct OpenDraftSaleCommandHandler {
+    pub customer_repository: Arc<dyn CustomerRepository + Send + Sync>,
+    pub draft_sale_repository: Arc<dyn DraftSaleRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<OpenDraftSaleCommand> for OpenDraftSaleCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<OpenDraftSaleCommand>,
+    ) -> Result<<OpenDraftSaleCommand as Input>::Output, SharedError> {
+        let customer_cc = CC::new(input.customer_cc.clone())?;
+        let customer = match self.customer_repository.get_by_cc(&customer_cc).await {
+            Some(customer) => customer,
+            None => {
+                return Err(SharedError::new(
+                    format!("Customer with {} CC not found", input.customer_cc).as_str(),
+                    404,
+                ));
+            }
+        };
+        let draft = self.draft_sale_repository.create(&customer).await?;
+        Ok(OpenDraftSaleOutput {
+            draft_sale_id: draft.id(),
+        })
+    }
+}
@@ -0,0 +1,49 @@
+use crate::domain::entities::Category;
+use crate::domain::repositories::CategoryRepository;
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use std::sync::Arc;
+
+/// Register a category, under the given parent one when set
+pub struct RegisterCategoryCommand {
+    pub name: String,
+    pub parent_id: Option<u32>,
+}
+pub struct RegisterCategoryOutput {
+    pub category_id: u32,
+}
+impl Input for RegisterCategoryCommand {
+    type Output = RegisterCategoryOutput;
+}
+
+pub struct RegisterCategoryCommandHandler {
+    pub category_repository: Arc<dyn CategoryRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<RegisterCategoryCommand> for RegisterCategoryCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<RegisterCategoryCommand>,
+    ) -> Result<<RegisterCategoryCommand as Input>::Output, SharedError> {
+        let name = Category::validate_name(&input.name)?;
+        if let Some(parent_id) = input.parent_id
+            && self
+                .category_repository
+                .get_by_id(parent_id)
+                .await
+                .is_none()
+        {
+            return Err(SharedError::new(
+                format!("Category with ID {} not found", parent_id).as_str(),
+                404,
+            ));
+        }
+        let category = self
+            .category_repository
+            .create(&name, input.parent_id)
+            .await?;
+        Ok(RegisterCategoryOutput {
+            category_id: category.id(),
+        })
+    }
+}
@@ -0,0 +1,51 @@
+use crate::domain::repositories::InvoiceSeriesRepository;
+use crate::domain::value_objects::InvoiceNumber;
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use std::sync::Arc;
+
+/// Register a new authorized range of invoice numbers, inactive until activated
+pub struct RegisterInvoiceSeriesCommand {
+    pub prefix: String,
+    pub range_from: u64,
+    pub range_to: u64,
+    pub warning_threshold: u64,
+}
+pub struct RegisterInvoiceSeriesOutput {
+    pub invoice_series_id: u32,
+}
+impl Input for RegisterInvoiceSeriesCommand {
+    type Output = RegisterInvoiceSeriesOutput;
+}
+
+pub struct RegisterInvoiceSeriesCommandHandler {
+    pub invoice_series_repository: Arc<dyn InvoiceSeriesRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<RegisterInvoiceSeriesCommand> for RegisterInvoiceSeriesCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<RegisterInvoiceSeriesCommand>,
+    ) -> Result<<RegisterInvoiceSeriesCommand as Input>::Output, SharedError> {
+        // Same rules as the numbers the series will give
+        InvoiceNumber::new(input.prefix.clone(), input.range_from)?;
+        if input.range_from == 0 || input.range_from > input.range_to {
+            return Err(SharedError::new(
+                "Invoice series range must start at 1 or more and not end before it starts",
+                400,
+            ));
+        }
+        let series = self
+            .invoice_series_repository
+            .create(
+                &input.prefix,
+                input.range_from,
+                input.range_to,
+                input.warning_threshold,
+            )
+            .await?;
+        Ok(RegisterInvoiceSeriesOutput {
+            invoice_series_id: series.id(),
+        })
+    }
+}
@@ -1,5 +1,7 @@
-use crate::domain::repositories::ProductRepository;
-use crate::domain::value_objects::Url;
+use crate::domain::entities::{Category, StockChange, StockMovementReason, TaxClass};
+use crate::domain::repositories::{CategoryRepository, ProductRepository};
+use crate::domain::unit_of_work::{UnitOfWork, run_in_transaction};
+use crate::domain::value_objects::{Money, Url};
 use crate::shared::SharedError;
 use crate::shared::input_handler::{Input, InputHandler};
 use std::sync::Arc;
@@ -9,9 +11,16 @@ pub struct RegisterProductCommand {
     pub name: String,
     pub description: Option<String>,
     pub stock: Option<u32>,
-    pub price: f64,
+    /// Stock at or below which the product has to be reordered
+    pub reorder_point: Option<u32>,
+    pub reorder_quantity: Option<u32>,
+    pub price: Money,
+    /// "general", "reduced" or "exempt", general when not given
+    pub tax_class: Option<String>,
     pub img_url: Option<String>,
-    pub flags: Vec<String>,
+    pub category_ids: Vec<u32>,
+    /// User registering the product, recorded in the stock movements
+    pub user: String,
 }
 
 pub struct RegisterProductOutput {
@@ -24,6 +33,8 @@ impl Input for RegisterProductCommand {
 
 pub struct RegisterProductCommandHandler {
     pub product_repository: Arc<dyn ProductRepository + Send + Sync>,
+    pub category_repository: Arc<dyn CategoryRepository + Send + Sync>,
+    pub unit_of_work: Arc<dyn UnitOfWork + Send + Sync>,
 }
 
 #[async_trait::async_trait]
@@ -36,6 +47,21 @@ impl InputHandler<RegisterProductCommand> for RegisterProductCommandHandler {
             Some(u) => Some(Url::new(u.clone())?),
             None => None,
         };
+        if input.price.is_negative() {
+            return Err(SharedError::new("Product price cannot be negative", 400));
+        }
+        if input.reorder_quantity == Some(0) {
+            return Err(SharedError::new(
+                "Reorder quantity must be greater than 0",
+                400,
+            ));
+        }
+        let tax_class = match &input.tax_class {
+            Some(tax_class) => TaxClass::parse(tax_class)?,
+            None => TaxClass::General,
+        };
+        let categories =
+            find_categories(self.category_repository.as_ref(), &input.category_ids).await?;
         let product_existing = self.product_repository.get_by_sku(&input.sku).await;
         if let Some(_) = product_existing {
             return Err(SharedError::new(
@@ -43,20 +69,72 @@ impl InputHandler<RegisterProductCommand> for RegisterProductCommandHandler {
                 400,
             ));
         }
-        let product = self
-            .product_repository
-            .create(
-                &input.sku,
-                &input.name,
-                (input.price * 100.0) as i64,
-                input.stock.unwrap_or(0 as u32),
-                &input.flags,
+        // The initial stock is loaded as a receipt, together with the product
+        let product = run_in_transaction(
+            self.unit_of_work.as_ref(),
+            &(
+                self,
+                input.as_ref(),
+                tax_class,
                 img_url.as_ref(),
-                input.description.as_ref(),
-            )
-            .await?;
+                &categories,
+            ),
+            |tx, (handler, input, tax_class, img_url, categories)| {
+                Box::pin(async move {
+                    let product = handler
+                        .product_repository
+                        .create(
+                            tx,
+                            &input.sku,
+                            &input.name,
+                            input.price,
+                            *tax_class,
+                            input.reorder_point,
+                            input.reorder_quantity,
+                            categories,
+                            *img_url,
+                            input.description.as_ref(),
+                        )
+                        .await?;
+                    let stock = input.stock.unwrap_or(0);
+                    if stock > 0 {
+                        let change = StockChange::new(
+                            StockMovementReason::Receipt,
+                            Some(product.id()),
+                            &input.user,
+                        );
+                        handler
+                            .product_repository
+                            .increment_stock(tx, &product, stock, &change)
+                            .await?;
+                    }
+                    Ok(product)
+                })
+            },
+        )
+        .await?;
         Ok(RegisterProductOutput {
             product_id: product.id(),
         })
     }
 }
+
+/// Categories with the given ids, failing with 400 if any of them does not exist
+pub(crate) async fn find_categories(
+    category_repository: &(dyn CategoryRepository + Send + Sync),
+    category_ids: &[u32],
+) -> Result<Vec<Category>, SharedError> {
+    let categories = category_repository.get_many_by_ids(category_ids).await;
+    let missing: Vec<String> = category_ids
+        .iter()
+        .filter(|id| !categories.iter().any(|category| category.id() == **id))
+        .map(|id| id.to_string())
+        .collect();
+    if !missing.is_empty() {
+        return Err(SharedError::new(
+            format!("Categories not found: {}", missing.join(", ")).as_str(),
+            400,
+        ));
+    }
+    Ok(categories)
+}
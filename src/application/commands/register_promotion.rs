@@ -0,0 +1,110 @@
+use crate::domain::entities::{Promotion, PromotionRule, PromotionScope};
+use crate::domain::repositories::{CategoryRepository, PromotionRepository};
+use crate::domain::value_objects::Money;
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+pub struct RegisterPromotionCommand {
+    pub name: String,
+    /// Coupon code, None for promotions applied automatically
+    pub code: Option<String>,
+    /// "line" or "sale"
+    pub scope: String,
+    /// "percentage", "fixed_amount" or "buy_x_pay_y"
+    pub kind: String,
+    /// Percentage off for percentage promotions
+    pub percentage: Option<f64>,
+    /// Amount off for fixed amount promotions
+    pub amount: Option<Money>,
+    pub buy_quantity: Option<u32>,
+    pub pay_quantity: Option<u32>,
+    /// Category whose products, subcategories included, are discounted
+    pub category_id: Option<u32>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+}
+pub struct RegisterPromotionOutput {
+    pub promotion_id: u32,
+}
+impl Input for RegisterPromotionCommand {
+    type Output = RegisterPromotionOutput;
+}
+
+pub struct RegisterPromotionCommandHandler {
+    pub promotion_repository: Arc<dyn PromotionRepository + Send + Sync>,
+    pub category_repository: Arc<dyn CategoryRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<RegisterPromotionCommand> for RegisterPromotionCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<RegisterPromotionCommand>,
+    ) -> Result<<RegisterPromotionCommand as Input>::Output, SharedError> {
+        let scope = match input.scope.as_str() {
+            "line" => PromotionScope::Line,
+            "sale" => PromotionScope::Sale,
+            other => {
+                return Err(SharedError::new(
+                    format!("Invalid promotion scope {}", other).as_str(),
+                    400,
+                ));
+            }
+        };
+        let rule = match input.kind.as_str() {
+            "percentage" => input.percentage.map(PromotionRule::Percentage),
+            "fixed_amount" => input.amount.map(PromotionRule::FixedAmount),
+            "buy_x_pay_y" => input
+                .buy_quantity
+                .zip(input.pay_quantity)
+                .map(|(buy, pay)| PromotionRule::BuyXPayY { buy, pay }),
+            _ => None,
+        }
+        .ok_or_else(|| SharedError::new("Invalid promotion rule", 400))?;
+        Promotion::validate_rule(scope, rule)?;
+        if let (Some(starts_at), Some(ends_at)) = (&input.starts_at, &input.ends_at)
+            && starts_at > ends_at
+        {
+            return Err(SharedError::new(
+                "Promotion must start before it ends",
+                400,
+            ));
+        }
+        if let Some(category_id) = input.category_id
+            && self
+                .category_repository
+                .get_by_id(category_id)
+                .await
+                .is_none()
+        {
+            return Err(SharedError::new(
+                format!("Category with ID {} not found", category_id).as_str(),
+                404,
+            ));
+        }
+        if let Some(code) = &input.code
+            && self.promotion_repository.get_by_code(code).await.is_some()
+        {
+            return Err(SharedError::new(
+                format!("Promotion with code {} already exists", code).as_str(),
+                400,
+            ));
+        }
+        let promotion = self
+            .promotion_repository
+            .create(
+                &input.name,
+                input.code.as_deref(),
+                scope,
+                rule,
+                input.category_id,
+                input.starts_at.as_ref(),
+                input.ends_at.as_ref(),
+            )
+            .await?;
+        Ok(RegisterPromotionOutput {
+            promotion_id: promotion.id(),
+        })
+    }
+}
@@ -0,0 +1,79 @@
+use crate::domain::entities::{StockChange, StockMovementReason};
+use crate::domain::repositories::{ProductRepository, SaleRepository};
+use crate::domain::unit_of_work::{UnitOfWork, run_in_transaction};
+use crate::domain::value_objects::Money;
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use std::sync::Arc;
+
+pub struct RegisterReturnCommand {
+    pub sale_id: u32,
+    pub product_skus_quantity: Vec<(String, u32)>,
+    pub reason: Option<String>,
+    /// User registering the return, recorded in the stock movements
+    pub user: String,
+}
+pub struct RegisterReturnOutput {
+    pub return_id: u32,
+    pub refunded_amount: Money,
+}
+impl Input for RegisterReturnCommand {
+    type Output = RegisterReturnOutput;
+}
+
+pub struct RegisterReturnCommandHandler {
+    pub product_repository: Arc<dyn ProductRepository + Send + Sync>,
+    pub sale_repository: Arc<dyn SaleRepository + Send + Sync>,
+    pub unit_of_work: Arc<dyn UnitOfWork + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<RegisterReturnCommand> for RegisterReturnCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<RegisterReturnCommand>,
+    ) -> Result<<RegisterReturnCommand as Input>::Output, SharedError> {
+        let sale_return = run_in_transaction(
+            self.unit_of_work.as_ref(),
+            &(self, input.as_ref()),
+            |tx, (handler, input)| {
+                Box::pin(async move {
+                    // Sale is read once locked so concurrent returns see each other
+                    let sale = handler
+                        .sale_repository
+                        .get_by_id_for_update(tx, &input.sale_id)
+                        .await?;
+                    let lines = sale.return_lines(&input.product_skus_quantity)?;
+                    let sale_return = handler
+                        .sale_repository
+                        .create_return(tx, &sale, lines.clone(), input.reason.as_deref())
+                        .await?;
+                    let change = StockChange::new(
+                        StockMovementReason::Return,
+                        Some(sale_return.id()),
+                        &input.user,
+                    );
+                    for line in lines.iter() {
+                        let product = match sale
+                            .lines()
+                            .iter()
+                            .find(|l| l.product().sku() == line.product_sku())
+                        {
+                            Some(sale_line) => sale_line.product(),
+                            None => continue,
+                        };
+                        handler
+                            .product_repository
+                            .increment_stock(tx, product, line.quantity(), &change)
+                            .await?;
+                    }
+                    Ok(sale_return)
+                })
+            },
+        )
+        .await?;
+        Ok(RegisterReturnOutput {
+            return_id: sale_return.id(),
+            refunded_amount: sale_return.refunded_amount()?,
+        })
+    }
+}
@@ -1,16 +1,118 @@
-use crate::domain::repositories::{CustomerRepository, ProductRepository, SaleRepository};
-use crate::domain::value_objects::CC;
+use super::send_sale_receipt::ReceiptEmailer;
+use crate::application::services::Logger;
+use crate::domain::entities::{
+    AppliedDiscount, Customer, InvoiceSeriesAlert, LowStockAlert, Payment, PaymentMethod, Product, Promotion,
+    PromotionScope, Sale, SaleLine, StockChange, StockMovementReason,
+};
+use crate::domain::repositories::{
+    CustomerRepository, IdempotencyRepository, InvoiceSeriesRepository, ProductRepository,
+    PromotionRepository, SaleRepository,
+};
+use crate::domain::unit_of_work::{Transaction, UnitOfWork, run_in_transaction};
+use crate::domain::value_objects::{CC, Money};
 use crate::shared::SharedError;
 use crate::shared::input_handler::{Input, InputHandler};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::sync::Arc;
 
 pub struct RegisterSaleCommand {
     pub customer_cc: String,
     pub product_skus_quantity: Vec<(String, u32)>,
+    pub coupon_codes: Vec<String>,
+    pub payments: Vec<PaymentInput>,
+    /// Key sent by the client so retries of the same request register the sale only once
+    pub idempotency_key: Option<String>,
+    /// User registering the sale, recorded in the stock movements
+    pub user: String,
+}
+impl RegisterSaleCommand {
+    /// SHA-256 of the request fields, equal for every retry of the same request
+    fn request_hash(&self) -> String {
+        let request = serde_json::json!({
+            "customer_cc": self.customer_cc,
+            "product_skus_quantity": self.product_skus_quantity,
+            "coupon_codes": self.coupon_codes,
+            "payments": self
+                .payments
+                .iter()
+                .map(|p| serde_json::json!({
+                    "method": p.method,
+                    "amount": p.amount.to_string(),
+                    "tendered": p.tendered.map(|t| t.to_string()),
+                }))
+                .collect::<Vec<_>>(),
+        });
+        format!("{:x}", Sha256::digest(request.to_string().as_bytes()))
+    }
+}
+pub struct PaymentInput {
+    /// "cash", "card", "bank_transfer", "nequi", "daviplata" or "store_credit"
+    pub method: String,
+    pub amount: Money,
+    /// Cash handed over by the customer, only for cash payments
+    pub tendered: Option<Money>,
 }
 pub struct RegisterSaleOutput {
     pub sale_id: u32,
-    pub total_amount: f64,
+    pub invoice_number: String,
+    pub subtotal: Money,
+    pub discount_amount: Money,
+    pub total_amount: Money,
+    pub change: Money,
+}
+impl TryFrom<&Sale> for RegisterSaleOutput {
+    type Error = SharedError;
+    fn try_from(sale: &Sale) -> Result<Self, SharedError> {
+        Ok(RegisterSaleOutput {
+            sale_id: sale.id(),
+            invoice_number: sale
+                .invoice_number()
+                .as_ref()
+                .map(|n| n.to_string())
+                .unwrap_or_default(),
+            subtotal: sale.subtotal()?,
+            discount_amount: sale.discount_amount()?,
+            total_amount: sale.total_amount()?,
+            change: sale.change()?,
+        })
+    }
+}
+/// Output stored along with its idempotency key, amounts as decimal strings
+#[derive(Serialize, Deserialize)]
+struct StoredSaleOutput {
+    sale_id: u32,
+    invoice_number: String,
+    subtotal: String,
+    discount_amount: String,
+    total_amount: String,
+    change: String,
+}
+impl From<&RegisterSaleOutput> for StoredSaleOutput {
+    fn from(output: &RegisterSaleOutput) -> Self {
+        StoredSaleOutput {
+            sale_id: output.sale_id,
+            invoice_number: output.invoice_number.clone(),
+            subtotal: output.subtotal.to_string(),
+            discount_amount: output.discount_amount.to_string(),
+            total_amount: output.total_amount.to_string(),
+            change: output.change.to_string(),
+        }
+    }
+}
+impl TryFrom<StoredSaleOutput> for RegisterSaleOutput {
+    type Error = SharedError;
+    fn try_from(stored: StoredSaleOutput) -> Result<Self, SharedError> {
+        Ok(RegisterSaleOutput {
+            sale_id: stored.sale_id,
+            invoice_number: stored.invoice_number,
+            subtotal: Money::parse(&stored.subtotal)?,
+            discount_amount: Money::parse(&stored.discount_amount)?,
+            total_amount: Money::parse(&stored.total_amount)?,
+            change: Money::parse(&stored.change)?,
+        })
+    }
 }
 impl Input for RegisterSaleCommand {
     type Output = RegisterSaleOutput;
@@ -20,6 +122,31 @@ pub struct RegisterSaleCommandHandler {
     pub customer_repository: Arc<dyn CustomerRepository + Send + Sync>,
     pub product_repository: Arc<dyn ProductRepository + Send + Sync>,
     pub sale_repository: Arc<dyn SaleRepository + Send + Sync>,
+    pub promotion_repository: Arc<dyn PromotionRepository + Send + Sync>,
+    pub invoice_series_repository: Arc<dyn InvoiceSeriesRepository + Send + Sync>,
+    pub idempotency_repository: Arc<dyn IdempotencyRepository + Send + Sync>,
+    pub receipt_emailer: Arc<ReceiptEmailer>,
+    pub logger: Arc<dyn Logger + Send + Sync>,
+    pub unit_of_work: Arc<dyn UnitOfWork + Send + Sync>,
+}
+impl RegisterSaleCommandHandler {
+    /// Output stored for an earlier request made with the key, failing with 422 if that
+    /// request was a different one
+    async fn replay(
+        &self,
+        key: &str,
+        request_hash: &str,
+    ) -> Result<Option<RegisterSaleOutput>, SharedError> {
+        let Some(record) = self.idempotency_repository.get_by_key(key).await else {
+            return Ok(None);
+        };
+        record.check_request(request_hash)?;
+        let stored: StoredSaleOutput = serde_json::from_str(record.response())
+            .map_err(|_| SharedError::new("Failed to read stored sale response", 500))?;
+        RegisterSaleOutput::try_from(stored)
+            .map(Some)
+            .map_err(|_| SharedError::new("Failed to read stored sale response", 500))
+    }
 }
 #[async_trait::async_trait]
 impl InputHandler<RegisterSaleCommand> for RegisterSaleCommandHandler {
@@ -27,6 +154,18 @@ impl InputHandler<RegisterSaleCommand> for RegisterSaleCommandHandler {
         &self,
         input: Arc<RegisterSaleCommand>,
     ) -> Result<<RegisterSaleCommand as Input>::Output, SharedError> {
+        let request_hash = input.request_hash();
+        if let Some(key) = &input.idempotency_key {
+            if key.is_empty() || key.len() > 255 {
+                return Err(SharedError::new(
+                    "Idempotency key must have between 1 and 255 characters",
+                    400,
+                ));
+            }
+            if let Some(output) = self.replay(key, &request_hash).await? {
+                return Ok(output);
+            }
+        }
         // Fetch customer by CC
         let customer_cc = CC::new(input.customer_cc.clone())?;
         let customer = match self.customer_repository.get_by_cc(&customer_cc).await {
@@ -38,44 +177,350 @@ impl InputHandler<RegisterSaleCommand> for RegisterSaleCommandHandler {
                 ));
             }
         };
-        // Prepare products and quantities
-        let products = self
-            .product_repository
-            .get_many_by_skus(
-                input
-                    .product_skus_quantity
-                    .iter()
-                    .map(|(sku, _)| sku.as_str())
-                    .collect(),
+        let registration = SaleRegistration::prepare(
+            self.product_repository.as_ref(),
+            self.promotion_repository.as_ref(),
+            customer,
+            &input.product_skus_quantity,
+            &input.coupon_codes,
+            &input.payments,
+        )
+        .await?;
+        // Stock updates, the sale and its idempotency key are committed or rolled back together
+        let idempotency_key = input.idempotency_key.as_deref();
+        let result = run_in_transaction(
+            self.unit_of_work.as_ref(),
+            &(
+                self,
+                &registration,
+                idempotency_key,
+                request_hash.as_str(),
+                input.user.as_str(),
+            ),
+            |tx, (handler, registration, idempotency_key, request_hash, user)| {
+                Box::pin(async move {
+                    let (sale, alerts) = registration
+                        .save(
+                            tx,
+                            handler.product_repository.as_ref(),
+                            handler.sale_repository.as_ref(),
+                            handler.invoice_series_repository.as_ref(),
+                            user,
+                        )
+                        .await?;
+                    let output = RegisterSaleOutput::try_from(&sale)?;
+                    if let Some(key) = idempotency_key {
+                        let response = serde_json::to_string(&StoredSaleOutput::from(&output))
+                            .map_err(|_| SharedError::new("Failed to store sale response", 500))?;
+                        handler
+                            .idempotency_repository
+                            .create(tx, key, request_hash, &response)
+                            .await?;
+                    }
+                    Ok((output, alerts))
+                })
+            },
+        )
+        .await;
+        let result = result.map(|(output, alerts)| {
+            alerts.log(self.logger.as_ref());
+            output
+        });
+        // Replayed requests return before getting here, so the receipt is emailed once
+        if let Ok(output) = &result {
+            self.receipt_emailer.send_in_background(output.sale_id);
+        }
+        match (result, idempotency_key) {
+            // A concurrent retry may have registered the sale while this one was running
+            (Err(err), Some(key)) => match self.replay(key, &request_hash).await? {
+                Some(output) => Ok(output),
+                None => Err(err),
+            },
+            (result, _) => result,
+        }
+    }
+}
+
+/// Sale checked against the registration rules, ready to be saved
+#[derive(Clone)]
+pub(crate) struct SaleRegistration {
+    customer: Customer,
+    products_sale: Vec<(Product, u32)>,
+    lines: Vec<SaleLine>,
+    discounts: Vec<AppliedDiscount>,
+    payments: Vec<Payment>,
+}
+impl SaleRegistration {
+    /// Check the products and their stock, apply the promotions and check the payments
+    /// cover the total to pay
+    pub(crate) async fn prepare(
+        product_repository: &(dyn ProductRepository + Send + Sync),
+        promotion_repository: &(dyn PromotionRepository + Send + Sync),
+        customer: Customer,
+        product_skus_quantity: &[(String, u32)],
+        coupon_codes: &[String],
+        payments: &[PaymentInput],
+    ) -> Result<Self, SharedError> {
+        let mut registration = Self::prepare_unpaid(
+            product_repository,
+            promotion_repository,
+            customer,
+            product_skus_quantity,
+            coupon_codes,
+        )
+        .await?;
+        registration.pay(build_payments(payments)?)?;
+        Ok(registration)
+    }
+
+    /// Check the products and their stock and apply the promotions, leaving the payments
+    /// to be set with `pay`
+    pub(crate) async fn prepare_unpaid(
+        product_repository: &(dyn ProductRepository + Send + Sync),
+        promotion_repository: &(dyn PromotionRepository + Send + Sync),
+        customer: Customer,
+        product_skus_quantity: &[(String, u32)],
+        coupon_codes: &[String],
+    ) -> Result<Self, SharedError> {
+        let products_sale =
+            prepare_sale_products(product_repository, product_skus_quantity).await?;
+        let lines: Vec<SaleLine> = products_sale
+            .iter()
+            .map(|(p, q)| SaleLine::from_product(p.clone(), *q))
+            .collect();
+        let discounts = apply_promotions(promotion_repository, &lines, coupon_codes).await?;
+        Ok(SaleRegistration {
+            customer,
+            products_sale,
+            lines,
+            discounts,
+            payments: Vec::new(),
+        })
+    }
+
+    pub(crate) fn total_to_pay(&self) -> Result<Money, SharedError> {
+        total_to_pay(&self.lines, &self.discounts)
+    }
+
+    /// Set the payments of the sale, failing if they do not cover the total to pay
+    pub(crate) fn pay(&mut self, payments: Vec<Payment>) -> Result<(), SharedError> {
+        Payment::check_cover(&payments, self.total_to_pay()?)?;
+        self.payments = payments;
+        Ok(())
+    }
+
+    /// Create the sale with the next invoice number and take its products out of stock,
+    /// within the given transaction. Returns the alerts to raise once committed.
+    pub(crate) async fn save(
+        &self,
+        tx: &mut dyn Transaction,
+        product_repository: &(dyn ProductRepository + Send + Sync),
+        sale_repository: &(dyn SaleRepository + Send + Sync),
+        invoice_series_repository: &(dyn InvoiceSeriesRepository + Send + Sync),
+        user: &str,
+    ) -> Result<(Sale, SaleAlerts), SharedError> {
+        let (invoice_number, invoice_series) = invoice_series_repository.next_number(tx).await?;
+        let sale = sale_repository
+            .create(
+                tx,
+                &self.customer,
+                invoice_number,
+                self.lines.clone(),
+                self.discounts.clone(),
+                self.payments.clone(),
             )
-            .await;
-        if products.len() != input.product_skus_quantity.len() {
-            return Err(SharedError::new("One or more products not found", 404));
+            .await?;
+        // Taken after the sale is created so the movements can reference it
+        let change = StockChange::new(StockMovementReason::Sale, Some(sale.id()), user);
+        let mut low_stock = Vec::new();
+        for (product, quantity) in self.products_sale.iter() {
+            let stock = product_repository
+                .decrement_stock(tx, product, *quantity, &change)
+                .await?;
+            low_stock.extend(product.low_stock_alert(stock + quantity, stock, change.reason));
+        }
+        let alerts = SaleAlerts {
+            low_stock,
+            invoice_series,
+        };
+        Ok((sale, alerts))
+    }
+}
+
+/// Alerts raised while saving a sale, logged only once it is committed
+pub(crate) struct SaleAlerts {
+    low_stock: Vec<LowStockAlert>,
+    invoice_series: Option<InvoiceSeriesAlert>,
+}
+impl SaleAlerts {
+    pub(crate) fn log(&self, logger: &(dyn Logger + Send + Sync)) {
+        log_low_stock(logger, &self.low_stock);
+        if let Some(alert) = &self.invoice_series {
+            logger.warn(&alert.message());
+        }
+    }
+}
+
+/// Log the alerts of a committed stock change, a rolled back one must not raise them
+pub(crate) fn log_low_stock(logger: &(dyn Logger + Send + Sync), alerts: &[LowStockAlert]) {
+    for alert in alerts {
+        logger.warn(&alert.message());
+    }
+}
+
+/// Amount of the lines minus their discounts, failing on overflow
+pub(crate) fn total_to_pay(
+    lines: &[SaleLine],
+    discounts: &[AppliedDiscount],
+) -> Result<Money, SharedError> {
+    let mut total = Money::zero();
+    for line in lines {
+        total = total.checked_add(line.amount()?)?;
+    }
+    for discount in discounts {
+        total = total.checked_sub(discount.amount())?;
+    }
+    Ok(total)
+}
+
+pub(crate) fn build_payments(payments: &[PaymentInput]) -> Result<Vec<Payment>, SharedError> {
+    payments
+        .iter()
+        .map(|p| Payment::new(PaymentMethod::parse(&p.method)?, p.amount, p.tendered))
+        .collect()
+}
+
+/// Products of the (SKU, quantity) items of a sale matched against the catalog
+pub(crate) struct SaleProductsCheck {
+    /// Found products with their quantity, in the items order
+    pub products_sale: Vec<(Product, u32)>,
+    pub missing_skus: Vec<String>,
+    /// Found products that are archived and cannot be sold anymore
+    pub archived_skus: Vec<String>,
+    /// Found products with variants, one of the variants has to be sold instead
+    pub parent_skus: Vec<String>,
+    /// "SKU (short by N)" for every product without enough stock
+    pub shortages: Vec<String>,
+}
+
+/// Fetch the products of the (SKU, quantity) items of a sale, collecting the missing ones
+/// and the ones short of stock
+pub(crate) async fn check_sale_products(
+    product_repository: &(dyn ProductRepository + Send + Sync),
+    product_skus_quantity: &[(String, u32)],
+) -> Result<SaleProductsCheck, SharedError> {
+    let products = product_repository
+        .get_many_by_skus(
+            product_skus_quantity
+                .iter()
+                .map(|(sku, _)| sku.as_str())
+                .collect(),
+        )
+        .await?;
+    let mut check = SaleProductsCheck {
+        products_sale: Vec::new(),
+        missing_skus: Vec::new(),
+        archived_skus: Vec::new(),
+        parent_skus: Vec::new(),
+        shortages: Vec::new(),
+    };
+    // Reserved over copies so repeated SKUs add up against the same stock
+    let mut reserved_products = products.clone();
+    for (sku, quantity) in product_skus_quantity {
+        if let Some(product) = reserved_products.iter_mut().find(|p| *p.sku() == *sku) {
+            check.products_sale.push((product.clone(), *quantity));
+            if product.is_archived() && !check.archived_skus.contains(sku) {
+                check.archived_skus.push(sku.clone());
+            }
+            if product.has_variants() && !check.parent_skus.contains(sku) {
+                check.parent_skus.push(sku.clone());
+            }
+            if let Err(missing) = product.reserve(*quantity) {
+                check.shortages.push(format!("{} (short by {})", sku, missing));
+            }
+        } else {
+            check.missing_skus.push(sku.clone());
         }
-        let mut products_sale = Vec::new();
-        for (sku, quantity) in &input.product_skus_quantity {
-            if let Some(product) = products.iter().find(|p| *p.sku() == *sku) {
-                let mut product = product.clone();
-                products_sale.push((product.clone(), *quantity as u32));
-                product.set_stock(product.stock() - *quantity);
-                self.product_repository.save(&product).await?;
-            } else {
+    }
+    Ok(check)
+}
+
+/// Fetch the products of the (SKU, quantity) items of a sale, checking all of them exist
+/// and have enough stock. Fails with 409 listing every SKU short of stock.
+pub(crate) async fn prepare_sale_products(
+    product_repository: &(dyn ProductRepository + Send + Sync),
+    product_skus_quantity: &[(String, u32)],
+) -> Result<Vec<(Product, u32)>, SharedError> {
+    if product_skus_quantity.is_empty() {
+        return Err(SharedError::new("Sale quantities must be greater than 0", 400));
+    }
+    for (_, quantity) in product_skus_quantity {
+        SaleLine::validate_quantity(*quantity)?;
+    }
+    let check = check_sale_products(product_repository, product_skus_quantity).await?;
+    if let Some(sku) = check.missing_skus.first() {
+        return Err(SharedError::new(
+            format!("Product with SKU {} not found", sku).as_str(),
+            404,
+        ));
+    }
+    if let Some(sku) = check.archived_skus.first() {
+        return Err(SharedError::new(
+            format!("Product with SKU {} is archived", sku).as_str(),
+            400,
+        ));
+    }
+    if let Some(sku) = check.parent_skus.first() {
+        return Err(SharedError::new(
+            format!(
+                "Product with SKU {} has variants, one of them has to be sold",
+                sku
+            )
+            .as_str(),
+            400,
+        ));
+    }
+    if !check.shortages.is_empty() {
+        return Err(SharedError::new(
+            format!("Insufficient stock for: {}", check.shortages.join(", ")).as_str(),
+            409,
+        ));
+    }
+    Ok(check.products_sale)
+}
+
+/// Discounts for the sale lines from the automatic promotions available now plus the given
+/// coupons. Line promotions go first so sale promotions apply over the discounted amount.
+/// Fails with 400 on unknown or unavailable coupons.
+pub(crate) async fn apply_promotions(
+    promotion_repository: &(dyn PromotionRepository + Send + Sync),
+    lines: &[SaleLine],
+    coupon_codes: &[String],
+) -> Result<Vec<AppliedDiscount>, SharedError> {
+    let now = Utc::now();
+    let mut promotions = promotion_repository.get_automatic_available_at(&now).await;
+    for code in coupon_codes {
+        match promotion_repository.get_by_code(code).await {
+            Some(promotion) if promotion.is_available_at(&now) => {
+                if !promotions.iter().any(|p| p.id() == promotion.id()) {
+                    promotions.push(promotion);
+                }
+            }
+            _ => {
                 return Err(SharedError::new(
-                    format!("Product with SKU {} not found", sku).as_str(),
-                    404,
+                    format!("Coupon {} is not valid", code).as_str(),
+                    400,
                 ));
             }
         }
-        let sale = self
-            .sale_repository
-            .create(
-                &customer,
-                products_sale.iter().map(|(p, q)| (p, *q)).collect(),
-            )
-            .await?;
-        Ok(RegisterSaleOutput {
-            sale_id: sale.id(),
-            total_amount: sale.total_amount(),
-        })
     }
+    let (line_promotions, sale_promotions): (Vec<Promotion>, Vec<Promotion>) = promotions
+        .into_iter()
+        .partition(|p| p.scope() == PromotionScope::Line);
+    let mut applied: Vec<AppliedDiscount> = Vec::new();
+    for promotion in line_promotions.iter().chain(sale_promotions.iter()) {
+        let discounts = promotion.discounts_for(lines, &applied)?;
+        applied.extend(discounts);
+    }
+    Ok(applied)
 }
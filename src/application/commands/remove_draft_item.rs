@@ -0,0 +1,57 @@
+use crate::domain::repositories::DraftSaleRepository;
+use crate::domain::unit_of_work::{UnitOfWork, run_in_transaction};
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use std::sync::Arc;
+
+pub struct RemoveDraftItemCommand {
+    pub draft_sale_id: u32,
+    pub sku: String,
+    /// Units to take out, the whole item when missing
+    pub quantity: Option<u32>,
+}
+impl Input for RemoveDraftItemCommand {
+    type Output = ();
+}
+
+pub struct RemoveDraftItemCommandHandler {
+    pub draft_sale_repository: Arc<dyn DraftSaleRepository + Send + Sync>,
+    pub unit_of_work: Arc<dyn UnitOfWork + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<RemoveDraftItemCommand> for RemoveDraftItemCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<RemoveDraftItemCommand>,
+    ) -> Result<<RemoveDraftItemCommand as Input>::Output, SharedError> {
+        run_in_transaction(
+            self.unit_of_work.as_ref(),
+            &(self, input.as_ref()),
+            |tx, (handler, input)| {
+                Box::pin(async move {
+                    handler
+                        .draft_sale_repository
+                        .lock(tx, input.draft_sale_id)
+                        .await?;
+                    let mut draft = match handler
+                        .draft_sale_repository
+                        .get_by_id(input.draft_sale_id)
+                        .await
+                    {
+                        Some(draft) => draft,
+                        None => {
+                            return Err(SharedError::new(
+                                format!("Draft sale with ID {} not found", input.draft_sale_id)
+                                    .as_str(),
+                                404,
+                            ));
+                        }
+                    };
+                    draft.remove_item(&input.sku, input.quantity)?;
+                    handler.draft_sale_repository.save(tx, &draft).await
+                })
+            },
+        )
+        .await
+    }
+}
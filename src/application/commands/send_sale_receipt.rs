@@ -0,0 +1,84 @@
+use crate::application::services::{EmailMessage, Logger, Mailer, ReceiptFormat, ReceiptRenderer};
+use crate::domain::repositories::SaleRepository;
+use crate::domain::value_objects::ValueObject;
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use std::sync::Arc;
+
+/// Emails the receipt of a sale to its customer
+pub struct ReceiptEmailer {
+    pub sale_repository: Arc<dyn SaleRepository + Send + Sync>,
+    pub receipt_renderer: Arc<dyn ReceiptRenderer + Send + Sync>,
+    pub mailer: Arc<dyn Mailer + Send + Sync>,
+    pub logger: Arc<dyn Logger + Send + Sync>,
+}
+impl ReceiptEmailer {
+    pub async fn send(&self, sale_id: u32) -> Result<(), SharedError> {
+        let sale = match self.sale_repository.get_by_id(&sale_id).await {
+            Some(sale) => sale,
+            None => {
+                return Err(SharedError::new(
+                    &format!("Sale with ID {} not found", sale_id),
+                    404,
+                ));
+            }
+        };
+        let reference = match sale.invoice_number() {
+            Some(invoice_number) => invoice_number.to_string(),
+            None => format!("#{}", sale.id()),
+        };
+        let text_receipt = self
+            .receipt_renderer
+            .render(&sale, ReceiptFormat::Text, false)?;
+        let html_receipt = self
+            .receipt_renderer
+            .render(&sale, ReceiptFormat::Html, false)?;
+        let message = EmailMessage {
+            to: sale.customer().email().value().clone(),
+            subject: format!("Your receipt {}", reference),
+            text_body: format!(
+                "Hello {},\n\nThank you for your purchase, this is the receipt of sale {}.\n\n{}",
+                sale.customer().name(),
+                reference,
+                String::from_utf8_lossy(&text_receipt)
+            ),
+            html_body: Some(String::from_utf8_lossy(&html_receipt).into_owned()),
+        };
+        self.mailer.send(&message).await
+    }
+
+    /// Send the receipt in a background task, only logging failures so the caller never waits
+    /// on the mail server
+    pub fn send_in_background(self: &Arc<Self>, sale_id: u32) {
+        let emailer = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = emailer.send(sale_id).await {
+                emailer.logger.warn(&format!(
+                    "Receipt of sale {} could not be emailed: {}",
+                    sale_id, e
+                ));
+            }
+        });
+    }
+}
+
+/// Email the receipt of a sale again, waiting for the mail to be sent
+pub struct SendSaleReceiptCommand {
+    pub sale_id: u32,
+}
+impl Input for SendSaleReceiptCommand {
+    type Output = ();
+}
+
+pub struct SendSaleReceiptCommandHandler {
+    pub receipt_emailer: Arc<ReceiptEmailer>,
+}
+#[async_trait::async_trait]
+impl InputHandler<SendSaleReceiptCommand> for SendSaleReceiptCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<SendSaleReceiptCommand>,
+    ) -> Result<<SendSaleReceiptCommand as Input>::Output, SharedError> {
+        self.receipt_emailer.send(input.sale_id).await
+    }
+}
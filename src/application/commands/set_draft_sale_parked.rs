@@ -0,0 +1,60 @@
+use crate::domain::repositories::DraftSaleRepository;
+use crate::domain::unit_of_work::{UnitOfWork, run_in_transaction};
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use std::sync::Arc;
+
+/// Park an open draft to resume it later, or resume a parked one
+pub struct SetDraftSaleParkedCommand {
+    pub draft_sale_id: u32,
+    pub parked: bool,
+}
+impl Input for SetDraftSaleParkedCommand {
+    type Output = ();
+}
+
+pub struct SetDraftSaleParkedCommandHandler {
+    pub draft_sale_repository: Arc<dyn DraftSaleRepository + Send + Sync>,
+    pub unit_of_work: Arc<dyn UnitOfWork + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<SetDraftSaleParkedCommand> for SetDraftSaleParkedCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<SetDraftSaleParkedCommand>,
+    ) -> Result<<SetDraftSaleParkedCommand as Input>::Output, SharedError> {
+        run_in_transaction(
+            self.unit_of_work.as_ref(),
+            &(self, input.as_ref()),
+            |tx, (handler, input)| {
+                Box::pin(async move {
+                    handler
+                        .draft_sale_repository
+                        .lock(tx, input.draft_sale_id)
+                        .await?;
+                    let mut draft = match handler
+                        .draft_sale_repository
+                        .get_by_id(input.draft_sale_id)
+                        .await
+                    {
+                        Some(draft) => draft,
+                        None => {
+                            return Err(SharedError::new(
+                                format!("Draft sale with ID {} not found", input.draft_sale_id)
+                                    .as_str(),
+                                404,
+                            ));
+                        }
+                    };
+                    if input.parked {
+                        draft.park()?;
+                    } else {
+                        draft.resume()?;
+                    }
+                    handler.draft_sale_repository.save(tx, &draft).await
+                })
+            },
+        )
+        .await
+    }
+}
@@ -0,0 +1,50 @@
+use crate::domain::repositories::ProductRepository;
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use std::sync::Arc;
+
+/// Archive a product so it is no longer listed nor sold, or bring an archived one back.
+/// A parent product takes its variants along.
+pub struct SetProductArchivedCommand {
+    pub sku: String,
+    pub archived: bool,
+}
+impl Input for SetProductArchivedCommand {
+    type Output = ();
+}
+
+pub struct SetProductArchivedCommandHandler {
+    pub product_repository: Arc<dyn ProductRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<SetProductArchivedCommand> for SetProductArchivedCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<SetProductArchivedCommand>,
+    ) -> Result<<SetProductArchivedCommand as Input>::Output, SharedError> {
+        let mut product = match self.product_repository.get_by_sku(&input.sku).await {
+            Some(product) => product,
+            None => {
+                return Err(SharedError::new(
+                    format!("Product with SKU {} not found", input.sku).as_str(),
+                    404,
+                ));
+            }
+        };
+        if input.archived {
+            product.archive()?;
+        } else {
+            if let Some(variant) = product.variant() {
+                let parent = self.product_repository.get_by_id(variant.parent_id()).await;
+                if parent.is_some_and(|parent| parent.is_archived()) {
+                    return Err(SharedError::new(
+                        "The parent product is archived, unarchive it instead",
+                        409,
+                    ));
+                }
+            }
+            product.unarchive()?;
+        }
+        self.product_repository.save_archived(&product).await
+    }
+}
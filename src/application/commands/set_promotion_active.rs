@@ -0,0 +1,27 @@
+use crate::domain::repositories::PromotionRepository;
+use crate::shared::SharedError;
+use crate::shared::input_handler::{Input, InputHandler};
+use std::sync::Arc;
+
+pub struct SetPromotionActiveCommand {
+    pub promotion_id: u32,
+    pub active: bool,
+}
+impl Input for SetPromotionActiveCommand {
+    type Output = ();
+}
+
+pub struct SetPromotionActiveCommandHandler {
+    pub promotion_repository: Arc<dyn PromotionRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<SetPromotionActiveCommand> for SetPromotionActiveCommandHandler {
+    async fn handle(
+        &self,
+        input: Arc<SetPromotionActiveCommand>,
+    ) -> Result<<SetPromotionActiveCommand as Input>::Output, SharedError> {
+        self.promotion_repository
+            .set_active(input.promotion_id, input.active)
+            .await
+    }
+}
@@ -1,7 +1,11 @@
 use crate::domain::{
-    entities::{Customer, Product, Sale},
-    value_objects::ValueObject,
+    entities::{
+        AppliedDiscount, Category, Customer, DraftSale, ElectronicInvoice, InvoiceSeries, LineTax, Payment, Product, Promotion,
+        PromotionRule, ReturnLine, Sale, SaleLine, SaleReturn, StockMovement, TaxBreakdown,
+    },
+    value_objects::{Money, ValueObject},
 };
+use crate::shared::SharedError;
 
 #[derive(Debug, Clone)]
 pub struct CustomerDTO {
@@ -26,16 +30,44 @@ impl From<Customer> for CustomerDTO {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct CategoryDTO {
+    pub id: u32,
+    pub name: String,
+    pub parent_id: Option<u32>,
+}
+
+impl From<Category> for CategoryDTO {
+    fn from(category: Category) -> Self {
+        CategoryDTO {
+            id: category.id(),
+            name: category.name().clone(),
+            parent_id: category.parent_id(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ProductDTO {
     pub id: u32,
     pub sku: String,
     pub name: String,
-    pub price: f64,
+    pub price: Money,
+    pub tax_class: String,
+    pub tax_rate: f64,
     pub stock: u32,
-    pub flags: Vec<String>,
+    pub reorder_point: Option<u32>,
+    pub reorder_quantity: Option<u32>,
+    /// Set when the product is a variant
+    pub parent_id: Option<u32>,
+    pub size: Option<String>,
+    pub color: Option<String>,
+    pub price_override: bool,
+    pub has_variants: bool,
+    pub categories: Vec<CategoryDTO>,
     pub img_url: Option<String>,
     pub description: Option<String>,
+    pub archived_at: Option<String>,
 }
 
 impl From<Product> for ProductDTO {
@@ -45,35 +77,508 @@ impl From<Product> for ProductDTO {
             sku: product.sku().clone(),
             name: product.name().clone(),
             price: product.price(),
+            tax_class: product.tax_class().as_str().to_string(),
+            tax_rate: product.tax_class().rate(),
             stock: product.stock(),
-            flags: product.flags().clone(),
+            reorder_point: product.reorder_point(),
+            reorder_quantity: product.reorder_quantity(),
+            parent_id: product.variant().as_ref().map(|variant| variant.parent_id()),
+            size: product.variant().as_ref().and_then(|variant| variant.size().clone()),
+            color: product.variant().as_ref().and_then(|variant| variant.color().clone()),
+            price_override: product
+                .variant()
+                .as_ref()
+                .is_some_and(|variant| variant.price_override()),
+            has_variants: product.has_variants(),
+            categories: product
+                .categories()
+                .iter()
+                .cloned()
+                .map(CategoryDTO::from)
+                .collect(),
             img_url: product.img_url().as_ref().map(|url| url.value().clone()),
             description: product.description().clone(),
+            archived_at: product.archived_at().map(|at| at.to_rfc3339()),
         }
     }
 }
 
+/// Product with its variants, a parent shows the stock of all of them
+#[derive(Debug, Clone)]
+pub struct ProductDetailDTO {
+    pub product: ProductDTO,
+    pub variants: Vec<ProductDTO>,
+    pub total_stock: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct SaleLineDTO {
+    pub product: ProductDTO,
+    pub quantity: u32,
+    pub product_name: String,
+    pub unit_price: Money,
+    pub amount: Money,
+    pub tax_class: String,
+    pub tax_rate: f64,
+    /// Paid for the line after discounts, without and with only IVA
+    pub base_amount: Money,
+    pub tax_amount: Money,
+}
+
+impl TryFrom<(SaleLine, LineTax)> for SaleLineDTO {
+    type Error = SharedError;
+    fn try_from((line, line_tax): (SaleLine, LineTax)) -> Result<Self, SharedError> {
+        Ok(SaleLineDTO {
+            product: ProductDTO::from(line.product().clone()),
+            quantity: line.quantity(),
+            product_name: line.product_name().clone(),
+            unit_price: line.unit_price(),
+            amount: line.amount()?,
+            tax_class: line.tax_class().as_str().to_string(),
+            tax_rate: line.tax_rate(),
+            base_amount: line_tax.base(),
+            tax_amount: line_tax.tax(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TaxBreakdownDTO {
+    pub tax_class: String,
+    pub tax_rate: f64,
+    pub base_amount: Money,
+    pub tax_amount: Money,
+}
+
+impl From<TaxBreakdown> for TaxBreakdownDTO {
+    fn from(breakdown: TaxBreakdown) -> Self {
+        TaxBreakdownDTO {
+            tax_class: breakdown.tax_class().as_str().to_string(),
+            tax_rate: breakdown.tax_rate(),
+            base_amount: breakdown.base(),
+            tax_amount: breakdown.tax(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReturnLineDTO {
+    pub product_sku: String,
+    pub quantity: u32,
+    pub unit_price: Money,
+    pub amount: Money,
+}
+
+impl TryFrom<ReturnLine> for ReturnLineDTO {
+    type Error = SharedError;
+    fn try_from(line: ReturnLine) -> Result<Self, SharedError> {
+        Ok(ReturnLineDTO {
+            product_sku: line.product_sku().clone(),
+            quantity: line.quantity(),
+            unit_price: line.unit_price(),
+            amount: line.amount()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SaleReturnDTO {
+    pub id: u32,
+    pub products: Vec<ReturnLineDTO>,
+    pub reason: Option<String>,
+    pub refunded_amount: Money,
+    pub created_at: String,
+}
+
+impl TryFrom<SaleReturn> for SaleReturnDTO {
+    type Error = SharedError;
+    fn try_from(sale_return: SaleReturn) -> Result<Self, SharedError> {
+        Ok(SaleReturnDTO {
+            id: sale_return.id(),
+            products: sale_return
+                .lines()
+                .iter()
+                .map(|line| ReturnLineDTO::try_from(line.clone()))
+                .collect::<Result<Vec<ReturnLineDTO>, SharedError>>()?,
+            reason: sale_return.reason().clone(),
+            refunded_amount: sale_return.refunded_amount()?,
+            created_at: sale_return.created_at().to_rfc3339(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AppliedDiscountDTO {
+    pub promotion_id: Option<u32>,
+    pub product_sku: Option<String>,
+    pub description: String,
+    pub amount: Money,
+}
+
+impl From<AppliedDiscount> for AppliedDiscountDTO {
+    fn from(discount: AppliedDiscount) -> Self {
+        AppliedDiscountDTO {
+            promotion_id: discount.promotion_id(),
+            product_sku: discount.product_sku().clone(),
+            description: discount.description().clone(),
+            amount: discount.amount(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PaymentDTO {
+    pub method: String,
+    pub amount: Money,
+    pub tendered: Option<Money>,
+    pub change: Money,
+}
+
+impl TryFrom<Payment> for PaymentDTO {
+    type Error = SharedError;
+    fn try_from(payment: Payment) -> Result<Self, SharedError> {
+        Ok(PaymentDTO {
+            method: payment.method().as_str().to_string(),
+            amount: payment.amount(),
+            tendered: payment.tendered(),
+            change: payment.change()?,
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SaleDTO {
     pub id: u32,
+    pub invoice_number: Option<String>,
     pub customer: CustomerDTO,
-    pub products: Vec<(ProductDTO, u32)>,
-    pub total_amount: f64,
+    pub products: Vec<SaleLineDTO>,
+    pub subtotal: Money,
+    pub discount_amount: Money,
+    pub discounts: Vec<AppliedDiscountDTO>,
+    pub total_amount: Money,
+    pub base_amount: Money,
+    pub tax_amount: Money,
+    pub taxes: Vec<TaxBreakdownDTO>,
+    pub payments: Vec<PaymentDTO>,
+    pub change: Money,
+    pub refunded_amount: Money,
+    pub net_amount: Money,
+    pub returns: Vec<SaleReturnDTO>,
+    pub exchange_of: Option<u32>,
     pub generated_at: String,
+    pub status: String,
+    pub cancel_reason: Option<String>,
+    pub cancelled_at: Option<String>,
 }
 
-impl From<Sale> for SaleDTO {
-    fn from(sale: Sale) -> Self {
-        SaleDTO {
+impl TryFrom<Sale> for SaleDTO {
+    type Error = SharedError;
+    fn try_from(sale: Sale) -> Result<Self, SharedError> {
+        Ok(SaleDTO {
             id: sale.id(),
+            invoice_number: sale.invoice_number().as_ref().map(|n| n.to_string()),
             customer: CustomerDTO::from(sale.customer().clone()),
             products: sale
-                .products_sale()
+                .lines()
                 .iter()
-                .map(|(product, quantity)| (ProductDTO::from(product.clone()), *quantity as u32))
+                .zip(sale.line_taxes()?)
+                .map(|(line, line_tax)| SaleLineDTO::try_from((line.clone(), line_tax)))
+                .collect::<Result<Vec<SaleLineDTO>, SharedError>>()?,
+            subtotal: sale.subtotal()?,
+            discount_amount: sale.discount_amount()?,
+            discounts: sale
+                .discounts()
+                .iter()
+                .map(|d| AppliedDiscountDTO::from(d.clone()))
+                .collect(),
+            total_amount: sale.total_amount()?,
+            base_amount: sale.base_amount()?,
+            tax_amount: sale.tax_amount()?,
+            taxes: sale
+                .tax_breakdown()?
+                .into_iter()
+                .map(TaxBreakdownDTO::from)
                 .collect(),
-            total_amount: sale.total_amount(),
+            payments: sale
+                .payments()
+                .iter()
+                .map(|p| PaymentDTO::try_from(p.clone()))
+                .collect::<Result<Vec<PaymentDTO>, SharedError>>()?,
+            change: sale.change()?,
+            refunded_amount: sale.refunded_amount()?,
+            net_amount: sale.net_amount()?,
+            returns: sale
+                .returns()
+                .iter()
+                .map(|r| SaleReturnDTO::try_from(r.clone()))
+                .collect::<Result<Vec<SaleReturnDTO>, SharedError>>()?,
+            exchange_of: sale.exchange_of(),
             generated_at: sale.generated_at().to_rfc3339(),
+            status: sale.status().as_str().to_string(),
+            cancel_reason: sale.cancel_reason().clone(),
+            cancelled_at: sale.cancelled_at().map(|date| date.to_rfc3339()),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SalePricingDTO {
+    pub customer: CustomerDTO,
+    pub products: Vec<SaleLineDTO>,
+    pub subtotal: Money,
+    pub discount_amount: Money,
+    pub discounts: Vec<AppliedDiscountDTO>,
+    pub total_amount: Money,
+    pub base_amount: Money,
+    pub tax_amount: Money,
+    pub taxes: Vec<TaxBreakdownDTO>,
+    pub payments: Vec<PaymentDTO>,
+    pub change: Money,
+}
+
+/// Prices of a sale not registered yet
+impl TryFrom<Sale> for SalePricingDTO {
+    type Error = SharedError;
+    fn try_from(sale: Sale) -> Result<Self, SharedError> {
+        Ok(SalePricingDTO {
+            customer: CustomerDTO::from(sale.customer().clone()),
+            products: sale
+                .lines()
+                .iter()
+                .zip(sale.line_taxes()?)
+                .map(|(line, line_tax)| SaleLineDTO::try_from((line.clone(), line_tax)))
+                .collect::<Result<Vec<SaleLineDTO>, SharedError>>()?,
+            subtotal: sale.subtotal()?,
+            discount_amount: sale.discount_amount()?,
+            discounts: sale
+                .discounts()
+                .iter()
+                .map(|d| AppliedDiscountDTO::from(d.clone()))
+                .collect(),
+            total_amount: sale.total_amount()?,
+            base_amount: sale.base_amount()?,
+            tax_amount: sale.tax_amount()?,
+            taxes: sale
+                .tax_breakdown()?
+                .into_iter()
+                .map(TaxBreakdownDTO::from)
+                .collect(),
+            payments: sale
+                .payments()
+                .iter()
+                .map(|p| PaymentDTO::try_from(p.clone()))
+                .collect::<Result<Vec<PaymentDTO>, SharedError>>()?,
+            change: sale.change()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SalePreviewDTO {
+    /// Reasons the sale would be rejected, empty when it can be registered
+    pub problems: Vec<String>,
+    /// Missing when there is no customer to price the sale for
+    pub pricing: Option<SalePricingDTO>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DraftLineDTO {
+    pub product_sku: String,
+    pub product_name: String,
+    pub unit_price: Money,
+    pub quantity: u32,
+    pub amount: Money,
+    /// Units currently in stock, they are not reserved until checkout
+    pub stock: u32,
+}
+
+impl TryFrom<SaleLine> for DraftLineDTO {
+    type Error = SharedError;
+    fn try_from(line: SaleLine) -> Result<Self, SharedError> {
+        Ok(DraftLineDTO {
+            product_sku: line.product().sku().clone(),
+            product_name: line.product_name().clone(),
+            unit_price: line.unit_price(),
+            quantity: line.quantity(),
+            amount: line.amount()?,
+            stock: line.product().stock(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DraftSaleDTO {
+    pub id: u32,
+    pub customer: CustomerDTO,
+    pub status: String,
+    pub lines: Vec<DraftLineDTO>,
+    pub subtotal: Money,
+    pub discount_amount: Money,
+    pub discounts: Vec<AppliedDiscountDTO>,
+    pub total_amount: Money,
+    pub sale_id: Option<u32>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Draft with its lines priced at the current product prices and the automatic promotions
+impl TryFrom<(DraftSale, Vec<SaleLine>, Vec<AppliedDiscount>)> for DraftSaleDTO {
+    type Error = SharedError;
+    fn try_from(
+        data: (DraftSale, Vec<SaleLine>, Vec<AppliedDiscount>),
+    ) -> Result<Self, SharedError> {
+        let (draft, lines, discounts) = data;
+        let lines = lines
+            .into_iter()
+            .map(DraftLineDTO::try_from)
+            .collect::<Result<Vec<DraftLineDTO>, SharedError>>()?;
+        let subtotal = Money::checked_sum(lines.iter().map(|line| line.amount))?;
+        let discount_amount = Money::checked_sum(discounts.iter().map(|d| d.amount()))?;
+        Ok(DraftSaleDTO {
+            id: draft.id(),
+            customer: CustomerDTO::from(draft.customer().clone()),
+            status: draft.status().as_str().to_string(),
+            lines,
+            subtotal,
+            discount_amount,
+            discounts: discounts.into_iter().map(AppliedDiscountDTO::from).collect(),
+            total_amount: subtotal.checked_sub(discount_amount)?,
+            sale_id: draft.sale_id(),
+            created_at: draft.created_at().to_rfc3339(),
+            updated_at: draft.updated_at().to_rfc3339(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InvoiceSeriesDTO {
+    pub id: u32,
+    pub prefix: String,
+    pub range_from: u64,
+    pub range_to: u64,
+    pub next_number: u64,
+    pub remaining: u64,
+    pub warning_threshold: u64,
+    pub near_end: bool,
+    pub active: bool,
+    pub created_at: String,
+}
+
+impl From<InvoiceSeries> for InvoiceSeriesDTO {
+    fn from(series: InvoiceSeries) -> Self {
+        InvoiceSeriesDTO {
+            id: series.id(),
+            prefix: series.prefix().clone(),
+            range_from: series.range_from(),
+            range_to: series.range_to(),
+            next_number: series.next_number(),
+            remaining: series.remaining(),
+            warning_threshold: series.warning_threshold(),
+            near_end: series.is_near_end(),
+            active: series.active(),
+            created_at: series.created_at().to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ElectronicInvoiceDTO {
+    pub sale_id: u32,
+    pub invoice_number: String,
+    pub cufe: String,
+    pub status: String,
+    pub created_at: String,
+    pub submitted_at: Option<String>,
+}
+
+impl From<ElectronicInvoice> for ElectronicInvoiceDTO {
+    fn from(invoice: ElectronicInvoice) -> Self {
+        ElectronicInvoiceDTO {
+            sale_id: invoice.sale_id(),
+            invoice_number: invoice.invoice_number().clone(),
+            cufe: invoice.cufe().clone(),
+            status: invoice.status().as_str().to_string(),
+            created_at: invoice.created_at().to_rfc3339(),
+            submitted_at: invoice.submitted_at().map(|date| date.to_rfc3339()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StockMovementDTO {
+    pub id: u32,
+    pub product_id: u32,
+    pub delta: i64,
+    pub resulting_stock: u32,
+    pub reason: String,
+    pub adjustment_reason: Option<String>,
+    pub reference_id: Option<u32>,
+    pub user: String,
+    pub created_at: String,
+}
+
+impl From<StockMovement> for StockMovementDTO {
+    fn from(movement: StockMovement) -> Self {
+        StockMovementDTO {
+            id: movement.id(),
+            product_id: movement.product_id(),
+            delta: movement.delta(),
+            resulting_stock: movement.resulting_stock(),
+            reason: movement.reason().as_str().to_string(),
+            adjustment_reason: movement
+                .adjustment_reason()
+                .map(|reason| reason.as_str().to_string()),
+            reference_id: movement.reference_id(),
+            user: movement.user().clone(),
+            created_at: movement.created_at().to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PromotionDTO {
+    pub id: u32,
+    pub name: String,
+    pub code: Option<String>,
+    pub scope: String,
+    pub kind: String,
+    pub percentage: Option<f64>,
+    pub amount: Option<Money>,
+    pub buy_quantity: Option<u32>,
+    pub pay_quantity: Option<u32>,
+    pub category_id: Option<u32>,
+    pub starts_at: Option<String>,
+    pub ends_at: Option<String>,
+    pub active: bool,
+    pub created_at: String,
+}
+
+impl From<Promotion> for PromotionDTO {
+    fn from(promotion: Promotion) -> Self {
+        let (kind, percentage, amount, buy_quantity, pay_quantity) = match promotion.rule() {
+            PromotionRule::Percentage(percentage) => {
+                ("percentage", Some(percentage), None, None, None)
+            }
+            PromotionRule::FixedAmount(amount) => ("fixed_amount", None, Some(amount), None, None),
+            PromotionRule::BuyXPayY { buy, pay } => {
+                ("buy_x_pay_y", None, None, Some(buy), Some(pay))
+            }
+        };
+        PromotionDTO {
+            id: promotion.id(),
+            name: promotion.name().clone(),
+            code: promotion.code().clone(),
+            scope: promotion.scope().as_str().to_string(),
+            kind: kind.to_string(),
+            percentage,
+            amount,
+            buy_quantity,
+            pay_quantity,
+            category_id: promotion.category_id(),
+            starts_at: promotion.starts_at().map(|date| date.to_rfc3339()),
+            ends_at: promotion.ends_at().map(|date| date.to_rfc3339()),
+            active: promotion.active(),
+            created_at: promotion.created_at().to_rfc3339(),
         }
     }
 }
@@ -0,0 +1,40 @@
+use crate::{
+    application::dtos::ProductDTO,
+    shared::{
+        SharedError,
+        input_handler::{Input, InputHandler},
+    },
+};
+use std::sync::Arc;
+
+use crate::domain::repositories::ProductRepository;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 50;
+
+/// Top products matching what is being typed at the POS, by SKU or name
+pub struct AutocompleteProductsQuery {
+    pub term: String,
+    pub limit: Option<u32>,
+}
+impl Input for AutocompleteProductsQuery {
+    type Output = Vec<ProductDTO>;
+}
+pub struct AutocompleteProductsQueryHandler {
+    pub product_repository: Arc<dyn ProductRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<AutocompleteProductsQuery> for AutocompleteProductsQueryHandler {
+    async fn handle(
+        &self,
+        input: Arc<AutocompleteProductsQuery>,
+    ) -> Result<<AutocompleteProductsQuery as Input>::Output, SharedError> {
+        let term = input.term.trim();
+        if term.is_empty() {
+            return Ok(Vec::new());
+        }
+        let limit = input.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        let products = self.product_repository.autocomplete(term, limit).await;
+        Ok(products.into_iter().map(ProductDTO::from).collect())
+    }
+}
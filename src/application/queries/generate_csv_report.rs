@@ -1,7 +1,7 @@
 use crate::{domain::value_objects::ValueObject, shared::{input_handler::{Input, InputHandler}, Pagination, PaginationResult, SharedError}};
 use std::sync::Arc;
 
-use crate::domain::{entities::Sale, repositories::SaleRepository};
+use crate::domain::{entities::Sale, repositories::SaleRepository, value_objects::Money};
 
 pub struct GenerateCsvReportQuery;
 pub struct GenerateCsvReportOutput {
@@ -20,23 +20,47 @@ impl InputHandler<GenerateCsvReportQuery> for GenerateCsvReportQueryHandler {
         &self,
         _: Arc<GenerateCsvReportQuery>,
     ) -> Result<<GenerateCsvReportQuery as Input>::Output, SharedError> {
-        let mut csv_data = String::from("Sale ID, Generated At, Customer CC, Products(Product SKU:Quantity), Total Amount\n");
+        let mut csv_data = String::from("Sale ID, Generated At, Customer CC, Status, Products(Product SKU:Quantity:Unit Price), Subtotal, Discount, Total Amount, Base Amount, Tax Amount, Taxes(Tax Class:Rate:Base:Tax), Payments(Method:Amount), Refunded Amount, Net Amount, Exchange Of\n");
         let sales = self.sale_repository.get_all().await;
+        // Cancelled sales are listed but left out of the total, which is net of refunds
+        let mut total_amount = Money::zero();
         for sale in sales {
-            let products_str = sale.products_sale().iter()
-                .map(|(p, quantity)| format!("{}:{}", p.sku(), quantity))
+            let products_str = sale.lines().iter()
+                .map(|line| format!("{}:{}:{}", line.product().sku(), line.quantity(), line.unit_price()))
+                .collect::<Vec<String>>()
+                .join("&");
+            let taxes_str = sale.tax_breakdown()?.iter()
+                .map(|t| format!("{}:{}:{}:{}", t.tax_class().as_str(), t.tax_rate(), t.base(), t.tax()))
+                .collect::<Vec<String>>()
+                .join("&");
+            let payments_str = sale.payments().iter()
+                .map(|p| format!("{}:{}", p.method().as_str(), p.amount()))
                 .collect::<Vec<String>>()
                 .join("&");
             let line = format!(
-                "{},{},{},{},{}\n",
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}\n",
                 sale.id(),
                 sale.generated_at(),
                 sale.customer().cc().value(),
+                sale.status().as_str(),
                 products_str,
-                sale.total_amount()
+                sale.subtotal()?,
+                sale.discount_amount()?,
+                sale.total_amount()?,
+                sale.base_amount()?,
+                sale.tax_amount()?,
+                taxes_str,
+                payments_str,
+                sale.refunded_amount()?,
+                sale.net_amount()?,
+                sale.exchange_of().map(|id| id.to_string()).unwrap_or_default()
             );
             csv_data.push_str(&line);
+            if !sale.is_cancelled() {
+                total_amount = total_amount.checked_add(sale.net_amount()?)?;
+            }
         }
+        csv_data.push_str(&format!("Total,,,,,,,,,,,,,{},\n", total_amount));
         Ok(GenerateCsvReportOutput { csv_data } )
     }
 }
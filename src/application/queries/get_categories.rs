@@ -0,0 +1,28 @@
+use crate::{
+    application::dtos::CategoryDTO,
+    shared::{
+        SharedError,
+        input_handler::{Input, InputHandler},
+    },
+};
+use std::sync::Arc;
+
+use crate::domain::repositories::CategoryRepository;
+
+pub struct GetCategoriesQuery {}
+impl Input for GetCategoriesQuery {
+    type Output = Vec<CategoryDTO>;
+}
+pub struct GetCategoriesQueryHandler {
+    pub category_repository: Arc<dyn CategoryRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<GetCategoriesQuery> for GetCategoriesQueryHandler {
+    async fn handle(
+        &self,
+        _input: Arc<GetCategoriesQuery>,
+    ) -> Result<<GetCategoriesQuery as Input>::Output, SharedError> {
+        let categories = self.category_repository.get_all().await;
+        Ok(categories.into_iter().map(CategoryDTO::from).collect())
+    }
+}
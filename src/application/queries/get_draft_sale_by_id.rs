@@ -0,0 +1,80 @@
+use crate::{
+    application::{commands::apply_promotions, dtos::DraftSaleDTO},
+    domain::{
+        entities::{DraftSale, SaleLine},
+        repositories::{DraftSaleRepository, ProductRepository, PromotionRepository},
+    },
+    shared::{
+        SharedError,
+        input_handler::{Input, InputHandler},
+    },
+};
+use std::sync::Arc;
+
+pub struct GetDraftSaleByIdQuery {
+    pub draft_sale_id: u32,
+}
+impl Input for GetDraftSaleByIdQuery {
+    type Output = DraftSaleDTO;
+}
+pub struct GetDraftSaleByIdQueryHandler {
+    pub product_repository: Arc<dyn ProductRepository + Send + Sync>,
+    pub promotion_repository: Arc<dyn PromotionRepository + Send + Sync>,
+    pub draft_sale_repository: Arc<dyn DraftSaleRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<GetDraftSaleByIdQuery> for GetDraftSaleByIdQueryHandler {
+    async fn handle(
+        &self,
+        input: Arc<GetDraftSaleByIdQuery>,
+    ) -> Result<<GetDraftSaleByIdQuery as Input>::Output, SharedError> {
+        match self
+            .draft_sale_repository
+            .get_by_id(input.draft_sale_id)
+            .await
+        {
+            Some(draft) => {
+                priced_draft(
+                    self.product_repository.as_ref(),
+                    self.promotion_repository.as_ref(),
+                    draft,
+                )
+                .await
+            }
+            None => Err(SharedError::new(
+                &format!("Draft sale with ID {} not found", input.draft_sale_id),
+                404,
+            )),
+        }
+    }
+}
+
+/// Draft priced with the current product prices and the automatic promotions available now,
+/// coupons are only applied on checkout
+pub(super) async fn priced_draft(
+    product_repository: &(dyn ProductRepository + Send + Sync),
+    promotion_repository: &(dyn PromotionRepository + Send + Sync),
+    draft: DraftSale,
+) -> Result<DraftSaleDTO, SharedError> {
+    let products = product_repository
+        .get_many_by_skus(
+            draft
+                .items()
+                .iter()
+                .map(|item| item.product_sku().as_str())
+                .collect(),
+        )
+        .await?;
+    let lines: Vec<SaleLine> = draft
+        .items()
+        .iter()
+        .filter_map(|item| {
+            products
+                .iter()
+                .find(|p| p.sku() == item.product_sku())
+                .map(|p| SaleLine::from_product(p.clone(), item.quantity()))
+        })
+        .collect();
+    let discounts = apply_promotions(promotion_repository, &lines, &[]).await?;
+    DraftSaleDTO::try_from((draft, lines, discounts))
+}
@@ -0,0 +1,55 @@
+use crate::{
+    application::dtos::DraftSaleDTO,
+    domain::{
+        entities::DraftSaleStatus,
+        repositories::{DraftSaleRepository, ProductRepository, PromotionRepository},
+    },
+    shared::{
+        SharedError,
+        input_handler::{Input, InputHandler},
+    },
+};
+use std::sync::Arc;
+
+use super::get_draft_sale_by_id::priced_draft;
+
+pub struct GetDraftSalesQuery {
+    /// "open" or "parked", both of them when missing
+    pub status: Option<String>,
+}
+impl Input for GetDraftSalesQuery {
+    type Output = Vec<DraftSaleDTO>;
+}
+pub struct GetDraftSalesQueryHandler {
+    pub product_repository: Arc<dyn ProductRepository + Send + Sync>,
+    pub promotion_repository: Arc<dyn PromotionRepository + Send + Sync>,
+    pub draft_sale_repository: Arc<dyn DraftSaleRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<GetDraftSalesQuery> for GetDraftSalesQueryHandler {
+    async fn handle(
+        &self,
+        input: Arc<GetDraftSalesQuery>,
+    ) -> Result<<GetDraftSalesQuery as Input>::Output, SharedError> {
+        let statuses = match &input.status {
+            Some(status) => vec![DraftSaleStatus::parse(status)?],
+            None => vec![DraftSaleStatus::Open, DraftSaleStatus::Parked],
+        };
+        let drafts = self
+            .draft_sale_repository
+            .get_all_by_status(&statuses)
+            .await;
+        let mut priced_drafts = Vec::with_capacity(drafts.len());
+        for draft in drafts {
+            priced_drafts.push(
+                priced_draft(
+                    self.product_repository.as_ref(),
+                    self.promotion_repository.as_ref(),
+                    draft,
+                )
+                .await?,
+            );
+        }
+        Ok(priced_drafts)
+    }
+}
@@ -0,0 +1,46 @@
+use crate::shared::{
+    SharedError,
+    input_handler::{Input, InputHandler},
+};
+use std::sync::Arc;
+
+use crate::domain::repositories::ElectronicInvoiceRepository;
+
+pub struct GetElectronicInvoiceQuery {
+    pub sale_id: u32,
+}
+pub struct GetElectronicInvoiceOutput {
+    pub invoice_number: String,
+    pub xml: String,
+}
+impl Input for GetElectronicInvoiceQuery {
+    type Output = GetElectronicInvoiceOutput;
+}
+pub struct GetElectronicInvoiceQueryHandler {
+    pub electronic_invoice_repository: Arc<dyn ElectronicInvoiceRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<GetElectronicInvoiceQuery> for GetElectronicInvoiceQueryHandler {
+    async fn handle(
+        &self,
+        input: Arc<GetElectronicInvoiceQuery>,
+    ) -> Result<<GetElectronicInvoiceQuery as Input>::Output, SharedError> {
+        match self
+            .electronic_invoice_repository
+            .get_by_sale_id(input.sale_id)
+            .await
+        {
+            Some(invoice) => Ok(GetElectronicInvoiceOutput {
+                invoice_number: invoice.invoice_number().clone(),
+                xml: invoice.xml().clone(),
+            }),
+            None => Err(SharedError::new(
+                &format!(
+                    "Sale with ID {} has no electronic invoice generated",
+                    input.sale_id
+                ),
+                404,
+            )),
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use crate::{
+    application::dtos::InvoiceSeriesDTO,
+    shared::{
+        SharedError,
+        input_handler::{Input, InputHandler},
+    },
+};
+use std::sync::Arc;
+
+use crate::domain::repositories::InvoiceSeriesRepository;
+
+pub struct GetInvoiceSeriesQuery {}
+impl Input for GetInvoiceSeriesQuery {
+    type Output = Vec<InvoiceSeriesDTO>;
+}
+pub struct GetInvoiceSeriesQueryHandler {
+    pub invoice_series_repository: Arc<dyn InvoiceSeriesRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<GetInvoiceSeriesQuery> for GetInvoiceSeriesQueryHandler {
+    async fn handle(
+        &self,
+        _input: Arc<GetInvoiceSeriesQuery>,
+    ) -> Result<<GetInvoiceSeriesQuery as Input>::Output, SharedError> {
+        let series = self.invoice_series_repository.get_all().await;
+        Ok(series.into_iter().map(InvoiceSeriesDTO::from).collect())
+    }
+}
@@ -0,0 +1,29 @@
+use crate::{
+    application::dtos::ProductDTO,
+    shared::{
+        SharedError,
+        input_handler::{Input, InputHandler},
+    },
+};
+use std::sync::Arc;
+
+use crate::domain::repositories::ProductRepository;
+
+/// Products at or below their reorder point, lowest stock first
+pub struct GetLowStockProductsQuery {}
+impl Input for GetLowStockProductsQuery {
+    type Output = Vec<ProductDTO>;
+}
+pub struct GetLowStockProductsQueryHandler {
+    pub product_repository: Arc<dyn ProductRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<GetLowStockProductsQuery> for GetLowStockProductsQueryHandler {
+    async fn handle(
+        &self,
+        _input: Arc<GetLowStockProductsQuery>,
+    ) -> Result<<GetLowStockProductsQuery as Input>::Output, SharedError> {
+        let products = self.product_repository.get_low_stock().await;
+        Ok(products.into_iter().map(ProductDTO::from).collect())
+    }
+}
@@ -0,0 +1,57 @@
+use crate::shared::{
+    SharedError,
+    input_handler::{Input, InputHandler},
+};
+use std::sync::Arc;
+
+use crate::domain::{
+    entities::PaymentMethod, repositories::SaleRepository, value_objects::Money,
+};
+
+pub struct GetPaymentTotalsQuery;
+pub struct GetPaymentTotalsOutput {
+    /// (method, amount paid with it), for every method
+    pub totals: Vec<(String, Money)>,
+    pub change: Money,
+}
+impl Input for GetPaymentTotalsQuery {
+    type Output = GetPaymentTotalsOutput;
+}
+
+pub struct GetPaymentTotalsQueryHandler {
+    pub sale_repository: Arc<dyn SaleRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<GetPaymentTotalsQuery> for GetPaymentTotalsQueryHandler {
+    async fn handle(
+        &self,
+        _: Arc<GetPaymentTotalsQuery>,
+    ) -> Result<<GetPaymentTotalsQuery as Input>::Output, SharedError> {
+        let sales = self.sale_repository.get_all().await;
+        // Cancelled sales are left out, as in the CSV report
+        let payments = sales
+            .iter()
+            .filter(|sale| !sale.is_cancelled())
+            .flat_map(|sale| sale.payments().iter())
+            .collect::<Vec<_>>();
+        let totals = PaymentMethod::ALL
+            .iter()
+            .map(|method| {
+                let amount = Money::checked_sum(
+                    payments
+                        .iter()
+                        .filter(|p| p.method() == *method)
+                        .map(|p| p.amount()),
+                )?;
+                Ok((method.as_str().to_string(), amount))
+            })
+            .collect::<Result<_, SharedError>>()?;
+        let change = Money::checked_sum(
+            payments
+                .iter()
+                .map(|p| p.change())
+                .collect::<Result<Vec<Money>, SharedError>>()?,
+        )?;
+        Ok(GetPaymentTotalsOutput { totals, change })
+    }
+}
@@ -0,0 +1,63 @@
+use crate::{
+    application::dtos::{ProductDTO, ProductDetailDTO},
+    shared::{
+        SharedError,
+        input_handler::{Input, InputHandler},
+    },
+};
+use std::sync::Arc;
+
+use crate::domain::repositories::ProductRepository;
+
+/// Product with all its variants, the parent is returned when the SKU is of a variant
+pub struct GetProductDetailQuery {
+    pub sku: String,
+}
+
+impl Input for GetProductDetailQuery {
+    type Output = ProductDetailDTO;
+}
+pub struct GetProductDetailQueryHandler {
+    pub product_repository: Arc<dyn ProductRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<GetProductDetailQuery> for GetProductDetailQueryHandler {
+    async fn handle(
+        &self,
+        input: Arc<GetProductDetailQuery>,
+    ) -> Result<<GetProductDetailQuery as Input>::Output, SharedError> {
+        let mut product = match self.product_repository.get_by_sku(&input.sku).await {
+            Some(product) => product,
+            None => {
+                return Err(SharedError::new(
+                    &format!("Product with SKU {} not found", input.sku),
+                    404,
+                ));
+            }
+        };
+        if let Some(variant) = product.variant() {
+            product = match self.product_repository.get_by_id(variant.parent_id()).await {
+                Some(parent) => parent,
+                None => {
+                    return Err(SharedError::new(
+                        &format!("Parent product of SKU {} not found", input.sku),
+                        404,
+                    ));
+                }
+            };
+        }
+        let variants = match product.has_variants() {
+            true => self.product_repository.get_variants(product.id()).await,
+            false => Vec::new(),
+        };
+        let total_stock = match product.has_variants() {
+            true => variants.iter().map(|variant| variant.stock()).sum(),
+            false => product.stock(),
+        };
+        Ok(ProductDetailDTO {
+            product: ProductDTO::from(product),
+            variants: variants.into_iter().map(ProductDTO::from).collect(),
+            total_stock,
+        })
+    }
+}
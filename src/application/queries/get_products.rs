@@ -7,10 +7,25 @@ use crate::{
 };
 use std::sync::Arc;
 
-use crate::domain::{entities::Product, repositories::ProductRepository};
+use crate::domain::{
+    entities::{Category, Product},
+    repositories::{CategoryRepository, ProductFilter, ProductRepository, ProductSort},
+    value_objects::Money,
+};
 
 pub struct GetProductsQuery {
     pub pagination: Pagination,
+    /// Only products in the category or any of its subcategories
+    pub category: Option<u32>,
+    /// Part of the SKU or the name
+    pub search: Option<String>,
+    pub min_price: Option<Money>,
+    pub max_price: Option<Money>,
+    pub in_stock: bool,
+    /// "name", "price", "stock" or "newest", by id when not given
+    pub sort: Option<String>,
+    /// Only archived products instead of the active ones
+    pub archived: bool,
 }
 pub struct GetProductsOutput {
     pub pagination_result: PaginationResult<ProductDTO>,
@@ -21,6 +36,7 @@ impl Input for GetProductsQuery {
 }
 pub struct GetProductsQueryHandler {
     pub product_repository: Arc<dyn ProductRepository + Send + Sync>,
+    pub category_repository: Arc<dyn CategoryRepository + Send + Sync>,
 }
 #[async_trait::async_trait]
 impl InputHandler<GetProductsQuery> for GetProductsQueryHandler {
@@ -28,9 +44,46 @@ impl InputHandler<GetProductsQuery> for GetProductsQueryHandler {
         &self,
         input: Arc<GetProductsQuery>,
     ) -> Result<<GetProductsQuery as Input>::Output, SharedError> {
+        if let (Some(min_price), Some(max_price)) = (input.min_price, input.max_price)
+            && min_price > max_price
+        {
+            return Err(SharedError::new(
+                "Minimum price cannot be greater than maximum price",
+                400,
+            ));
+        }
+        let mut filter = ProductFilter {
+            search: input
+                .search
+                .as_ref()
+                .map(|search| search.trim().to_string())
+                .filter(|search| !search.is_empty()),
+            category_ids: None,
+            min_price: input.min_price,
+            max_price: input.max_price,
+            in_stock: input.in_stock,
+            sort: match &input.sort {
+                Some(sort) => Some(ProductSort::parse(sort)?),
+                None => None,
+            },
+            archived: input.archived,
+        };
+        if let Some(category_id) = input.category {
+            let categories = self.category_repository.get_all().await;
+            if !categories
+                .iter()
+                .any(|category| category.id() == category_id)
+            {
+                return Err(SharedError::new(
+                    format!("Category with ID {} not found", category_id).as_str(),
+                    404,
+                ));
+            }
+            filter.category_ids = Some(Category::subtree_ids(&categories, category_id));
+        }
         let products = self
             .product_repository
-            .get_paginated(&input.pagination)
+            .get_paginated(&input.pagination, &filter)
             .await;
         Ok(GetProductsOutput {
             pagination_result: PaginationResult::from_other::<ProductDTO, Product>(&products),
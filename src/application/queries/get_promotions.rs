@@ -0,0 +1,41 @@
+use crate::{
+    application::dtos::PromotionDTO,
+    shared::{
+        Pagination, PaginationResult, SharedError,
+        input_handler::{Input, InputHandler},
+    },
+};
+use std::sync::Arc;
+
+use crate::domain::{entities::Promotion, repositories::PromotionRepository};
+
+pub struct GetPromotionsQuery {
+    pub pagination: Pagination,
+}
+pub struct GetPromotionsOutput {
+    pub pagination_result: PaginationResult<PromotionDTO>,
+}
+
+impl Input for GetPromotionsQuery {
+    type Output = GetPromotionsOutput;
+}
+pub struct GetPromotionsQueryHandler {
+    pub promotion_repository: Arc<dyn PromotionRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<GetPromotionsQuery> for GetPromotionsQueryHandler {
+    async fn handle(
+        &self,
+        input: Arc<GetPromotionsQuery>,
+    ) -> Result<<GetPromotionsQuery as Input>::Output, SharedError> {
+        let promotions = self
+            .promotion_repository
+            .get_paginated(&input.pagination)
+            .await;
+        Ok(GetPromotionsOutput {
+            pagination_result: PaginationResult::from_other::<PromotionDTO, Promotion>(
+                &promotions,
+            ),
+        })
+    }
+}
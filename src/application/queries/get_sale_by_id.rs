@@ -26,7 +26,7 @@ impl InputHandler<GetSaleByIdQuery> for GetSaleByIdQueryHandler {
     ) -> Result<<GetSaleByIdQuery as Input>::Output, SharedError> {
         let sale_option = self.sale_repository.get_by_id(&input.sale_id).await;
         match sale_option {
-            Some(sale) => Ok(SaleDTO::from(sale)),
+            Some(sale) => SaleDTO::try_from(sale),
             None => Err(SharedError::new(
                 &format!("Sale with ID {} not found", input.sale_id),
                 404,
@@ -0,0 +1,42 @@
+use crate::{
+    application::dtos::SaleDTO,
+    domain::value_objects::InvoiceNumber,
+    shared::{
+        SharedError,
+        input_handler::{Input, InputHandler},
+    },
+};
+use std::sync::Arc;
+
+use crate::domain::repositories::SaleRepository;
+
+pub struct GetSaleByInvoiceNumberQuery {
+    /// Full invoice number, prefix included, e.g. "FV1024"
+    pub invoice_number: String,
+}
+impl Input for GetSaleByInvoiceNumberQuery {
+    type Output = SaleDTO;
+}
+pub struct GetSaleByInvoiceNumberQueryHandler {
+    pub sale_repository: Arc<dyn SaleRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<GetSaleByInvoiceNumberQuery> for GetSaleByInvoiceNumberQueryHandler {
+    async fn handle(
+        &self,
+        input: Arc<GetSaleByInvoiceNumberQuery>,
+    ) -> Result<<GetSaleByInvoiceNumberQuery as Input>::Output, SharedError> {
+        let invoice_number = InvoiceNumber::parse(&input.invoice_number)?;
+        match self
+            .sale_repository
+            .get_by_invoice_number(&invoice_number)
+            .await
+        {
+            Some(sale) => SaleDTO::try_from(sale),
+            None => Err(SharedError::new(
+                &format!("Sale with invoice number {} not found", invoice_number),
+                404,
+            )),
+        }
+    }
+}
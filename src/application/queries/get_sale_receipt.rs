@@ -0,0 +1,51 @@
+use crate::{
+    application::services::{ReceiptFormat, ReceiptRenderer},
+    shared::{
+        SharedError,
+        input_handler::{Input, InputHandler},
+    },
+};
+use std::sync::Arc;
+
+use crate::domain::repositories::SaleRepository;
+
+pub struct GetSaleReceiptQuery {
+    pub sale_id: u32,
+    /// "escpos", "text" or "html"
+    pub format: String,
+    /// Gift receipts leave out prices, totals and payments
+    pub gift: bool,
+}
+pub struct GetSaleReceiptOutput {
+    pub content: Vec<u8>,
+    pub content_type: &'static str,
+}
+impl Input for GetSaleReceiptQuery {
+    type Output = GetSaleReceiptOutput;
+}
+pub struct GetSaleReceiptQueryHandler {
+    pub sale_repository: Arc<dyn SaleRepository + Send + Sync>,
+    pub receipt_renderer: Arc<dyn ReceiptRenderer + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<GetSaleReceiptQuery> for GetSaleReceiptQueryHandler {
+    async fn handle(
+        &self,
+        input: Arc<GetSaleReceiptQuery>,
+    ) -> Result<<GetSaleReceiptQuery as Input>::Output, SharedError> {
+        let format = ReceiptFormat::parse(&input.format)?;
+        let sale = match self.sale_repository.get_by_id(&input.sale_id).await {
+            Some(sale) => sale,
+            None => {
+                return Err(SharedError::new(
+                    &format!("Sale with ID {} not found", input.sale_id),
+                    404,
+                ));
+            }
+        };
+        Ok(GetSaleReceiptOutput {
+            content: self.receipt_renderer.render(&sale, format, input.gift)?,
+            content_type: format.content_type(),
+        })
+    }
+}
@@ -7,7 +7,7 @@ use crate::{
 };
 use std::sync::Arc;
 
-use crate::domain::{entities::Sale, repositories::SaleRepository};
+use crate::domain::repositories::SaleRepository;
 
 pub struct GetSalesQuery {
     pub pagination: Pagination,
@@ -29,8 +29,19 @@ impl InputHandler<GetSalesQuery> for GetSalesQueryHandler {
         input: Arc<GetSalesQuery>,
     ) -> Result<<GetSalesQuery as Input>::Output, SharedError> {
         let sales = self.sale_repository.get_paginated(&input.pagination).await;
+        let items = sales
+            .items
+            .iter()
+            .map(|sale| SaleDTO::try_from(sale.clone()))
+            .collect::<Result<Vec<SaleDTO>, SharedError>>()?;
         Ok(GetSalesOutput {
-            pagination_result: PaginationResult::from_other::<SaleDTO, Sale>(&sales),
+            pagination_result: PaginationResult {
+                items,
+                items_count: sales.items_count,
+                total_items: sales.total_items,
+                current_page: sales.current_page,
+                per_page: sales.per_page,
+            },
         })
     }
 }
@@ -0,0 +1,56 @@
+use crate::{
+    application::dtos::StockMovementDTO,
+    shared::{
+        Pagination, PaginationResult, SharedError,
+        input_handler::{Input, InputHandler},
+    },
+};
+use std::sync::Arc;
+
+use crate::domain::{
+    entities::StockMovement,
+    repositories::{ProductRepository, StockMovementRepository},
+};
+
+/// Stock ledger of a product, newest movements first
+pub struct GetStockMovementsQuery {
+    pub sku: String,
+    pub pagination: Pagination,
+}
+pub struct GetStockMovementsOutput {
+    pub pagination_result: PaginationResult<StockMovementDTO>,
+}
+
+impl Input for GetStockMovementsQuery {
+    type Output = GetStockMovementsOutput;
+}
+pub struct GetStockMovementsQueryHandler {
+    pub product_repository: Arc<dyn ProductRepository + Send + Sync>,
+    pub stock_movement_repository: Arc<dyn StockMovementRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<GetStockMovementsQuery> for GetStockMovementsQueryHandler {
+    async fn handle(
+        &self,
+        input: Arc<GetStockMovementsQuery>,
+    ) -> Result<<GetStockMovementsQuery as Input>::Output, SharedError> {
+        let product = match self.product_repository.get_by_sku(&input.sku).await {
+            Some(product) => product,
+            None => {
+                return Err(SharedError::new(
+                    &format!("Product with SKU {} not found", input.sku),
+                    404,
+                ));
+            }
+        };
+        let movements = self
+            .stock_movement_repository
+            .get_paginated_by_product(product.id(), &input.pagination)
+            .await;
+        Ok(GetStockMovementsOutput {
+            pagination_result: PaginationResult::from_other::<StockMovementDTO, StockMovement>(
+                &movements,
+            ),
+        })
+    }
+}
@@ -1,17 +1,47 @@
+mod autocomplete_products;
 mod generate_csv_report;
+mod get_categories;
 mod get_customer_by_cc;
 mod get_customers;
+mod get_draft_sale_by_id;
+mod get_draft_sales;
+mod get_electronic_invoice;
+mod get_invoice_series;
+mod get_low_stock_products;
+mod get_payment_totals;
 mod get_product_by_sku;
+mod get_product_detail;
 mod get_products;
+mod get_promotions;
 mod get_sale_by_id;
+mod get_sale_by_invoice_number;
+mod get_sale_receipt;
 mod get_sales;
+mod get_stock_movements;
 mod login;
+mod preview_sale;
 
+pub use autocomplete_products::{AutocompleteProductsQuery, AutocompleteProductsQueryHandler};
 pub use generate_csv_report::{GenerateCsvReportQuery, GenerateCsvReportQueryHandler};
+pub use get_categories::{GetCategoriesQuery, GetCategoriesQueryHandler};
 pub use get_customer_by_cc::{GetCustomerByCcQuery, GetCustomerByCcQueryHandler};
 pub use get_customers::{GetCustomersQuery, GetCustomersQueryHandler};
+pub use get_draft_sale_by_id::{GetDraftSaleByIdQuery, GetDraftSaleByIdQueryHandler};
+pub use get_draft_sales::{GetDraftSalesQuery, GetDraftSalesQueryHandler};
+pub use get_electronic_invoice::{GetElectronicInvoiceQuery, GetElectronicInvoiceQueryHandler};
+pub use get_invoice_series::{GetInvoiceSeriesQuery, GetInvoiceSeriesQueryHandler};
+pub use get_low_stock_products::{GetLowStockProductsQuery, GetLowStockProductsQueryHandler};
+pub use get_payment_totals::{GetPaymentTotalsQuery, GetPaymentTotalsQueryHandler};
 pub use get_product_by_sku::{GetProductBySkuQuery, GetProductsBySkuQueryHandler};
+pub use get_product_detail::{GetProductDetailQuery, GetProductDetailQueryHandler};
 pub use get_products::{GetProductsQuery, GetProductsQueryHandler};
+pub use get_promotions::{GetPromotionsQuery, GetPromotionsQueryHandler};
 pub use get_sale_by_id::{GetSaleByIdQuery, GetSaleByIdQueryHandler};
+pub use get_sale_by_invoice_number::{
+    GetSaleByInvoiceNumberQuery, GetSaleByInvoiceNumberQueryHandler,
+};
+pub use get_sale_receipt::{GetSaleReceiptQuery, GetSaleReceiptQueryHandler};
 pub use get_sales::{GetSalesQuery, GetSalesQueryHandler};
+pub use get_stock_movements::{GetStockMovementsQuery, GetStockMovementsQueryHandler};
 pub use login::{LoginQuery, LoginQueryHandler};
+pub use preview_sale::{PreviewSaleQuery, PreviewSaleQueryHandler};
@@ -0,0 +1,152 @@
+use crate::{
+    application::{
+        commands::{
+            PaymentInput, apply_promotions, build_payments, check_sale_products, total_to_pay,
+        },
+        dtos::{SalePreviewDTO, SalePricingDTO},
+    },
+    domain::{
+        entities::{Payment, Sale, SaleLine, SaleParams, SaleStatus},
+        repositories::{CustomerRepository, ProductRepository, PromotionRepository},
+        value_objects::CC,
+    },
+    shared::{
+        SharedError,
+        input_handler::{Input, InputHandler},
+    },
+};
+use chrono::Utc;
+use std::sync::Arc;
+
+/// Price a sale through the registration checks without saving anything
+pub struct PreviewSaleQuery {
+    pub customer_cc: String,
+    pub product_skus_quantity: Vec<(String, u32)>,
+    pub coupon_codes: Vec<String>,
+    /// Checked against the total only when some are given
+    pub payments: Vec<PaymentInput>,
+}
+impl Input for PreviewSaleQuery {
+    type Output = SalePreviewDTO;
+}
+pub struct PreviewSaleQueryHandler {
+    pub customer_repository: Arc<dyn CustomerRepository + Send + Sync>,
+    pub product_repository: Arc<dyn ProductRepository + Send + Sync>,
+    pub promotion_repository: Arc<dyn PromotionRepository + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl InputHandler<PreviewSaleQuery> for PreviewSaleQueryHandler {
+    async fn handle(
+        &self,
+        input: Arc<PreviewSaleQuery>,
+    ) -> Result<<PreviewSaleQuery as Input>::Output, SharedError> {
+        // Every check runs so all the problems are reported at once
+        let mut problems = Vec::new();
+        let customer = match CC::new(input.customer_cc.clone()) {
+            Ok(cc) => {
+                let customer = self.customer_repository.get_by_cc(&cc).await;
+                if customer.is_none() {
+                    problems.push(format!("Customer with {} CC not found", input.customer_cc));
+                }
+                customer
+            }
+            Err(err) => {
+                problems.push(err.message);
+                None
+            }
+        };
+        let quantities_check = if input.product_skus_quantity.is_empty() {
+            Err(SharedError::new(
+                "Sale quantities must be greater than 0",
+                400,
+            ))
+        } else {
+            input
+                .product_skus_quantity
+                .iter()
+                .try_for_each(|(_, q)| SaleLine::validate_quantity(*q))
+        };
+        if let Err(err) = &quantities_check {
+            problems.push(err.message.clone());
+        }
+        let check = check_sale_products(
+            self.product_repository.as_ref(),
+            &input.product_skus_quantity,
+        )
+        .await?;
+        for sku in &check.missing_skus {
+            problems.push(format!("Product with SKU {} not found", sku));
+        }
+        for sku in &check.archived_skus {
+            problems.push(format!("Product with SKU {} is archived", sku));
+        }
+        for sku in &check.parent_skus {
+            problems.push(format!(
+                "Product with SKU {} has variants, one of them has to be sold",
+                sku
+            ));
+        }
+        if !check.shortages.is_empty() {
+            problems.push(format!(
+                "Insufficient stock for: {}",
+                check.shortages.join(", ")
+            ));
+        }
+        // Out of range quantities cannot be priced
+        if quantities_check.is_err() {
+            return Ok(SalePreviewDTO {
+                problems,
+                pricing: None,
+            });
+        }
+        let lines: Vec<SaleLine> = check
+            .products_sale
+            .iter()
+            .map(|(p, q)| SaleLine::from_product(p.clone(), *q))
+            .collect();
+        let discounts = match apply_promotions(
+            self.promotion_repository.as_ref(),
+            &lines,
+            &input.coupon_codes,
+        )
+        .await
+        {
+            Ok(discounts) => discounts,
+            Err(err) => {
+                // Priced with the automatic promotions only
+                problems.push(err.message);
+                apply_promotions(self.promotion_repository.as_ref(), &lines, &[]).await?
+            }
+        };
+        let total = total_to_pay(&lines, &discounts)?;
+        let mut payments = Vec::new();
+        if !input.payments.is_empty() {
+            match build_payments(&input.payments)
+                .and_then(|p| Payment::check_cover(&p, total).map(|_| p))
+            {
+                Ok(p) => payments = p,
+                Err(err) => problems.push(err.message),
+            }
+        }
+        // The sale as it would be registered, never saved
+        let pricing = customer
+            .map(|customer| {
+                SalePricingDTO::try_from(Sale::new(SaleParams {
+                    id: 0,
+                    lines,
+                    customer,
+                    generated_at: Utc::now(),
+                    status: SaleStatus::Active,
+                    cancel_reason: None,
+                    cancelled_at: None,
+                    returns: vec![],
+                    exchange_of: None,
+                    discounts,
+                    payments,
+                    invoice_number: None,
+                }))
+            })
+            .transpose()?;
+        Ok(SalePreviewDTO { problems, pricing })
+    }
+}
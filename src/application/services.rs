@@ -1,3 +1,4 @@
+use crate::domain::entities::{ElectronicInvoice, Sale};
 use crate::shared::SharedError;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
@@ -8,6 +9,19 @@ pub enum LogLevel {
     Debug,
 }
 
+/// Email to a single recipient, with an HTML alternative to its text
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub text_body: String,
+    pub html_body: Option<String>,
+}
+
+#[async_trait::async_trait]
+pub trait Mailer {
+    async fn send(&self, message: &EmailMessage) -> Result<(), SharedError>;
+}
+
 pub trait Logger {
     fn log(&self, level: LogLevel, message: &str);
     fn error(&self, message: &str) {
@@ -26,5 +40,55 @@ pub trait Logger {
 
 pub trait CredentialsValidator {
     fn validate(&self, user: &String, pass: &String) -> Result<String, SharedError>;
-    fn has_access(&self, api_key: &String) -> Result<(), SharedError>;
+    /// Check the API key, returning the user it belongs to
+    fn has_access(&self, api_key: &String) -> Result<String, SharedError>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReceiptFormat {
+    /// Raw commands for thermal printers
+    EscPos,
+    Text,
+    Html,
+}
+impl ReceiptFormat {
+    pub fn parse(value: &str) -> Result<Self, SharedError> {
+        match value {
+            "escpos" => Ok(ReceiptFormat::EscPos),
+            "text" => Ok(ReceiptFormat::Text),
+            "html" => Ok(ReceiptFormat::Html),
+            _ => Err(SharedError::new(
+                &format!("Invalid receipt format {}", value),
+                400,
+            )),
+        }
+    }
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ReceiptFormat::EscPos => "application/octet-stream",
+            ReceiptFormat::Text => "text/plain; charset=utf-8",
+            ReceiptFormat::Html => "text/html; charset=utf-8",
+        }
+    }
+}
+
+pub trait ReceiptRenderer {
+    /// Receipt of the sale in the given format, without prices for gift receipts
+    fn render(
+        &self,
+        sale: &Sale,
+        format: ReceiptFormat,
+        gift: bool,
+    ) -> Result<Vec<u8>, SharedError>;
+}
+
+pub trait ElectronicInvoiceBuilder {
+    /// UBL 2.1 document of the sale with its CUFE, failing with 409 if it has no invoice number
+    fn build(&self, sale: &Sale) -> Result<ElectronicInvoice, SharedError>;
+}
+
+#[async_trait::async_trait]
+pub trait ElectronicInvoiceSubmitter {
+    /// Hand the document over to the tax authority
+    async fn submit(&self, invoice: &ElectronicInvoice) -> Result<(), SharedError>;
 }
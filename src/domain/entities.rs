@@ -1,4 +1,5 @@
-use crate::domain::value_objects::{CC, Email, Url, Phone};
+use crate::domain::value_objects::{CC, Email, InvoiceNumber, Money, Url, Phone};
+use crate::shared::SharedError;
 use chrono::{DateTime, Utc};
 
 
@@ -74,41 +75,222 @@ impl Customer {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaxClass {
+    /// IVA general rate, 19%
+    General,
+    /// IVA reduced rate, 5%
+    Reduced,
+    /// Exempt from IVA
+    Exempt,
+}
+impl TaxClass {
+    /// Percentage of IVA over the base
+    pub fn rate(&self) -> f64 {
+        match self {
+            TaxClass::General => 19.0,
+            TaxClass::Reduced => 5.0,
+            TaxClass::Exempt => 0.0,
+        }
+    }
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaxClass::General => "general",
+            TaxClass::Reduced => "reduced",
+            TaxClass::Exempt => "exempt",
+        }
+    }
+    pub fn parse(value: &str) -> Result<Self, SharedError> {
+        match value {
+            "general" => Ok(TaxClass::General),
+            "reduced" => Ok(TaxClass::Reduced),
+            "exempt" => Ok(TaxClass::Exempt),
+            _ => Err(SharedError::new(
+                &format!("Invalid tax class {}", value),
+                400,
+            )),
+        }
+    }
+}
+
+/// Group of products, nested under a parent category to form a hierarchy
+#[derive(Clone, Debug)]
+pub struct Category {
+    id: u32,
+    name: String,
+    parent_id: Option<u32>,
+}
+impl Category {
+    pub fn new(id: u32, name: String, parent_id: Option<u32>) -> Self {
+        Category {
+            id,
+            name,
+            parent_id,
+        }
+    }
+
+    /// Trimmed name of a category, failing with 400 if it is empty or longer than 100 characters
+    pub fn validate_name(name: &str) -> Result<String, SharedError> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > 100 {
+            return Err(SharedError::new(
+                "Category name must have between 1 and 100 characters",
+                400,
+            ));
+        }
+        Ok(name.to_string())
+    }
+    /// Ids of the category with the given id and all the categories under it
+    pub fn subtree_ids(categories: &[Category], id: u32) -> Vec<u32> {
+        let mut ids = vec![id];
+        let mut index = 0;
+        while index < ids.len() {
+            let current = ids[index];
+            ids.extend(
+                categories
+                    .iter()
+                    .filter(|category| category.parent_id == Some(current))
+                    .map(|category| category.id),
+            );
+            index += 1;
+        }
+        ids
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+    pub fn parent_id(&self) -> Option<u32> {
+        self.parent_id
+    }
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+    pub fn set_parent_id(&mut self, parent_id: Option<u32>) {
+        self.parent_id = parent_id;
+    }
+}
+
+/// Size and color of a product sold as one of the variants of a parent product
+#[derive(Clone, Debug)]
+pub struct ProductVariant {
+    parent_id: u32,
+    size: Option<String>,
+    color: Option<String>,
+    /// Whether the variant keeps its own price instead of following the parent one
+    price_override: bool,
+}
+impl ProductVariant {
+    pub fn new(
+        parent_id: u32,
+        size: Option<String>,
+        color: Option<String>,
+        price_override: bool,
+    ) -> Self {
+        ProductVariant {
+            parent_id,
+            size,
+            color,
+            price_override,
+        }
+    }
+
+    pub fn parent_id(&self) -> u32 {
+        self.parent_id
+    }
+    pub fn size(&self) -> &Option<String> {
+        &self.size
+    }
+    pub fn color(&self) -> &Option<String> {
+        &self.color
+    }
+    pub fn price_override(&self) -> bool {
+        self.price_override
+    }
+}
+
 #[derive(Clone)]
 pub struct Product {
     id: u32,
     sku: String,
     name: String,
-    price: f64,
+    /// Price to the customer, IVA included
+    price: Money,
+    tax_class: TaxClass,
     stock: u32,
-    flags: Vec<String>,
+    /// Stock at or below which the product has to be reordered, no alerts when not set
+    reorder_point: Option<u32>,
+    /// Units to order when the reorder point is reached
+    reorder_quantity: Option<u32>,
+    /// Set when the product is a variant of a parent product
+    variant: Option<ProductVariant>,
+    /// Parent products are not sold, their variants are
+    has_variants: bool,
+    categories: Vec<Category>,
     img_url: Option<Url>,
     description: Option<String>,
+    /// Archived products are no longer listed nor sold, but old sales still show them
+    archived_at: Option<DateTime<Utc>>,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
 }
+/// Fields of a product, named so the ones of the same type cannot be swapped
+pub struct ProductParams {
+    pub id: u32,
+    pub sku: String,
+    pub name: String,
+    pub price: Money,
+    pub tax_class: TaxClass,
+    pub stock: u32,
+    pub reorder_point: Option<u32>,
+    pub reorder_quantity: Option<u32>,
+    pub variant: Option<ProductVariant>,
+    pub has_variants: bool,
+    pub categories: Vec<Category>,
+    pub img_url: Option<Url>,
+    pub description: Option<String>,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
 impl Product {
-    pub fn new(
-        id: u32,
-        sku: String,
-        name: String,
-        price: f64,
-        stock: u32,
-        flags: Vec<String>,
-        img_url: Option<Url>,
-        description: Option<String>,
-        created_at: DateTime<Utc>,
-        updated_at: DateTime<Utc>,
-    ) -> Self {
+    pub fn new(params: ProductParams) -> Self {
+        let ProductParams {
+            id,
+            sku,
+            name,
+            price,
+            tax_class,
+            stock,
+            reorder_point,
+            reorder_quantity,
+            variant,
+            has_variants,
+            categories,
+            img_url,
+            description,
+            archived_at,
+            created_at,
+            updated_at,
+        } = params;
         Product {
             id,
             sku,
             name,
             price,
+            tax_class,
             stock,
-            flags,
+            reorder_point,
+            reorder_quantity,
+            variant,
+            has_variants,
+            categories,
             img_url,
             description,
+            archived_at,
             created_at,
             updated_at,
         }
@@ -123,14 +305,34 @@ impl Product {
     pub fn name(&self) -> &String {
         &self.name
     }
-    pub fn price(&self) -> f64 {
+    pub fn price(&self) -> Money {
         self.price
     }
+    pub fn tax_class(&self) -> TaxClass {
+        self.tax_class
+    }
     pub fn stock(&self) -> u32 {
         self.stock
     }
-    pub fn flags(&self) -> Vec<String> {
-        self.flags.clone()
+    pub fn reorder_point(&self) -> Option<u32> {
+        self.reorder_point
+    }
+    pub fn reorder_quantity(&self) -> Option<u32> {
+        self.reorder_quantity
+    }
+    pub fn variant(&self) -> &Option<ProductVariant> {
+        &self.variant
+    }
+    pub fn has_variants(&self) -> bool {
+        self.has_variants
+    }
+    pub fn categories(&self) -> &Vec<Category> {
+        &self.categories
+    }
+    pub fn in_any_category(&self, ids: &[u32]) -> bool {
+        self.categories
+            .iter()
+            .any(|category| ids.contains(&category.id))
     }
     pub fn img_url(&self) -> &Option<Url> {
         &self.img_url
@@ -144,12 +346,88 @@ impl Product {
     pub fn description(&self) -> &Option<String> {
         &self.description
     }
+    pub fn archived_at(&self) -> &Option<DateTime<Utc>> {
+        &self.archived_at
+    }
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+    pub fn archive(&mut self) -> Result<(), SharedError> {
+        if self.is_archived() {
+            return Err(SharedError::new(
+                format!("Product with SKU {} is already archived", self.sku).as_str(),
+                409,
+            ));
+        }
+        self.archived_at = Some(Utc::now());
+        Ok(())
+    }
+    pub fn unarchive(&mut self) -> Result<(), SharedError> {
+        if !self.is_archived() {
+            return Err(SharedError::new(
+                format!("Product with SKU {} is not archived", self.sku).as_str(),
+                409,
+            ));
+        }
+        self.archived_at = None;
+        Ok(())
+    }
 
-    pub fn set_stock(&mut self, stock: u32) {
-        self.stock = stock;
+    /// Take quantity units out of the stock, failing with the units missing if there are not enough
+    pub fn reserve(&mut self, quantity: u32) -> Result<(), u32> {
+        match self.stock.checked_sub(quantity) {
+            Some(stock) => {
+                self.stock = stock;
+                Ok(())
+            }
+            None => Err(quantity - self.stock),
+        }
+    }
+    /// Whether taking the stock from stock_before down to stock_after reaches the reorder point
+    pub fn crosses_reorder_point(&self, stock_before: u32, stock_after: u32) -> bool {
+        match self.reorder_point {
+            Some(point) => stock_before > point && stock_after <= point,
+            None => false,
+        }
+    }
+    /// Alert for a change taking the stock from stock_before down to stock_after, if it reaches
+    /// the reorder point
+    pub fn low_stock_alert(
+        &self,
+        stock_before: u32,
+        stock_after: u32,
+        reason: StockMovementReason,
+    ) -> Option<LowStockAlert> {
+        if !self.crosses_reorder_point(stock_before, stock_after) {
+            return None;
+        }
+        Some(LowStockAlert {
+            sku: self.sku.clone(),
+            stock: stock_after,
+            reason,
+            reorder_point: self.reorder_point.unwrap_or(0),
+            reorder_quantity: self.reorder_quantity,
+        })
+    }
+    pub fn set_reorder_point(&mut self, reorder_point: u32) {
+        self.reorder_point = Some(reorder_point);
+    }
+    pub fn set_reorder_quantity(&mut self, reorder_quantity: u32) -> Result<(), SharedError> {
+        if reorder_quantity == 0 {
+            return Err(SharedError::new("Reorder quantity must be greater than 0", 400));
+        }
+        self.reorder_quantity = Some(reorder_quantity);
+        Ok(())
     }
-    pub fn set_price(&mut self, price: f64) {
+    /// Variants given a price of their own stop following the parent price
+    pub fn set_price(&mut self, price: Money) {
         self.price = price;
+        if let Some(variant) = &mut self.variant {
+            variant.price_override = true;
+        }
+    }
+    pub fn set_tax_class(&mut self, tax_class: TaxClass) {
+        self.tax_class = tax_class;
     }
     pub fn set_description(&mut self, description: Option<String>) {
         self.description = description;
@@ -160,50 +438,2008 @@ impl Product {
     pub fn set_name(&mut self, name: String) {
         self.name = name;
     }
-    pub fn set_flags(&mut self, flags: Vec<String>) {
-        self.flags = flags;
+    pub fn set_categories(&mut self, categories: Vec<Category>) {
+        self.categories = categories;
     }
 }
 
+/// Most units of a product a sale line can have
+const MAX_LINE_QUANTITY: u32 = 10_000;
+
 #[derive(Clone)]
-pub struct Sale {
+pub struct SaleLine {
+    product: Product,
+    quantity: u32,
+    /// Product name when it was sold
+    product_name: String,
+    /// Product unit price when it was sold
+    unit_price: Money,
+    /// Product tax class and its IVA percentage when it was sold
+    tax_class: TaxClass,
+    tax_rate: f64,
+}
+impl SaleLine {
+    pub fn new(
+        product: Product,
+        quantity: u32,
+        product_name: String,
+        unit_price: Money,
+        tax_class: TaxClass,
+        tax_rate: f64,
+    ) -> Self {
+        SaleLine {
+            product,
+            quantity,
+            product_name,
+            unit_price,
+            tax_class,
+            tax_rate,
+        }
+    }
+    /// Check a quantity to sell is between 1 and MAX_LINE_QUANTITY, failing with 400 otherwise
+    pub fn validate_quantity(quantity: u32) -> Result<(), SharedError> {
+        if quantity == 0 || quantity > MAX_LINE_QUANTITY {
+            return Err(SharedError::new(
+                &format!(
+                    "Sale quantities must be between 1 and {}",
+                    MAX_LINE_QUANTITY
+                ),
+                400,
+            ));
+        }
+        Ok(())
+    }
+    /// Line selling the product at its current name, price and tax class
+    pub fn from_product(product: Product, quantity: u32) -> Self {
+        let product_name = product.name().clone();
+        let unit_price = product.price();
+        let tax_class = product.tax_class();
+        SaleLine::new(
+            product,
+            quantity,
+            product_name,
+            unit_price,
+            tax_class,
+            tax_class.rate(),
+        )
+    }
+
+    pub fn amount(&self) -> Result<Money, SharedError> {
+        self.unit_price.checked_mul(self.quantity)
+    }
+    pub fn product(&self) -> &Product {
+        &self.product
+    }
+    pub fn quantity(&self) -> u32 {
+        self.quantity
+    }
+    pub fn product_name(&self) -> &String {
+        &self.product_name
+    }
+    pub fn unit_price(&self) -> Money {
+        self.unit_price
+    }
+    pub fn tax_class(&self) -> TaxClass {
+        self.tax_class
+    }
+    pub fn tax_rate(&self) -> f64 {
+        self.tax_rate
+    }
+}
+
+/// IVA included in the amount paid for a sale line
+#[derive(Clone)]
+pub struct LineTax {
+    tax_class: TaxClass,
+    tax_rate: f64,
+    base: Money,
+    tax: Money,
+}
+impl LineTax {
+    /// Split an amount with IVA included into its base and tax
+    pub fn from_paid_amount(
+        tax_class: TaxClass,
+        tax_rate: f64,
+        amount: Money,
+    ) -> Result<Self, SharedError> {
+        // Rates are taken to the hundredth, as basis points
+        let rate = (tax_rate * 100.0).round() as i64;
+        let tax = amount.proportion(rate, 10_000 + rate);
+        Ok(LineTax {
+            tax_class,
+            tax_rate,
+            base: amount.checked_sub(tax)?,
+            tax,
+        })
+    }
+
+    pub fn base(&self) -> Money {
+        self.base
+    }
+    pub fn tax(&self) -> Money {
+        self.tax
+    }
+}
+
+/// Base and IVA of a sale added up by tax class and rate
+#[derive(Clone)]
+pub struct TaxBreakdown {
+    tax_class: TaxClass,
+    tax_rate: f64,
+    base: Money,
+    tax: Money,
+}
+impl TaxBreakdown {
+    pub fn tax_class(&self) -> TaxClass {
+        self.tax_class
+    }
+    pub fn tax_rate(&self) -> f64 {
+        self.tax_rate
+    }
+    pub fn base(&self) -> Money {
+        self.base
+    }
+    pub fn tax(&self) -> Money {
+        self.tax
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromotionScope {
+    /// Discount over each sale line matching the promotion
+    Line,
+    /// Discount over the whole sale
+    Sale,
+}
+impl PromotionScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PromotionScope::Line => "line",
+            PromotionScope::Sale => "sale",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PromotionRule {
+    /// Percentage off, from 0 to 100
+    Percentage(f64),
+    /// Amount off, per unit on line promotions and once on sale promotions
+    FixedAmount(Money),
+    /// Pay only `pay` of every `buy` units (e.g. 2x1), line promotions only
+    BuyXPayY { buy: u32, pay: u32 },
+}
+
+#[derive(Clone)]
+pub struct AppliedDiscount {
+    promotion_id: Option<u32>,
+    /// SKU of the discounted line, None for sale discounts
+    product_sku: Option<String>,
+    description: String,
+    amount: Money,
+}
+impl AppliedDiscount {
+    pub fn new(
+        promotion_id: Option<u32>,
+        product_sku: Option<String>,
+        description: String,
+        amount: Money,
+    ) -> Self {
+        AppliedDiscount {
+            promotion_id,
+            product_sku,
+            description,
+            amount,
+        }
+    }
+
+    pub fn promotion_id(&self) -> Option<u32> {
+        self.promotion_id
+    }
+    pub fn product_sku(&self) -> &Option<String> {
+        &self.product_sku
+    }
+    pub fn description(&self) -> &String {
+        &self.description
+    }
+    pub fn amount(&self) -> Money {
+        self.amount
+    }
+}
+
+#[derive(Clone)]
+pub struct Promotion {
     id: u32,
-    /// Vector of (Product, quantity)
-    products_sale: Vec<(Product, u32)>,
-    customer: Customer,
-    generated_at: DateTime<Utc>,
+    name: String,
+    /// Coupon code, promotions without one are applied automatically
+    code: Option<String>,
+    scope: PromotionScope,
+    rule: PromotionRule,
+    /// Only lines of products in this category or its subcategories are discounted
+    category_id: Option<u32>,
+    /// Ids of the targeted category and its subcategories
+    category_ids: Vec<u32>,
+    starts_at: Option<DateTime<Utc>>,
+    ends_at: Option<DateTime<Utc>>,
+    active: bool,
+    created_at: DateTime<Utc>,
 }
-impl Sale {
+/// Fields of a promotion, named so the ones of the same type cannot be swapped
+pub struct PromotionParams {
+    pub id: u32,
+    pub name: String,
+    pub code: Option<String>,
+    pub scope: PromotionScope,
+    pub rule: PromotionRule,
+    pub category_id: Option<u32>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+impl Promotion {
+    pub fn new(params: PromotionParams) -> Self {
+        let PromotionParams {
+            id,
+            name,
+            code,
+            scope,
+            rule,
+            category_id,
+            starts_at,
+            ends_at,
+            active,
+            created_at,
+        } = params;
+        Promotion {
+            id,
+            name,
+            code,
+            scope,
+            rule,
+            category_id,
+            category_ids: category_id.into_iter().collect(),
+            starts_at,
+            ends_at,
+            active,
+            created_at,
+        }
+    }
+
+    /// Check the rule makes sense for the scope, before creating a promotion
+    pub fn validate_rule(scope: PromotionScope, rule: PromotionRule) -> Result<(), SharedError> {
+        let valid = match rule {
+            PromotionRule::Percentage(percentage) => percentage > 0.0 && percentage <= 100.0,
+            PromotionRule::FixedAmount(amount) => amount.is_positive(),
+            PromotionRule::BuyXPayY { buy, pay } => scope == PromotionScope::Line && buy > pay,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(SharedError::new("Invalid promotion rule", 400))
+        }
+    }
+
+    /// Extend the targeted category with the subcategories under it in the given hierarchy
+    pub fn include_subcategories(&mut self, categories: &[Category]) {
+        if let Some(category_id) = self.category_id {
+            self.category_ids = Category::subtree_ids(categories, category_id);
+        }
+    }
+
+    pub fn is_available_at(&self, at: &DateTime<Utc>) -> bool {
+        self.active
+            && self.starts_at.is_none_or(|starts_at| starts_at <= *at)
+            && self.ends_at.is_none_or(|ends_at| *at <= ends_at)
+    }
+
+    /// Discounts granted over the sale lines, never exceeding what is left to pay after the
+    /// already applied discounts
+    pub fn discounts_for(
+        &self,
+        lines: &[SaleLine],
+        applied: &[AppliedDiscount],
+    ) -> Result<Vec<AppliedDiscount>, SharedError> {
+        match self.scope {
+            PromotionScope::Line => {
+                let mut discounts = Vec::new();
+                let matching = lines.iter().filter(|line| {
+                    self.category_id.is_none()
+                        || line.product().in_any_category(&self.category_ids)
+                });
+                for line in matching {
+                    let sku = line.product().sku();
+                    let line_amount = line.amount()?;
+                    let amount = match self.rule {
+                        PromotionRule::Percentage(percentage) => line_amount.percent(percentage),
+                        PromotionRule::FixedAmount(amount) => {
+                            amount.min(line.unit_price()).checked_mul(line.quantity())?
+                        }
+                        PromotionRule::BuyXPayY { buy, pay } => {
+                            let free_units = (line.quantity() / buy) * (buy - pay);
+                            line.unit_price().checked_mul(free_units)?
+                        }
+                    };
+                    let discounted = Money::checked_sum(
+                        applied
+                            .iter()
+                            .filter(|d| d.product_sku().as_ref() == Some(sku))
+                            .map(|d| d.amount()),
+                    )?;
+                    let amount = amount.min(line_amount.checked_sub(discounted)?);
+                    if amount.is_positive() {
+                        discounts.push(AppliedDiscount::new(
+                            Some(self.id),
+                            Some(sku.clone()),
+                            self.name.clone(),
+                            amount,
+                        ));
+                    }
+                }
+                Ok(discounts)
+            }
+            PromotionScope::Sale => {
+                let subtotal = Money::checked_sum(
+                    lines
+                        .iter()
+                        .map(|line| line.amount())
+                        .collect::<Result<Vec<Money>, SharedError>>()?,
+                )?;
+                let left =
+                    subtotal.checked_sub(Money::checked_sum(applied.iter().map(|d| d.amount()))?)?;
+                let amount = match self.rule {
+                    PromotionRule::Percentage(percentage) => left.percent(percentage),
+                    PromotionRule::FixedAmount(amount) => amount,
+                    PromotionRule::BuyXPayY { .. } => Money::zero(),
+                }
+                .min(left);
+                if !amount.is_positive() {
+                    return Ok(vec![]);
+                }
+                Ok(vec![AppliedDiscount::new(
+                    Some(self.id),
+                    None,
+                    self.name.clone(),
+                    amount,
+                )])
+            }
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+    pub fn code(&self) -> &Option<String> {
+        &self.code
+    }
+    pub fn scope(&self) -> PromotionScope {
+        self.scope
+    }
+    pub fn rule(&self) -> PromotionRule {
+        self.rule
+    }
+    pub fn category_id(&self) -> Option<u32> {
+        self.category_id
+    }
+    pub fn starts_at(&self) -> &Option<DateTime<Utc>> {
+        &self.starts_at
+    }
+    pub fn ends_at(&self) -> &Option<DateTime<Utc>> {
+        &self.ends_at
+    }
+    pub fn active(&self) -> bool {
+        self.active
+    }
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+}
+
+#[derive(Clone)]
+pub struct ReturnLine {
+    product_sku: String,
+    quantity: u32,
+    /// Unit price refunded, the one paid for the product
+    unit_price: Money,
+}
+impl ReturnLine {
+    pub fn new(product_sku: String, quantity: u32, unit_price: Money) -> Self {
+        ReturnLine {
+            product_sku,
+            quantity,
+            unit_price,
+        }
+    }
+
+    pub fn amount(&self) -> Result<Money, SharedError> {
+        self.unit_price.checked_mul(self.quantity)
+    }
+    pub fn product_sku(&self) -> &String {
+        &self.product_sku
+    }
+    pub fn quantity(&self) -> u32 {
+        self.quantity
+    }
+    pub fn unit_price(&self) -> Money {
+        self.unit_price
+    }
+}
+
+#[derive(Clone)]
+pub struct SaleReturn {
+    id: u32,
+    lines: Vec<ReturnLine>,
+    reason: Option<String>,
+    created_at: DateTime<Utc>,
+}
+impl SaleReturn {
     pub fn new(
         id: u32,
-        products: Vec<(Product, u32)>,
-        customer: Customer,
-        generated_at: DateTime<Utc>,
+        lines: Vec<ReturnLine>,
+        reason: Option<String>,
+        created_at: DateTime<Utc>,
     ) -> Self {
-        Sale {
+        SaleReturn {
             id,
-            products_sale: products,
-            customer,
-            generated_at: generated_at,
+            lines,
+            reason,
+            created_at,
         }
     }
 
-    pub fn total_amount(&self) -> f64 {
-        self.products_sale
-            .iter()
-            .map(|(product, quantity)| product.price * (*quantity as f64))
-            .sum()
+    pub fn refunded_amount(&self) -> Result<Money, SharedError> {
+        Money::checked_sum(
+            self.lines
+                .iter()
+                .map(|line| line.amount())
+                .collect::<Result<Vec<Money>, SharedError>>()?,
+        )
     }
     pub fn id(&self) -> u32 {
         self.id
     }
-    pub fn products_sale(&self) -> &Vec<(Product, u32)> {
-        &self.products_sale
+    pub fn lines(&self) -> &Vec<ReturnLine> {
+        &self.lines
     }
-    pub fn customer(&self) -> &Customer {
-        &self.customer
+    pub fn reason(&self) -> &Option<String> {
+        &self.reason
     }
-    pub fn generated_at(&self) -> &DateTime<Utc> {
-        &self.generated_at
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaleStatus {
+    Active,
+    Cancelled,
+}
+impl SaleStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SaleStatus::Active => "active",
+            SaleStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentMethod {
+    Cash,
+    Card,
+    BankTransfer,
+    Nequi,
+    Daviplata,
+    StoreCredit,
+}
+impl PaymentMethod {
+    pub const ALL: [PaymentMethod; 6] = [
+        PaymentMethod::Cash,
+        PaymentMethod::Card,
+        PaymentMethod::BankTransfer,
+        PaymentMethod::Nequi,
+        PaymentMethod::Daviplata,
+        PaymentMethod::StoreCredit,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentMethod::Cash => "cash",
+            PaymentMethod::Card => "card",
+            PaymentMethod::BankTransfer => "bank_transfer",
+            PaymentMethod::Nequi => "nequi",
+            PaymentMethod::Daviplata => "daviplata",
+            PaymentMethod::StoreCredit => "store_credit",
+        }
+    }
+    pub fn parse(value: &str) -> Result<Self, SharedError> {
+        PaymentMethod::ALL
+            .into_iter()
+            .find(|method| method.as_str() == value)
+            .ok_or_else(|| SharedError::new(&format!("Invalid payment method {}", value), 400))
+    }
+}
+
+#[derive(Clone)]
+pub struct Payment {
+    method: PaymentMethod,
+    /// Part of the sale total paid with the method
+    amount: Money,
+    /// Cash handed over by the customer, only for cash payments
+    tendered: Option<Money>,
+}
+impl Payment {
+    pub fn new(
+        method: PaymentMethod,
+        amount: Money,
+        tendered: Option<Money>,
+    ) -> Result<Self, SharedError> {
+        if !amount.is_positive() {
+            return Err(SharedError::new("Payment amounts must be greater than 0", 400));
+        }
+        match tendered {
+            Some(_) if method != PaymentMethod::Cash => Err(SharedError::new(
+                "Only cash payments can have a tendered amount",
+                400,
+            )),
+            Some(tendered) if tendered < amount => Err(SharedError::new(
+                "Tendered cash must cover the payment amount",
+                400,
+            )),
+            _ => Ok(Payment {
+                method,
+                amount,
+                tendered,
+            }),
+        }
+    }
+    /// Check the payments add up exactly to the total to pay
+    pub fn check_cover(payments: &[Payment], total: Money) -> Result<(), SharedError> {
+        let paid = payments
+            .iter()
+            .try_fold(Money::zero(), |paid, p| paid.checked_add(p.amount))?;
+        if paid != total {
+            return Err(SharedError::new(
+                &format!("Payments add up to {} but the sale total is {}", paid, total),
+                400,
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn method(&self) -> PaymentMethod {
+        self.method
+    }
+    pub fn amount(&self) -> Money {
+        self.amount
+    }
+    pub fn tendered(&self) -> Option<Money> {
+        self.tendered
+    }
+    /// Cash given back to the customer
+    pub fn change(&self) -> Result<Money, SharedError> {
+        self.tendered
+            .map_or(Ok(Money::zero()), |tendered| tendered.checked_sub(self.amount))
+    }
+}
+
+#[derive(Clone)]
+pub struct Sale {
+    id: u32,
+    lines: Vec<SaleLine>,
+    customer: Customer,
+    generated_at: DateTime<Utc>,
+    status: SaleStatus,
+    cancel_reason: Option<String>,
+    cancelled_at: Option<DateTime<Utc>>,
+    returns: Vec<SaleReturn>,
+    /// Original sale when this one replaces products by an exchange
+    exchange_of: Option<u32>,
+    discounts: Vec<AppliedDiscount>,
+    payments: Vec<Payment>,
+    /// None for sales made before invoice series existed, or not registered yet
+    invoice_number: Option<InvoiceNumber>,
+}
+/// Fields of a sale, named so the ones of the same type cannot be swapped
+pub struct SaleParams {
+    pub id: u32,
+    pub lines: Vec<SaleLine>,
+    pub customer: Customer,
+    pub generated_at: DateTime<Utc>,
+    pub status: SaleStatus,
+    pub cancel_reason: Option<String>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub returns: Vec<SaleReturn>,
+    pub exchange_of: Option<u32>,
+    pub discounts: Vec<AppliedDiscount>,
+    pub payments: Vec<Payment>,
+    pub invoice_number: Option<InvoiceNumber>,
+}
+impl Sale {
+    pub fn new(params: SaleParams) -> Self {
+        let SaleParams {
+            id,
+            lines,
+            customer,
+            generated_at,
+            status,
+            cancel_reason,
+            cancelled_at,
+            returns,
+            exchange_of,
+            discounts,
+            payments,
+            invoice_number,
+        } = params;
+        Sale {
+            id,
+            lines,
+            customer,
+            generated_at: generated_at,
+            status,
+            cancel_reason,
+            cancelled_at,
+            returns,
+            exchange_of,
+            discounts,
+            payments,
+            invoice_number,
+        }
+    }
+
+    /// Void the sale, its products must be returned to stock by the caller
+    pub fn cancel(&mut self, reason: String) -> Result<(), SharedError> {
+        if self.status == SaleStatus::Cancelled {
+            return Err(SharedError::new(
+                &format!("Sale with ID {} is already cancelled", self.id),
+                409,
+            ));
+        }
+        self.status = SaleStatus::Cancelled;
+        self.cancel_reason = Some(reason);
+        self.cancelled_at = Some(Utc::now());
+        Ok(())
+    }
+    pub fn is_cancelled(&self) -> bool {
+        self.status == SaleStatus::Cancelled
+    }
+
+    /// Amount of the lines before discounts
+    pub fn subtotal(&self) -> Result<Money, SharedError> {
+        Money::checked_sum(
+            self.lines
+                .iter()
+                .map(|line| line.amount())
+                .collect::<Result<Vec<Money>, SharedError>>()?,
+        )
+    }
+    pub fn discount_amount(&self) -> Result<Money, SharedError> {
+        Money::checked_sum(self.discounts.iter().map(|d| d.amount()))
+    }
+    pub fn total_amount(&self) -> Result<Money, SharedError> {
+        self.subtotal()?.checked_sub(self.discount_amount()?)
+    }
+    /// Unit prices refunded for returning `quantity` units of a SKU, as (unit price, quantity).
+    /// What is left of the amount paid for the SKU after earlier refunds is split among the
+    /// units still held, so returning all of them refunds exactly what was paid.
+    pub fn refund_unit_prices(
+        &self,
+        sku: &str,
+        quantity: u32,
+    ) -> Result<Vec<(Money, u32)>, SharedError> {
+        let refunded = Money::checked_sum(
+            self.returns
+                .iter()
+                .flat_map(|r| r.lines().iter())
+                .filter(|line| line.product_sku() == sku)
+                .map(|line| line.amount())
+                .collect::<Result<Vec<Money>, SharedError>>()?,
+        )?;
+        let left = self
+            .paid_amount(sku)?
+            .checked_sub(refunded)?
+            .max(Money::zero());
+        let held = self.sold_quantity(sku) - self.returned_quantity(sku);
+        let mut prices: Vec<(Money, u32)> = Vec::new();
+        for unit_price in left
+            .allocate(&vec![1; held as usize])
+            .into_iter()
+            .take(quantity as usize)
+        {
+            match prices.last_mut() {
+                Some((price, units)) if *price == unit_price => *units += 1,
+                _ => prices.push((unit_price, 1)),
+            }
+        }
+        Ok(prices)
+    }
+    /// Amount paid for all the units of a SKU, its sold price minus its share of the discounts
+    pub fn paid_amount(&self, sku: &str) -> Result<Money, SharedError> {
+        Ok(self
+            .paid_amounts()?
+            .into_iter()
+            .find(|(paid_sku, _)| paid_sku.as_str() == sku)
+            .map(|(_, amount)| amount)
+            .unwrap_or_else(Money::zero))
+    }
+    /// Amount paid for each SKU, in the order of the lines. Sale discounts are shared by the SKUs
+    /// in proportion to what is paid for them, the shares adding up to the discount exactly.
+    fn paid_amounts(&self) -> Result<Vec<(&String, Money)>, SharedError> {
+        let mut skus: Vec<&String> = Vec::new();
+        for line in self.lines.iter() {
+            if !skus.contains(&line.product().sku()) {
+                skus.push(line.product().sku());
+            }
+        }
+        let mut nets: Vec<Money> = Vec::new();
+        for sku in skus.iter() {
+            let line_amount = Money::checked_sum(
+                self.lines
+                    .iter()
+                    .filter(|line| line.product().sku() == *sku)
+                    .map(|line| line.amount())
+                    .collect::<Result<Vec<Money>, SharedError>>()?,
+            )?;
+            let line_discount = Money::checked_sum(
+                self.discounts
+                    .iter()
+                    .filter(|d| d.product_sku().as_ref() == Some(*sku))
+                    .map(|d| d.amount()),
+            )?;
+            nets.push(line_amount.checked_sub(line_discount)?);
+        }
+        let sale_discount = Money::checked_sum(
+            self.discounts
+                .iter()
+                .filter(|d| d.product_sku().is_none())
+                .map(|d| d.amount()),
+        )?;
+        let shares =
+            sale_discount.allocate(&nets.iter().map(|net| net.minor()).collect::<Vec<i64>>());
+        skus.into_iter()
+            .zip(nets.into_iter().zip(shares))
+            .map(|(sku, (net, share))| Ok((sku, net.checked_sub(share)?)))
+            .collect()
+    }
+    /// IVA of each line, in the same order, over what is paid for it after discounts. What is
+    /// paid for a SKU is split among its lines by quantity, so the lines add up to the total.
+    pub fn line_taxes(&self) -> Result<Vec<LineTax>, SharedError> {
+        let mut line_paid: Vec<Money> = vec![Money::zero(); self.lines.len()];
+        for (sku, amount) in self.paid_amounts()? {
+            let indexes: Vec<usize> = (0..self.lines.len())
+                .filter(|index| self.lines[*index].product().sku() == sku)
+                .collect();
+            let quantities: Vec<i64> = indexes
+                .iter()
+                .map(|index| self.lines[*index].quantity() as i64)
+                .collect();
+            for (index, share) in indexes.into_iter().zip(amount.allocate(&quantities)) {
+                line_paid[index] = share;
+            }
+        }
+        self.lines
+            .iter()
+            .zip(line_paid)
+            .map(|(line, paid)| LineTax::from_paid_amount(line.tax_class(), line.tax_rate(), paid))
+            .collect()
+    }
+    pub fn tax_breakdown(&self) -> Result<Vec<TaxBreakdown>, SharedError> {
+        let mut breakdown: Vec<TaxBreakdown> = Vec::new();
+        for line_tax in self.line_taxes()? {
+            match breakdown
+                .iter_mut()
+                .find(|b| b.tax_class == line_tax.tax_class && b.tax_rate == line_tax.tax_rate)
+            {
+                Some(b) => {
+                    b.base = b.base.checked_add(line_tax.base)?;
+                    b.tax = b.tax.checked_add(line_tax.tax)?;
+                }
+                None => breakdown.push(TaxBreakdown {
+                    tax_class: line_tax.tax_class,
+                    tax_rate: line_tax.tax_rate,
+                    base: line_tax.base,
+                    tax: line_tax.tax,
+                }),
+            }
+        }
+        Ok(breakdown)
+    }
+    /// Total amount without IVA
+    pub fn base_amount(&self) -> Result<Money, SharedError> {
+        Money::checked_sum(self.line_taxes()?.iter().map(|t| t.base()))
+    }
+    pub fn tax_amount(&self) -> Result<Money, SharedError> {
+        Money::checked_sum(self.line_taxes()?.iter().map(|t| t.tax()))
+    }
+    pub fn refunded_amount(&self) -> Result<Money, SharedError> {
+        Money::checked_sum(
+            self.returns
+                .iter()
+                .map(|r| r.refunded_amount())
+                .collect::<Result<Vec<Money>, SharedError>>()?,
+        )
+    }
+    /// Total amount minus what has been refunded by returns
+    pub fn net_amount(&self) -> Result<Money, SharedError> {
+        self.total_amount()?.checked_sub(self.refunded_amount()?)
+    }
+    pub fn sold_quantity(&self, sku: &str) -> u32 {
+        self.lines
+            .iter()
+            .filter(|line| line.product().sku() == sku)
+            .map(|line| line.quantity())
+            .sum()
+    }
+    pub fn returned_quantity(&self, sku: &str) -> u32 {
+        self.returns
+            .iter()
+            .flat_map(|r| r.lines().iter())
+            .filter(|line| line.product_sku() == sku)
+            .map(|line| line.quantity())
+            .sum()
+    }
+    /// Lines still held by the customer, as (product, quantity) by SKU
+    pub fn kept_products(&self) -> Vec<(&Product, u32)> {
+        let mut kept: Vec<(&Product, u32)> = Vec::new();
+        for line in self.lines.iter() {
+            if kept.iter().any(|(p, _)| p.sku() == line.product().sku()) {
+                continue;
+            }
+            let sku = line.product().sku();
+            let quantity = self.sold_quantity(sku) - self.returned_quantity(sku);
+            if quantity > 0 {
+                kept.push((line.product(), quantity));
+            }
+        }
+        kept
+    }
+    /// Build the lines returning the given (SKU, quantity) items, refunded at the price they were paid.
+    /// Fails if the sale is cancelled or a quantity exceeds what was sold minus previous returns.
+    pub fn return_lines(&self, items: &[(String, u32)]) -> Result<Vec<ReturnLine>, SharedError> {
+        if self.is_cancelled() {
+            return Err(SharedError::new(
+                &format!("Sale with ID {} is cancelled", self.id),
+                409,
+            ));
+        }
+        if items.is_empty() || items.iter().any(|(_, quantity)| *quantity == 0) {
+            return Err(SharedError::new("Return quantities must be greater than 0", 400));
+        }
+        // Repeated SKUs are merged so they add up against the same returnable quantity
+        let mut requested: Vec<(&String, u32)> = Vec::new();
+        for (sku, quantity) in items {
+            match requested.iter_mut().find(|(s, _)| *s == sku) {
+                Some(item) => item.1 = item.1.saturating_add(*quantity),
+                None => requested.push((sku, *quantity)),
+            }
+        }
+        let mut lines: Vec<ReturnLine> = Vec::new();
+        let mut exceeded = Vec::new();
+        for (sku, quantity) in requested {
+            let sale_line = match self.lines.iter().find(|line| line.product().sku() == sku) {
+                Some(line) => line,
+                None => {
+                    return Err(SharedError::new(
+                        &format!("Product with SKU {} is not part of sale {}", sku, self.id),
+                        400,
+                    ));
+                }
+            };
+            let returnable = self.sold_quantity(sku) - self.returned_quantity(sku);
+            if quantity > returnable {
+                exceeded.push(format!("{} (returnable {})", sku, returnable));
+                continue;
+            }
+            // Units refunded at different prices go in separate lines
+            for (unit_price, units) in self.refund_unit_prices(sku, quantity)? {
+                lines.push(ReturnLine::new(
+                    sale_line.product().sku().clone(),
+                    units,
+                    unit_price,
+                ));
+            }
+        }
+        if !exceeded.is_empty() {
+            return Err(SharedError::new(
+                &format!("Returned quantity exceeds the sold one for: {}", exceeded.join(", ")),
+                409,
+            ));
+        }
+        Ok(lines)
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn lines(&self) -> &Vec<SaleLine> {
+        &self.lines
+    }
+    pub fn customer(&self) -> &Customer {
+        &self.customer
+    }
+    pub fn generated_at(&self) -> &DateTime<Utc> {
+        &self.generated_at
+    }
+    pub fn status(&self) -> SaleStatus {
+        self.status
+    }
+    pub fn cancel_reason(&self) -> &Option<String> {
+        &self.cancel_reason
+    }
+    pub fn cancelled_at(&self) -> &Option<DateTime<Utc>> {
+        &self.cancelled_at
+    }
+    pub fn returns(&self) -> &Vec<SaleReturn> {
+        &self.returns
+    }
+    pub fn exchange_of(&self) -> Option<u32> {
+        self.exchange_of
+    }
+    pub fn discounts(&self) -> &Vec<AppliedDiscount> {
+        &self.discounts
+    }
+    pub fn payments(&self) -> &Vec<Payment> {
+        &self.payments
+    }
+    pub fn change(&self) -> Result<Money, SharedError> {
+        Money::checked_sum(
+            self.payments
+                .iter()
+                .map(|p| p.change())
+                .collect::<Result<Vec<Money>, SharedError>>()?,
+        )
+    }
+    pub fn invoice_number(&self) -> &Option<InvoiceNumber> {
+        &self.invoice_number
+    }
+}
+
+/// Link between a sale, the return of some of its products and the sale replacing them
+#[derive(Clone)]
+pub struct Exchange {
+    id: u32,
+    original_sale_id: u32,
+    return_id: u32,
+    replacement_sale_id: u32,
+    /// Positive when the customer owes it, negative when it is owed to the customer
+    price_difference: Money,
+    created_at: DateTime<Utc>,
+}
+impl Exchange {
+    pub fn new(
+        id: u32,
+        original_sale_id: u32,
+        return_id: u32,
+        replacement_sale_id: u32,
+        price_difference: Money,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Exchange {
+            id,
+            original_sale_id,
+            return_id,
+            replacement_sale_id,
+            price_difference,
+            created_at,
+        }
+    }
+    /// Amount the customer owes (positive) or is owed (negative) for swapping the returned products
+    pub fn price_difference_of(
+        sale_return: &SaleReturn,
+        replacement_sale: &Sale,
+    ) -> Result<Money, SharedError> {
+        replacement_sale
+            .total_amount()?
+            .checked_sub(sale_return.refunded_amount()?)
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn original_sale_id(&self) -> u32 {
+        self.original_sale_id
+    }
+    pub fn return_id(&self) -> u32 {
+        self.return_id
+    }
+    pub fn replacement_sale_id(&self) -> u32 {
+        self.replacement_sale_id
+    }
+    pub fn price_difference(&self) -> Money {
+        self.price_difference
+    }
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+}
+
+/// Range of consecutive invoice numbers authorized under a prefix
+#[derive(Clone)]
+pub struct InvoiceSeries {
+    id: u32,
+    prefix: String,
+    range_from: u64,
+    range_to: u64,
+    /// Number the next invoice of the series gets
+    next_number: u64,
+    /// Remaining numbers from which the series is about to run out
+    warning_threshold: u64,
+    active: bool,
+    created_at: DateTime<Utc>,
+}
+/// Fields of an invoice series, named so the ones of the same type cannot be swapped
+pub struct InvoiceSeriesParams {
+    pub id: u32,
+    pub prefix: String,
+    pub range_from: u64,
+    pub range_to: u64,
+    pub next_number: u64,
+    pub warning_threshold: u64,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+impl InvoiceSeries {
+    pub fn new(params: InvoiceSeriesParams) -> Self {
+        let InvoiceSeriesParams {
+            id,
+            prefix,
+            range_from,
+            range_to,
+            next_number,
+            warning_threshold,
+            active,
+            created_at,
+        } = params;
+        InvoiceSeries {
+            id,
+            prefix,
+            range_from,
+            range_to,
+            next_number,
+            warning_threshold,
+            active,
+            created_at,
+        }
+    }
+
+    /// Take the next number of the series, failing with 409 once the authorized range is used up
+    pub fn assign_next(&mut self) -> Result<InvoiceNumber, SharedError> {
+        if self.next_number > self.range_to {
+            return Err(SharedError::new(
+                format!(
+                    "Invoice series {} is exhausted, it was authorized up to {}",
+                    self.prefix, self.range_to
+                )
+                .as_str(),
+                409,
+            ));
+        }
+        let invoice_number = InvoiceNumber::new(self.prefix.clone(), self.next_number)?;
+        self.next_number += 1;
+        Ok(invoice_number)
+    }
+    pub fn remaining(&self) -> u64 {
+        (self.range_to + 1).saturating_sub(self.next_number)
+    }
+    pub fn is_near_end(&self) -> bool {
+        self.remaining() <= self.warning_threshold
+    }
+    /// Alert for a series that reached its warning threshold, raised once the number is committed
+    pub fn near_end_alert(&self) -> Option<InvoiceSeriesAlert> {
+        if !self.is_near_end() {
+            return None;
+        }
+        Some(InvoiceSeriesAlert {
+            prefix: self.prefix.clone(),
+            remaining: self.remaining(),
+            range_to: self.range_to,
+        })
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn prefix(&self) -> &String {
+        &self.prefix
+    }
+    pub fn range_from(&self) -> u64 {
+        self.range_from
+    }
+    pub fn range_to(&self) -> u64 {
+        self.range_to
+    }
+    pub fn next_number(&self) -> u64 {
+        self.next_number
+    }
+    pub fn warning_threshold(&self) -> u64 {
+        self.warning_threshold
+    }
+    pub fn active(&self) -> bool {
+        self.active
+    }
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+}
+
+/// Invoice series running out of authorized numbers
+#[derive(Debug, Clone)]
+pub struct InvoiceSeriesAlert {
+    prefix: String,
+    remaining: u64,
+    range_to: u64,
+}
+impl InvoiceSeriesAlert {
+    pub fn message(&self) -> String {
+        format!(
+            "Invoice series {} has {} numbers left, it is authorized up to {}",
+            self.prefix, self.remaining, self.range_to
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DraftSaleStatus {
+    Open,
+    Parked,
+    CheckedOut,
+}
+impl DraftSaleStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DraftSaleStatus::Open => "open",
+            DraftSaleStatus::Parked => "parked",
+            DraftSaleStatus::CheckedOut => "checked_out",
+        }
+    }
+    pub fn parse(status: &str) -> Result<Self, SharedError> {
+        match status {
+            "open" => Ok(DraftSaleStatus::Open),
+            "parked" => Ok(DraftSaleStatus::Parked),
+            "checked_out" => Ok(DraftSaleStatus::CheckedOut),
+            _ => Err(SharedError::new(
+                format!("Invalid draft sale status {}", status).as_str(),
+                400,
+            )),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct DraftItem {
+    product_sku: String,
+    quantity: u32,
+}
+impl DraftItem {
+    pub fn new(product_sku: String, quantity: u32) -> Self {
+        DraftItem {
+            product_sku,
+            quantity,
+        }
+    }
+
+    pub fn product_sku(&self) -> &String {
+        &self.product_sku
+    }
+    pub fn quantity(&self) -> u32 {
+        self.quantity
+    }
+}
+
+/// Sale being built over several requests, turned into a real sale on checkout.
+/// Stock is not reserved until then.
+#[derive(Clone)]
+pub struct DraftSale {
+    id: u32,
+    customer: Customer,
+    items: Vec<DraftItem>,
+    status: DraftSaleStatus,
+    /// Sale the draft was checked out into
+    sale_id: Option<u32>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+impl DraftSale {
+    pub fn new(
+        id: u32,
+        customer: Customer,
+        items: Vec<DraftItem>,
+        status: DraftSaleStatus,
+        sale_id: Option<u32>,
+        created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+    ) -> Self {
+        DraftSale {
+            id,
+            customer,
+            items,
+            status,
+            sale_id,
+            created_at,
+            updated_at,
+        }
+    }
+
+    /// Fail with 409 unless the draft is open, parked drafts have to be resumed first
+    pub fn check_open(&self) -> Result<(), SharedError> {
+        if self.status != DraftSaleStatus::Open {
+            return Err(SharedError::new(
+                format!(
+                    "Draft sale with ID {} is {}, it must be open",
+                    self.id,
+                    self.status.as_str()
+                )
+                .as_str(),
+                409,
+            ));
+        }
+        Ok(())
+    }
+    /// Add quantity units of the product, on top of the ones already in the draft
+    pub fn add_item(&mut self, product_sku: &str, quantity: u32) -> Result<(), SharedError> {
+        self.check_open()?;
+        SaleLine::validate_quantity(quantity)?;
+        match self.items.iter_mut().find(|i| i.product_sku == product_sku) {
+            Some(item) => {
+                let total = item.quantity.saturating_add(quantity);
+                SaleLine::validate_quantity(total)?;
+                item.quantity = total;
+            }
+            None => self.items.push(DraftItem::new(product_sku.to_string(), quantity)),
+        }
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+    /// Take quantity units of the product out of the draft, all of them if no quantity is given
+    pub fn remove_item(
+        &mut self,
+        product_sku: &str,
+        quantity: Option<u32>,
+    ) -> Result<(), SharedError> {
+        self.check_open()?;
+        let Some(position) = self.items.iter().position(|i| i.product_sku == product_sku) else {
+            return Err(SharedError::new(
+                format!(
+                    "Product with SKU {} is not in draft sale {}",
+                    product_sku, self.id
+                )
+                .as_str(),
+                404,
+            ));
+        };
+        match quantity {
+            Some(0) => {
+                return Err(SharedError::new("Quantity must be greater than 0", 400));
+            }
+            Some(quantity) if quantity < self.items[position].quantity => {
+                self.items[position].quantity -= quantity;
+            }
+            _ => {
+                self.items.remove(position);
+            }
+        }
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+    pub fn park(&mut self) -> Result<(), SharedError> {
+        self.check_open()?;
+        self.status = DraftSaleStatus::Parked;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+    pub fn resume(&mut self) -> Result<(), SharedError> {
+        if self.status != DraftSaleStatus::Parked {
+            return Err(SharedError::new(
+                format!("Draft sale with ID {} is not parked", self.id).as_str(),
+                409,
+            ));
+        }
+        self.status = DraftSaleStatus::Open;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+    /// Fail unless the draft is open (409) and has items (400)
+    pub fn check_can_check_out(&self) -> Result<(), SharedError> {
+        self.check_open()?;
+        if self.items.is_empty() {
+            return Err(SharedError::new(
+                format!("Draft sale with ID {} has no items", self.id).as_str(),
+                400,
+            ));
+        }
+        Ok(())
+    }
+    /// Close the draft once the sale made from it is registered
+    pub fn check_out(&mut self, sale_id: u32) -> Result<(), SharedError> {
+        self.check_can_check_out()?;
+        self.status = DraftSaleStatus::CheckedOut;
+        self.sale_id = Some(sale_id);
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+    /// Items as (SKU, quantity), the shape taken by the sale registration
+    pub fn skus_quantity(&self) -> Vec<(String, u32)> {
+        self.items
+            .iter()
+            .map(|i| (i.product_sku.clone(), i.quantity))
+            .collect()
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn customer(&self) -> &Customer {
+        &self.customer
+    }
+    pub fn items(&self) -> &Vec<DraftItem> {
+        &self.items
+    }
+    pub fn status(&self) -> DraftSaleStatus {
+        self.status
+    }
+    pub fn sale_id(&self) -> Option<u32> {
+        self.sale_id
+    }
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+    pub fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+}
+
+/// Output of a request stored under the key sent by the client, replayed when the request is retried
+#[derive(Clone)]
+pub struct IdempotencyRecord {
+    key: String,
+    /// SHA-256 of the request, tells apart a retry from another request reusing the key
+    request_hash: String,
+    /// Serialized output of the request
+    response: String,
+}
+impl IdempotencyRecord {
+    pub fn new(key: String, request_hash: String, response: String) -> Self {
+        IdempotencyRecord {
+            key,
+            request_hash,
+            response,
+        }
+    }
+    /// Check the record belongs to the given request, failing with 422 if the key was reused
+    pub fn check_request(&self, request_hash: &str) -> Result<(), SharedError> {
+        if self.request_hash != request_hash {
+            return Err(SharedError::new(
+                format!(
+                    "Idempotency key {} was already used with a different request",
+                    self.key
+                )
+                .as_str(),
+                422,
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn response(&self) -> &String {
+        &self.response
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElectronicInvoiceStatus {
+    /// Generated and stored, not yet accepted by the submitter
+    Generated,
+    Submitted,
+}
+impl ElectronicInvoiceStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ElectronicInvoiceStatus::Generated => "generated",
+            ElectronicInvoiceStatus::Submitted => "submitted",
+        }
+    }
+    pub fn parse(status: &str) -> Result<Self, SharedError> {
+        match status {
+            "generated" => Ok(ElectronicInvoiceStatus::Generated),
+            "submitted" => Ok(ElectronicInvoiceStatus::Submitted),
+            _ => Err(SharedError::new(
+                &format!("Invalid electronic invoice status {}", status),
+                400,
+            )),
+        }
+    }
+}
+
+/// UBL 2.1 document of a sale for the DIAN, never regenerated once stored so its CUFE
+/// stays the one submitted
+#[derive(Clone)]
+pub struct ElectronicInvoice {
+    sale_id: u32,
+    invoice_number: String,
+    /// SHA-384 of the invoice fields, the unique code of the invoice for the DIAN
+    cufe: String,
+    xml: String,
+    status: ElectronicInvoiceStatus,
+    created_at: DateTime<Utc>,
+    submitted_at: Option<DateTime<Utc>>,
+}
+impl ElectronicInvoice {
+    pub fn new(
+        sale_id: u32,
+        invoice_number: String,
+        cufe: String,
+        xml: String,
+        status: ElectronicInvoiceStatus,
+        created_at: DateTime<Utc>,
+        submitted_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        ElectronicInvoice {
+            sale_id,
+            invoice_number,
+            cufe,
+            xml,
+            status,
+            created_at,
+            submitted_at,
+        }
+    }
+
+    pub fn is_submitted(&self) -> bool {
+        self.status == ElectronicInvoiceStatus::Submitted
+    }
+    pub fn mark_submitted(&mut self, at: DateTime<Utc>) {
+        self.status = ElectronicInvoiceStatus::Submitted;
+        self.submitted_at = Some(at);
+    }
+
+    pub fn sale_id(&self) -> u32 {
+        self.sale_id
+    }
+    pub fn invoice_number(&self) -> &String {
+        &self.invoice_number
+    }
+    pub fn cufe(&self) -> &String {
+        &self.cufe
+    }
+    pub fn xml(&self) -> &String {
+        &self.xml
+    }
+    pub fn status(&self) -> ElectronicInvoiceStatus {
+        self.status
+    }
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+    pub fn submitted_at(&self) -> &Option<DateTime<Utc>> {
+        &self.submitted_at
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StockMovementReason {
+    Sale,
+    Return,
+    /// Units of a cancelled sale put back into stock
+    Cancellation,
+    /// Manual correction of the stock
+    Adjustment,
+    /// Units received from a supplier or loaded when registering the product
+    Receipt,
+}
+impl StockMovementReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StockMovementReason::Sale => "sale",
+            StockMovementReason::Return => "return",
+            StockMovementReason::Cancellation => "cancellation",
+            StockMovementReason::Adjustment => "adjustment",
+            StockMovementReason::Receipt => "receipt",
+        }
+    }
+    pub fn parse(reason: &str) -> Result<Self, SharedError> {
+        match reason {
+            "sale" => Ok(StockMovementReason::Sale),
+            "return" => Ok(StockMovementReason::Return),
+            "cancellation" => Ok(StockMovementReason::Cancellation),
+            "adjustment" => Ok(StockMovementReason::Adjustment),
+            "receipt" => Ok(StockMovementReason::Receipt),
+            _ => Err(SharedError::new(
+                &format!("Invalid stock movement reason {}", reason),
+                400,
+            )),
+        }
+    }
+}
+
+/// Cause of a manual stock adjustment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StockAdjustmentReason {
+    Damage,
+    Theft,
+    /// Stock corrected after a physical count
+    Recount,
+    Gift,
+}
+impl StockAdjustmentReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StockAdjustmentReason::Damage => "damage",
+            StockAdjustmentReason::Theft => "theft",
+            StockAdjustmentReason::Recount => "recount",
+            StockAdjustmentReason::Gift => "gift",
+        }
+    }
+    pub fn parse(reason: &str) -> Result<Self, SharedError> {
+        match reason {
+            "damage" => Ok(StockAdjustmentReason::Damage),
+            "theft" => Ok(StockAdjustmentReason::Theft),
+            "recount" => Ok(StockAdjustmentReason::Recount),
+            "gift" => Ok(StockAdjustmentReason::Gift),
+            _ => Err(SharedError::new(
+                &format!(
+                    "Invalid stock adjustment reason {}, expected damage, theft, recount or gift",
+                    reason
+                ),
+                400,
+            )),
+        }
+    }
+}
+
+/// Product whose stock reached its reorder point, raised once the change is committed
+#[derive(Debug, Clone)]
+pub struct LowStockAlert {
+    sku: String,
+    stock: u32,
+    reason: StockMovementReason,
+    reorder_point: u32,
+    reorder_quantity: Option<u32>,
+}
+impl LowStockAlert {
+    pub fn message(&self) -> String {
+        format!(
+            "Low stock: {} has {} units left after a {}, reorder point {}, reorder quantity {}",
+            self.sku,
+            self.stock,
+            self.reason.as_str(),
+            self.reorder_point,
+            self.reorder_quantity
+                .map(|quantity| quantity.to_string())
+                .unwrap_or("not set".to_string())
+        )
+    }
+}
+
+/// Why and by whom the stock of a product is changed, recorded along the change
+#[derive(Debug, Clone)]
+pub struct StockChange {
+    pub reason: StockMovementReason,
+    /// Set when the change is a manual adjustment
+    pub adjustment_reason: Option<StockAdjustmentReason>,
+    /// Id of the sale, return or product the change comes from
+    pub reference_id: Option<u32>,
+    pub user: String,
+}
+impl StockChange {
+    pub fn new(reason: StockMovementReason, reference_id: Option<u32>, user: &str) -> Self {
+        StockChange {
+            reason,
+            adjustment_reason: None,
+            reference_id,
+            user: user.to_string(),
+        }
+    }
+    /// Manual adjustment of the stock of a product
+    pub fn adjustment(reason: StockAdjustmentReason, product_id: u32, user: &str) -> Self {
+        StockChange {
+            reason: StockMovementReason::Adjustment,
+            adjustment_reason: Some(reason),
+            reference_id: Some(product_id),
+            user: user.to_string(),
+        }
+    }
+}
+
+/// Entry of the inventory ledger, one per stock change of a product
+#[derive(Clone)]
+pub struct StockMovement {
+    id: u32,
+    product_id: u32,
+    /// Units added, negative when taken out
+    delta: i64,
+    resulting_stock: u32,
+    reason: StockMovementReason,
+    adjustment_reason: Option<StockAdjustmentReason>,
+    reference_id: Option<u32>,
+    user: String,
+    created_at: DateTime<Utc>,
+}
+/// Fields of a stock movement, named so the ones of the same type cannot be swapped
+pub struct StockMovementParams {
+    pub id: u32,
+    pub product_id: u32,
+    pub delta: i64,
+    pub resulting_stock: u32,
+    pub reason: StockMovementReason,
+    pub adjustment_reason: Option<StockAdjustmentReason>,
+    pub reference_id: Option<u32>,
+    pub user: String,
+    pub created_at: DateTime<Utc>,
+}
+impl StockMovement {
+    pub fn new(params: StockMovementParams) -> Self {
+        let StockMovementParams {
+            id,
+            product_id,
+            delta,
+            resulting_stock,
+            reason,
+            adjustment_reason,
+            reference_id,
+            user,
+            created_at,
+        } = params;
+        StockMovement {
+            id,
+            product_id,
+            delta,
+            resulting_stock,
+            reason,
+            adjustment_reason,
+            reference_id,
+            user,
+            created_at,
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn product_id(&self) -> u32 {
+        self.product_id
+    }
+    pub fn delta(&self) -> i64 {
+        self.delta
+    }
+    pub fn resulting_stock(&self) -> u32 {
+        self.resulting_stock
+    }
+    pub fn reason(&self) -> StockMovementReason {
+        self.reason
+    }
+    pub fn adjustment_reason(&self) -> Option<StockAdjustmentReason> {
+        self.adjustment_reason
+    }
+    pub fn reference_id(&self) -> Option<u32> {
+        self.reference_id
+    }
+    pub fn user(&self) -> &String {
+        &self.user
+    }
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn product(sku: &str, price: i64, categories: Vec<Category>) -> Product {
+        Product::new(ProductParams {
+            id: 1,
+            sku: sku.to_string(),
+            name: sku.to_string(),
+            price: Money::from_minor(price),
+            tax_class: TaxClass::General,
+            stock: 100,
+            reorder_point: None,
+            reorder_quantity: None,
+            variant: None,
+            has_variants: false,
+            categories,
+            img_url: None,
+            description: None,
+            archived_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        })
+    }
+
+    fn promotion(
+        scope: PromotionScope,
+        rule: PromotionRule,
+        category_id: Option<u32>,
+    ) -> Promotion {
+        Promotion::new(PromotionParams {
+            id: 7,
+            name: "Promotion".to_string(),
+            code: None,
+            scope,
+            rule,
+            category_id,
+            starts_at: None,
+            ends_at: None,
+            active: true,
+            created_at: Utc::now(),
+        })
+    }
+
+    fn amounts(discounts: &[AppliedDiscount]) -> Vec<(Option<String>, i64)> {
+        discounts
+            .iter()
+            .map(|d| (d.product_sku().clone(), d.amount().minor()))
+            .collect()
+    }
+
+    #[test]
+    fn buy_two_pay_one_discounts_the_free_units() {
+        let lines = vec![SaleLine::from_product(product("A1", 10_000, vec![]), 5)];
+        let promotion = promotion(
+            PromotionScope::Line,
+            PromotionRule::BuyXPayY { buy: 2, pay: 1 },
+            None,
+        );
+        let discounts = promotion.discounts_for(&lines, &[]).unwrap();
+        assert_eq!(amounts(&discounts), vec![(Some("A1".to_string()), 20_000)]);
+    }
+
+    #[test]
+    fn buy_two_pay_one_skips_lines_without_enough_units() {
+        let lines = vec![SaleLine::from_product(product("A1", 10_000, vec![]), 1)];
+        let promotion = promotion(
+            PromotionScope::Line,
+            PromotionRule::BuyXPayY { buy: 2, pay: 1 },
+            None,
+        );
+        assert!(promotion.discounts_for(&lines, &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn percentage_discounts_each_line() {
+        let lines = vec![
+            SaleLine::from_product(product("A1", 15_000, vec![]), 3),
+            SaleLine::from_product(product("A2", 999, vec![]), 1),
+        ];
+        let promotion = promotion(PromotionScope::Line, PromotionRule::Percentage(10.0), None);
+        let discounts = promotion.discounts_for(&lines, &[]).unwrap();
+        assert_eq!(
+            amounts(&discounts),
+            vec![
+                (Some("A1".to_string()), 4_500),
+                (Some("A2".to_string()), 100)
+            ]
+        );
+    }
+
+    #[test]
+    fn percentage_over_the_sale_applies_after_line_discounts() {
+        let lines = vec![SaleLine::from_product(product("A1", 10_000, vec![]), 2)];
+        let applied = vec![AppliedDiscount::new(
+            Some(1),
+            Some("A1".to_string()),
+            "Line".to_string(),
+            Money::from_minor(5_000),
+        )];
+        let promotion = promotion(PromotionScope::Sale, PromotionRule::Percentage(20.0), None);
+        let discounts = promotion.discounts_for(&lines, &applied).unwrap();
+        assert_eq!(amounts(&discounts), vec![(None, 3_000)]);
+    }
+
+    #[test]
+    fn fixed_amount_per_unit_never_exceeds_the_unit_price() {
+        let lines = vec![
+            SaleLine::from_product(product("A1", 2_000, vec![]), 2),
+            SaleLine::from_product(product("A2", 10_000, vec![]), 3),
+        ];
+        let promotion = promotion(
+            PromotionScope::Line,
+            PromotionRule::FixedAmount(Money::from_minor(3_000)),
+            None,
+        );
+        let discounts = promotion.discounts_for(&lines, &[]).unwrap();
+        assert_eq!(
+            amounts(&discounts),
+            vec![
+                (Some("A1".to_string()), 4_000),
+                (Some("A2".to_string()), 9_000)
+            ]
+        );
+    }
+
+    #[test]
+    fn fixed_amount_over_the_sale_is_capped_at_what_is_left() {
+        let lines = vec![SaleLine::from_product(product("A1", 10_000, vec![]), 1)];
+        let applied = vec![AppliedDiscount::new(
+            Some(1),
+            Some("A1".to_string()),
+            "Line".to_string(),
+            Money::from_minor(8_000),
+        )];
+        let promotion = promotion(
+            PromotionScope::Sale,
+            PromotionRule::FixedAmount(Money::from_minor(5_000)),
+            None,
+        );
+        let discounts = promotion.discounts_for(&lines, &applied).unwrap();
+        assert_eq!(amounts(&discounts), vec![(None, 2_000)]);
+    }
+
+    #[test]
+    fn line_discounts_never_exceed_the_line_amount() {
+        let lines = vec![SaleLine::from_product(product("A1", 10_000, vec![]), 1)];
+        let applied = vec![AppliedDiscount::new(
+            Some(1),
+            Some("A1".to_string()),
+            "Line".to_string(),
+            Money::from_minor(10_000),
+        )];
+        let promotion = promotion(PromotionScope::Line, PromotionRule::Percentage(50.0), None);
+        assert!(
+            promotion
+                .discounts_for(&lines, &applied)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn category_promotion_discounts_its_subcategories_only() {
+        let categories = vec![
+            Category::new(1, "Men".to_string(), None),
+            Category::new(2, "Shirts".to_string(), Some(1)),
+            Category::new(3, "Women".to_string(), None),
+        ];
+        let lines = vec![
+            SaleLine::from_product(product("A1", 10_000, vec![categories[1].clone()]), 1),
+            SaleLine::from_product(product("A2", 10_000, vec![categories[2].clone()]), 1),
+            SaleLine::from_product(product("A3", 10_000, vec![]), 1),
+        ];
+        let mut promotion = promotion(
+            PromotionScope::Line,
+            PromotionRule::Percentage(10.0),
+            Some(1),
+        );
+        assert!(promotion.discounts_for(&lines, &[]).unwrap().is_empty());
+
+        promotion.include_subcategories(&categories);
+        let discounts = promotion.discounts_for(&lines, &[]).unwrap();
+        assert_eq!(amounts(&discounts), vec![(Some("A1".to_string()), 1_000)]);
+    }
+
+    fn invoice_series(range_from: u64, range_to: u64, warning_threshold: u64) -> InvoiceSeries {
+        InvoiceSeries::new(InvoiceSeriesParams {
+            id: 1,
+            prefix: "FV".to_string(),
+            range_from,
+            range_to,
+            next_number: range_from,
+            warning_threshold,
+            active: true,
+            created_at: Utc::now(),
+        })
+    }
+
+    #[test]
+    fn assign_next_takes_consecutive_numbers() {
+        let mut series = invoice_series(1000, 1002, 0);
+        assert_eq!(series.assign_next().unwrap().to_string(), "FV1000");
+        assert_eq!(series.assign_next().unwrap().to_string(), "FV1001");
+        assert_eq!(series.next_number(), 1002);
+    }
+
+    #[test]
+    fn assign_next_fails_once_the_range_is_used_up() {
+        let mut series = invoice_series(1, 2, 0);
+        assert_eq!(series.assign_next().unwrap().number(), 1);
+        assert_eq!(series.assign_next().unwrap().number(), 2);
+        let err = series.assign_next().unwrap_err();
+        assert_eq!(err.code, 409);
+        assert_eq!(series.next_number(), 3);
+        assert_eq!(series.remaining(), 0);
+    }
+
+    #[test]
+    fn is_near_end_once_the_remaining_numbers_reach_the_threshold() {
+        let mut series = invoice_series(1, 5, 2);
+        assert_eq!(series.remaining(), 5);
+        assert!(!series.is_near_end());
+        series.assign_next().unwrap();
+        series.assign_next().unwrap();
+        assert_eq!(series.remaining(), 3);
+        assert!(!series.is_near_end());
+        series.assign_next().unwrap();
+        assert_eq!(series.remaining(), 2);
+        assert!(series.is_near_end());
+    }
+
+    #[test]
+    fn exhausted_series_is_near_end_even_without_threshold() {
+        let mut series = invoice_series(1, 1, 0);
+        assert!(!series.is_near_end());
+        series.assign_next().unwrap();
+        assert!(series.is_near_end());
+    }
+
+    #[test]
+    fn near_end_alert_is_raised_only_once_near_end() {
+        let mut series = invoice_series(1, 3, 1);
+        series.assign_next().unwrap();
+        assert!(series.near_end_alert().is_none());
+        series.assign_next().unwrap();
+        let alert = series.near_end_alert().unwrap();
+        assert_eq!(
+            alert.message(),
+            "Invoice series FV has 1 numbers left, it is authorized up to 3"
+        );
+    }
+
+    /// Sale of 3 units of A1 that paid 200 for them
+    fn sale_paying_200_for_3_units(returns: Vec<SaleReturn>) -> Sale {
+        let customer = Customer::new(
+            1,
+            CC::new("1000000".to_string()).unwrap(),
+            "Customer".to_string(),
+            Email::new("customer@example.com".to_string()).unwrap(),
+            None,
+            None,
+            Utc::now(),
+            Utc::now(),
+        );
+        Sale::new(SaleParams {
+            id: 1,
+            lines: vec![SaleLine::from_product(product("A1", 200, vec![]), 3)],
+            customer,
+            generated_at: Utc::now(),
+            status: SaleStatus::Active,
+            cancel_reason: None,
+            cancelled_at: None,
+            returns,
+            exchange_of: None,
+            discounts: vec![AppliedDiscount::new(
+                None,
+                Some("A1".to_string()),
+                "Line".to_string(),
+                Money::from_minor(400),
+            )],
+            payments: Vec::new(),
+            invoice_number: None,
+        })
+    }
+
+    fn refunds(lines: &[ReturnLine]) -> Vec<(i64, u32)> {
+        lines
+            .iter()
+            .map(|line| (line.unit_price().minor(), line.quantity()))
+            .collect()
+    }
+
+    #[test]
+    fn returning_every_unit_refunds_exactly_what_was_paid() {
+        let sale = sale_paying_200_for_3_units(Vec::new());
+        let lines = sale.return_lines(&[("A1".to_string(), 3)]).unwrap();
+        assert_eq!(refunds(&lines), vec![(67, 2), (66, 1)]);
+        let refunded = SaleReturn::new(1, lines, None, Utc::now())
+            .refunded_amount()
+            .unwrap();
+        assert_eq!(refunded.minor(), 200);
+    }
+
+    #[test]
+    fn returning_units_one_by_one_refunds_what_is_left() {
+        let mut returns = Vec::new();
+        let mut refunded = 0;
+        for id in 1..=3 {
+            let sale = sale_paying_200_for_3_units(returns.clone());
+            let lines = sale.return_lines(&[("A1".to_string(), 1)]).unwrap();
+            let sale_return = SaleReturn::new(id, lines, None, Utc::now());
+            refunded += sale_return.refunded_amount().unwrap().minor();
+            returns.push(sale_return);
+        }
+        assert_eq!(refunded, 200);
+        let sale = sale_paying_200_for_3_units(returns);
+        assert!(sale.return_lines(&[("A1".to_string(), 1)]).is_err());
     }
 }
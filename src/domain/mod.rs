@@ -1,3 +1,4 @@
 pub mod value_objects;
 pub mod entities;
 pub mod repositories;
+pub mod unit_of_work;
@@ -1,15 +1,54 @@
-use super::entities::{Customer, Product, Sale};
-use super::value_objects::{CC, Email, Phone, Url};
+use super::entities::{
+    AppliedDiscount, Category, Customer, DraftSale, DraftSaleStatus, ElectronicInvoice, Exchange,
+    IdempotencyRecord, InvoiceSeries, InvoiceSeriesAlert, Payment, Product, Promotion,
+    PromotionRule, PromotionScope, ReturnLine, Sale, SaleLine, SaleReturn, StockChange,
+    StockMovement, TaxClass,
+};
+use chrono::{DateTime, Utc};
+use super::unit_of_work::Transaction;
+use super::value_objects::{CC, Email, InvoiceNumber, Money, Phone, Url};
 use crate::shared::{Pagination, PaginationResult, SharedError};
 
 #[async_trait::async_trait]
 pub trait SaleRepository {
     async fn get_by_id(&self, id: &u32) -> Option<Sale>;
+    async fn get_by_invoice_number(&self, invoice_number: &InvoiceNumber) -> Option<Sale>;
     async fn create(
         &self,
+        tx: &mut dyn Transaction,
         customer: &Customer,
-        products_sale: Vec<(&Product, u32)>,
+        invoice_number: InvoiceNumber,
+        lines: Vec<SaleLine>,
+        discounts: Vec<AppliedDiscount>,
+        payments: Vec<Payment>,
     ) -> Result<Sale, SharedError>;
+    async fn create_exchange(
+        &self,
+        tx: &mut dyn Transaction,
+        original_sale: &Sale,
+        sale_return: &SaleReturn,
+        replacement_sale: &Sale,
+    ) -> Result<Exchange, SharedError>;
+    /// Persist the cancellation of an active sale, failing with 409 if it was already cancelled
+    async fn save_cancellation(
+        &self,
+        tx: &mut dyn Transaction,
+        sale: &Sale,
+    ) -> Result<(), SharedError>;
+    /// Lock the sale until the transaction ends, serializing the changes made over it, and read
+    /// it within the transaction. Fails with 404 if it does not exist.
+    async fn get_by_id_for_update(
+        &self,
+        tx: &mut dyn Transaction,
+        id: &u32,
+    ) -> Result<Sale, SharedError>;
+    async fn create_return(
+        &self,
+        tx: &mut dyn Transaction,
+        sale: &Sale,
+        lines: Vec<ReturnLine>,
+        reason: Option<&str>,
+    ) -> Result<SaleReturn, SharedError>;
     async fn get_paginated(&self, pagination: &Pagination) -> PaginationResult<Sale>;
     async fn get_all_by_customer_cc(&self, cc: &CC) -> Vec<Sale>;
     async fn get_all(&self) -> Vec<Sale>;
@@ -18,19 +57,123 @@ pub trait SaleRepository {
 pub trait ProductRepository {
     async fn get_by_id(&self, id: u32) -> Option<Product>;
     async fn get_by_sku(&self, sku: &str) -> Option<Product>;
-    async fn get_many_by_skus(&self, skus: Vec<&str>) -> Vec<Product>;
+    async fn get_many_by_skus(&self, skus: Vec<&str>) -> Result<Vec<Product>, SharedError>;
+    /// Create the product without stock, which is only loaded through a recorded stock change
     async fn create(
         &self,
+        tx: &mut dyn Transaction,
         sku: &str,
         name: &str,
-        price: i64,
-        stock: u32,
-        flags: &Vec<String>,
+        price: Money,
+        tax_class: TaxClass,
+        reorder_point: Option<u32>,
+        reorder_quantity: Option<u32>,
+        categories: &[Category],
         img_url: Option<&Url>,
         description: Option<&String>,
     ) -> Result<Product, SharedError>;
+    /// Create a variant of the parent product without stock, copying the parent details and
+    /// categories
+    async fn create_variant(
+        &self,
+        tx: &mut dyn Transaction,
+        parent: &Product,
+        sku: &str,
+        name: &str,
+        size: Option<&String>,
+        color: Option<&String>,
+    ) -> Result<Product, SharedError>;
+    async fn get_variants(&self, parent_id: u32) -> Vec<Product>;
+    /// Persist the product details, the stock is left untouched. Variants of a parent follow
+    /// its tax class, and its price unless they override it.
     async fn save(&self, product: &Product) -> Result<(), SharedError>;
-    async fn get_paginated(&self, pagination: &Pagination) -> PaginationResult<Product>;
+    /// Persist whether the product is archived. Variants follow their parent, except the ones
+    /// archived on their own, which stay archived when the parent is unarchived
+    async fn save_archived(&self, product: &Product) -> Result<(), SharedError>;
+    /// Take quantity units out of the product stock, failing with 409 if there are not enough,
+    /// and record the movement in the same transaction. Returns the stock left, low stock is
+    /// alerted by the caller once the transaction is committed.
+    async fn decrement_stock(
+        &self,
+        tx: &mut dyn Transaction,
+        product: &Product,
+        quantity: u32,
+        change: &StockChange,
+    ) -> Result<u32, SharedError>;
+    /// Put quantity units back into the product stock and record the movement in the same
+    /// transaction. Returns the stock left.
+    async fn increment_stock(
+        &self,
+        tx: &mut dyn Transaction,
+        product: &Product,
+        quantity: u32,
+        change: &StockChange,
+    ) -> Result<u32, SharedError>;
+    /// Active products with a reorder point and their stock at or below it, lowest stock first
+    async fn get_low_stock(&self) -> Vec<Product>;
+    async fn get_paginated(
+        &self,
+        pagination: &Pagination,
+        filter: &ProductFilter,
+    ) -> PaginationResult<Product>;
+    /// Active products that can be sold whose SKU or name contain the term, best matches first
+    async fn autocomplete(&self, term: &str, limit: u32) -> Vec<Product>;
+}
+
+/// Conditions the listed products have to meet, all of them optional
+#[derive(Debug, Clone, Default)]
+pub struct ProductFilter {
+    /// Part of the SKU or the name, ignoring case and accents
+    pub search: Option<String>,
+    /// Products in any of these categories
+    pub category_ids: Option<Vec<u32>>,
+    pub min_price: Option<Money>,
+    pub max_price: Option<Money>,
+    /// Only products with stock left
+    pub in_stock: bool,
+    /// Order of the products, by id when not given
+    pub sort: Option<ProductSort>,
+    /// Only archived products, which are left out otherwise
+    pub archived: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProductSort {
+    /// Alphabetically
+    Name,
+    /// Cheapest first
+    Price,
+    /// Lowest stock first
+    Stock,
+    /// Last registered first
+    Newest,
+}
+impl ProductSort {
+    pub fn parse(value: &str) -> Result<Self, SharedError> {
+        match value {
+            "name" => Ok(ProductSort::Name),
+            "price" => Ok(ProductSort::Price),
+            "stock" => Ok(ProductSort::Stock),
+            "newest" => Ok(ProductSort::Newest),
+            _ => Err(SharedError::new(
+                &format!("Invalid product sort {}", value),
+                400,
+            )),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+pub trait CategoryRepository {
+    async fn get_all(&self) -> Vec<Category>;
+    async fn get_by_id(&self, id: u32) -> Option<Category>;
+    async fn get_many_by_ids(&self, ids: &[u32]) -> Vec<Category>;
+    /// Create the category, failing with 409 if the name is taken
+    async fn create(&self, name: &str, parent_id: Option<u32>) -> Result<Category, SharedError>;
+    /// Persist the name and parent of the category, failing with 409 if the name is taken
+    async fn save(&self, category: &Category) -> Result<(), SharedError>;
+    /// Delete the category, failing with 409 if it has subcategories, products or promotions
+    async fn delete(&self, id: u32) -> Result<(), SharedError>;
 }
 
 #[async_trait::async_trait]
@@ -50,3 +193,89 @@ pub trait CustomerRepository {
     async fn get_paginated(&self, pagination: &Pagination) -> PaginationResult<Customer>;
     async fn get_all(&self) -> Vec<Customer>;
 }
+
+#[async_trait::async_trait]
+pub trait PromotionRepository {
+    /// Promotions targeting a category are loaded with its subcategories included
+    async fn get_by_code(&self, code: &str) -> Option<Promotion>;
+    /// Promotions without coupon code available at the given time
+    async fn get_automatic_available_at(&self, at: &DateTime<Utc>) -> Vec<Promotion>;
+    async fn create(
+        &self,
+        name: &str,
+        code: Option<&str>,
+        scope: PromotionScope,
+        rule: PromotionRule,
+        category_id: Option<u32>,
+        starts_at: Option<&DateTime<Utc>>,
+        ends_at: Option<&DateTime<Utc>>,
+    ) -> Result<Promotion, SharedError>;
+    async fn set_active(&self, id: u32, active: bool) -> Result<(), SharedError>;
+    async fn get_paginated(&self, pagination: &Pagination) -> PaginationResult<Promotion>;
+}
+
+#[async_trait::async_trait]
+pub trait IdempotencyRepository {
+    /// Record stored under the key, as long as it has not expired
+    async fn get_by_key(&self, key: &str) -> Option<IdempotencyRecord>;
+    /// Store the record with the work of the transaction, failing with 409 if the key is in use
+    async fn create(
+        &self,
+        tx: &mut dyn Transaction,
+        key: &str,
+        request_hash: &str,
+        response: &str,
+    ) -> Result<IdempotencyRecord, SharedError>;
+}
+
+#[async_trait::async_trait]
+pub trait DraftSaleRepository {
+    async fn get_by_id(&self, id: u32) -> Option<DraftSale>;
+    /// Drafts in any of the given statuses, most recently updated first
+    async fn get_all_by_status(&self, statuses: &[DraftSaleStatus]) -> Vec<DraftSale>;
+    async fn create(&self, customer: &Customer) -> Result<DraftSale, SharedError>;
+    /// Lock the draft until the transaction ends, serializing the changes made over it
+    async fn lock(&self, tx: &mut dyn Transaction, id: u32) -> Result<(), SharedError>;
+    /// Persist the status and items of the draft, failing with 409 if it was already checked out
+    async fn save(&self, tx: &mut dyn Transaction, draft: &DraftSale) -> Result<(), SharedError>;
+}
+
+#[async_trait::async_trait]
+pub trait InvoiceSeriesRepository {
+    async fn get_all(&self) -> Vec<InvoiceSeries>;
+    async fn create(
+        &self,
+        prefix: &str,
+        range_from: u64,
+        range_to: u64,
+        warning_threshold: u64,
+    ) -> Result<InvoiceSeries, SharedError>;
+    /// Make the series the one numbering new sales, deactivating the rest
+    async fn activate(&self, id: u32) -> Result<(), SharedError>;
+    /// Take the next number of the active series, locking it until the transaction ends so
+    /// numbers are consecutive and only used up by committed sales. Returns the alert to raise
+    /// once committed if the series is near its end.
+    async fn next_number(
+        &self,
+        tx: &mut dyn Transaction,
+    ) -> Result<(InvoiceNumber, Option<InvoiceSeriesAlert>), SharedError>;
+}
+
+#[async_trait::async_trait]
+pub trait ElectronicInvoiceRepository {
+    async fn get_by_sale_id(&self, sale_id: u32) -> Option<ElectronicInvoice>;
+    /// Store a new document, failing with 409 if the sale already has one
+    async fn create(&self, invoice: &ElectronicInvoice) -> Result<(), SharedError>;
+    /// Persist the submission status of the document
+    async fn save_status(&self, invoice: &ElectronicInvoice) -> Result<(), SharedError>;
+}
+
+#[async_trait::async_trait]
+pub trait StockMovementRepository {
+    /// Movements of the product, newest first
+    async fn get_paginated_by_product(
+        &self,
+        product_id: u32,
+        pagination: &Pagination,
+    ) -> PaginationResult<StockMovement>;
+}
@@ -0,0 +1,58 @@
+use std::{any::Any, future::Future, pin::Pin};
+
+use crate::shared::SharedError;
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Max attempts of a unit of work failing by retryable errors (e.g. deadlocks)
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Transaction shared by the repositories taking part in a unit of work
+#[async_trait::async_trait]
+pub trait Transaction: Send {
+    /// Concrete transaction, used by the repositories implementations
+    fn as_any(&mut self) -> &mut (dyn Any + Send);
+    /// Whether the work failed by an error that can be solved retrying it
+    fn is_retryable(&self) -> bool;
+    async fn commit(self: Box<Self>) -> Result<(), SharedError>;
+    /// Undo the work of the transaction, logging why if it cannot be rolled back
+    async fn rollback(self: Box<Self>) -> Result<(), SharedError>;
+}
+
+#[async_trait::async_trait]
+pub trait UnitOfWork {
+    async fn begin(&self) -> Result<Box<dyn Transaction>, SharedError>;
+}
+
+/// Run work inside a transaction, committing if it succeeds and rolling back if it fails.
+/// Work failing by retryable errors is run again up to MAX_ATTEMPTS times.
+/// The ctx is handed back to the work so it can borrow data living outside the transaction.
+pub async fn run_in_transaction<C, T, F>(
+    unit_of_work: &(dyn UnitOfWork + Send + Sync),
+    ctx: &C,
+    mut work: F,
+) -> Result<T, SharedError>
+where
+    C: Sync + ?Sized,
+    F: for<'t> FnMut(&'t mut dyn Transaction, &'t C) -> BoxFuture<'t, Result<T, SharedError>>
+        + Send,
+{
+    let mut attempt = 1;
+    loop {
+        let mut tx = unit_of_work.begin().await?;
+        match work(tx.as_mut(), ctx).await {
+            Ok(output) => {
+                tx.commit().await?;
+                return Ok(output);
+            }
+            Err(err) => {
+                let retry = tx.is_retryable() && attempt < MAX_ATTEMPTS;
+                // A failed rollback is logged by the transaction, the work error is the one returned
+                if tx.rollback().await.is_err() || !retry {
+                    return Err(err);
+                }
+                attempt += 1;
+            }
+        }
+    }
+}
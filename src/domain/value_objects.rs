@@ -111,3 +111,347 @@ impl Url {
         }
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Currency {
+    #[default]
+    Cop,
+}
+impl Currency {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::Cop => "COP",
+        }
+    }
+    /// Number of decimal digits of the minor unit
+    pub fn decimals(&self) -> u32 {
+        match self {
+            Currency::Cop => 2,
+        }
+    }
+}
+
+/// Amount of money in minor units (cents) of its currency
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Money {
+    minor: i64,
+    currency: Currency,
+}
+impl Money {
+    pub fn new(minor: i64, currency: Currency) -> Self {
+        Money { minor, currency }
+    }
+    /// Amount in minor units of the default currency
+    pub fn from_minor(minor: i64) -> Self {
+        Money::new(minor, Currency::default())
+    }
+    pub fn zero() -> Self {
+        Money::from_minor(0)
+    }
+    /// Parse a decimal amount such as "1500", "-3.5" or "19900.90" of the default currency
+    pub fn parse(value: &str) -> Result<Self, SharedError> {
+        let currency = Currency::default();
+        let invalid = || SharedError::new(&format!("Invalid money amount {}", value), 400);
+        let (negative, digits) = match value.trim().strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, value.trim()),
+        };
+        let (units, decimals) = digits.split_once('.').unwrap_or((digits, ""));
+        if units.is_empty()
+            || !units.chars().all(|c| c.is_ascii_digit())
+            || !decimals.chars().all(|c| c.is_ascii_digit())
+            || decimals.len() > currency.decimals() as usize
+        {
+            return Err(invalid());
+        }
+        let scale = 10_i64.pow(currency.decimals());
+        let decimals = format!("{:0<width$}", decimals, width = currency.decimals() as usize);
+        let minor = units
+            .parse::<i64>()
+            .ok()
+            .and_then(|units| units.checked_mul(scale))
+            .and_then(|minor| minor.checked_add(decimals.parse::<i64>().unwrap_or(0)))
+            .ok_or_else(invalid)?;
+        Ok(Money::new(if negative { -minor } else { minor }, currency))
+    }
+
+    pub fn minor(&self) -> i64 {
+        self.minor
+    }
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+    pub fn is_zero(&self) -> bool {
+        self.minor == 0
+    }
+    pub fn is_positive(&self) -> bool {
+        self.minor > 0
+    }
+    pub fn is_negative(&self) -> bool {
+        self.minor < 0
+    }
+
+    pub fn checked_add(&self, other: Money) -> Result<Money, SharedError> {
+        self.same_currency(&other)?;
+        self.minor
+            .checked_add(other.minor)
+            .map(|minor| Money::new(minor, self.currency))
+            .ok_or_else(Money::overflow)
+    }
+    pub fn checked_sub(&self, other: Money) -> Result<Money, SharedError> {
+        self.same_currency(&other)?;
+        self.minor
+            .checked_sub(other.minor)
+            .map(|minor| Money::new(minor, self.currency))
+            .ok_or_else(Money::overflow)
+    }
+    pub fn checked_neg(&self) -> Result<Money, SharedError> {
+        self.minor
+            .checked_neg()
+            .map(|minor| Money::new(minor, self.currency))
+            .ok_or_else(Money::overflow)
+    }
+    pub fn checked_mul(&self, quantity: u32) -> Result<Money, SharedError> {
+        self.minor
+            .checked_mul(quantity as i64)
+            .map(|minor| Money::new(minor, self.currency))
+            .ok_or_else(Money::overflow)
+    }
+    /// Add up the amounts, failing with 400 if the total is out of range
+    pub fn checked_sum(amounts: impl IntoIterator<Item = Money>) -> Result<Money, SharedError> {
+        amounts
+            .into_iter()
+            .try_fold(Money::zero(), |total, amount| total.checked_add(amount))
+    }
+    /// The `part / whole` share of the amount, rounded half away from zero to the minor unit
+    pub fn proportion(&self, part: i64, whole: i64) -> Money {
+        if whole == 0 {
+            return Money::new(0, self.currency);
+        }
+        let numerator = (self.minor as i128) * (part as i128);
+        let whole = whole as i128;
+        let half = whole.abs() / 2;
+        let rounded = if (numerator < 0) == (whole < 0) {
+            (numerator.abs() + half) / whole.abs()
+        } else {
+            -((numerator.abs() + half) / whole.abs())
+        };
+        Money::new(rounded as i64, self.currency)
+    }
+    /// Split the amount in shares proportional to the weights that add up to it exactly, the
+    /// minor units left by rounding down going to the largest remainders
+    pub fn allocate(&self, weights: &[i64]) -> Vec<Money> {
+        let total: i128 = weights.iter().map(|weight| *weight as i128).sum();
+        if total <= 0 {
+            return weights
+                .iter()
+                .map(|_| Money::new(0, self.currency))
+                .collect();
+        }
+        let amount = (self.minor as i128).abs();
+        let mut shares: Vec<i128> = Vec::new();
+        let mut remainders: Vec<(usize, i128)> = Vec::new();
+        for (index, weight) in weights.iter().enumerate() {
+            let scaled = amount * (*weight as i128);
+            shares.push(scaled / total);
+            remainders.push((index, scaled % total));
+        }
+        let left = amount - shares.iter().sum::<i128>();
+        // Ties go to the first shares
+        remainders.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        for (index, _) in remainders.into_iter().take(left as usize) {
+            shares[index] += 1;
+        }
+        shares
+            .into_iter()
+            .map(|share| {
+                let share = share as i64;
+                Money::new(if self.minor < 0 { -share } else { share }, self.currency)
+            })
+            .collect()
+    }
+    /// The given percentage of the amount, rounded to the minor unit
+    pub fn percent(&self, percentage: f64) -> Money {
+        // Percentages are taken to the hundredth, as basis points
+        self.proportion((percentage * 100.0).round() as i64, 10_000)
+    }
+
+    fn same_currency(&self, other: &Money) -> Result<(), SharedError> {
+        if self.currency == other.currency {
+            Ok(())
+        } else {
+            Err(SharedError::new(
+                &format!(
+                    "Cannot operate {} with {}",
+                    self.currency.code(),
+                    other.currency.code()
+                ),
+                400,
+            ))
+        }
+    }
+    fn overflow() -> SharedError {
+        SharedError::new("Money amount out of range", 400)
+    }
+}
+impl ValueObject<i64> for Money {
+    fn value(&self) -> &i64 {
+        &self.minor
+    }
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+impl std::fmt::Display for Money {
+    /// Decimal amount without the currency, e.g. "-1500.50"
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scale = 10_u64.pow(self.currency.decimals());
+        let abs = self.minor.unsigned_abs();
+        write!(
+            f,
+            "{}{}.{:0width$}",
+            if self.minor < 0 { "-" } else { "" },
+            abs / scale,
+            abs % scale,
+            width = self.currency.decimals() as usize
+        )
+    }
+}
+
+/// Invoice number made of the series prefix and its consecutive, e.g. "FV1001"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvoiceNumber {
+    prefix: String,
+    number: u64,
+}
+impl InvoiceNumber {
+    pub fn new(prefix: String, number: u64) -> Result<Self, SharedError> {
+        if !(1..=4).contains(&prefix.len()) || !prefix.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(SharedError::new(
+                "Invoice prefix must have 1 to 4 uppercase letters",
+                400,
+            ));
+        }
+        Ok(InvoiceNumber { prefix, number })
+    }
+    /// Split a full invoice number into its letters prefix and its number
+    pub fn parse(value: &str) -> Result<Self, SharedError> {
+        let digits_at = value
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(value.len());
+        let (prefix, number) = value.split_at(digits_at);
+        match number.parse::<u64>() {
+            Ok(number) => InvoiceNumber::new(prefix.to_string(), number),
+            Err(_) => Err(SharedError::new(
+                format!("Invalid invoice number {}", value).as_str(),
+                400,
+            )),
+        }
+    }
+
+    pub fn prefix(&self) -> &String {
+        &self.prefix
+    }
+    pub fn number(&self) -> u64 {
+        self.number
+    }
+}
+impl std::fmt::Display for InvoiceNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.prefix, self.number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_units_and_decimals() {
+        assert_eq!(Money::parse("1500").unwrap().minor(), 150_000);
+        assert_eq!(Money::parse("19900.9").unwrap().minor(), 1_990_090);
+        assert_eq!(Money::parse(" 0.05 ").unwrap().minor(), 5);
+        assert_eq!(Money::parse("-3.5").unwrap().minor(), -350);
+    }
+
+    #[test]
+    fn parse_rejects_malformed_amounts() {
+        for value in ["", "-", ".5", "1.234", "1,5", "abc", "1.-5", "--1"] {
+            let err = Money::parse(value).unwrap_err();
+            assert_eq!(err.code, 400, "{}", value);
+        }
+    }
+
+    #[test]
+    fn parse_rejects_amounts_out_of_range() {
+        assert!(Money::parse("92233720368547758.07").is_ok());
+        assert!(Money::parse("92233720368547758.08").is_err());
+        assert!(Money::parse("99999999999999999999").is_err());
+    }
+
+    #[test]
+    fn proportion_rounds_half_away_from_zero() {
+        assert_eq!(Money::from_minor(100).proportion(1, 3).minor(), 33);
+        assert_eq!(Money::from_minor(200).proportion(1, 3).minor(), 67);
+        assert_eq!(Money::from_minor(5).proportion(1, 2).minor(), 3);
+        assert_eq!(Money::from_minor(-5).proportion(1, 2).minor(), -3);
+        assert_eq!(Money::from_minor(5).proportion(-1, 2).minor(), -3);
+        assert_eq!(Money::from_minor(100).proportion(1, 0).minor(), 0);
+    }
+
+    #[test]
+    fn proportion_does_not_overflow_on_large_amounts() {
+        let amount = Money::from_minor(i64::MAX);
+        assert_eq!(amount.proportion(10_000, 10_000).minor(), i64::MAX);
+    }
+
+    #[test]
+    fn checked_operations_fail_on_overflow() {
+        let max = Money::from_minor(i64::MAX);
+        let min = Money::from_minor(i64::MIN);
+        assert_eq!(max.checked_add(Money::from_minor(1)).unwrap_err().code, 400);
+        assert_eq!(min.checked_sub(Money::from_minor(1)).unwrap_err().code, 400);
+        assert_eq!(max.checked_mul(2).unwrap_err().code, 400);
+        assert_eq!(min.checked_neg().unwrap_err().code, 400);
+        assert!(Money::checked_sum([max, Money::from_minor(1)]).is_err());
+    }
+
+    #[test]
+    fn checked_operations_handle_negative_amounts() {
+        let amount = Money::from_minor(-250);
+        assert_eq!(
+            amount.checked_add(Money::from_minor(100)).unwrap().minor(),
+            -150
+        );
+        assert_eq!(
+            amount.checked_sub(Money::from_minor(100)).unwrap().minor(),
+            -350
+        );
+        assert_eq!(amount.checked_mul(3).unwrap().minor(), -750);
+        assert_eq!(amount.checked_neg().unwrap().minor(), 250);
+        assert_eq!(
+            Money::checked_sum([amount, Money::from_minor(400)])
+                .unwrap()
+                .minor(),
+            150
+        );
+    }
+
+    #[test]
+    fn allocate_adds_up_to_the_amount() {
+        let shares = Money::from_minor(100).allocate(&[1, 1, 1]);
+        let minors: Vec<i64> = shares.iter().map(|share| share.minor()).collect();
+        assert_eq!(minors, vec![34, 33, 33]);
+
+        let shares = Money::from_minor(-1000).allocate(&[300, 700, 1]);
+        let minors: Vec<i64> = shares.iter().map(|share| share.minor()).collect();
+        assert_eq!(minors.iter().sum::<i64>(), -1000);
+        assert_eq!(minors, vec![-300, -699, -1]);
+    }
+
+    #[test]
+    fn allocate_without_weights_gives_nothing() {
+        let shares = Money::from_minor(100).allocate(&[0, 0]);
+        assert!(shares.iter().all(|share| share.is_zero()));
+    }
+}
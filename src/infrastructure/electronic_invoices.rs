@@ -0,0 +1,392 @@
+use chrono::{FixedOffset, Utc};
+use sha2::{Digest, Sha384};
+use std::{path::PathBuf, sync::Arc};
+
+use crate::{
+    application::services::{ElectronicInvoiceBuilder, ElectronicInvoiceSubmitter, Logger},
+    domain::{
+        entities::{ElectronicInvoice, ElectronicInvoiceStatus, PaymentMethod, Sale},
+        value_objects::{Money, ValueObject},
+    },
+    shared::SharedError,
+};
+
+/// Issuer data and DIAN credentials the invoices are generated with
+pub struct DianSettings {
+    /// NIT of the store without its check digit
+    pub nit: String,
+    pub check_digit: String,
+    pub name: String,
+    /// Technical key given by the DIAN with the numbering authorization
+    pub technical_key: String,
+    /// "1" for production, "2" for the test environment
+    pub environment: String,
+}
+
+/// Builds the UBL 2.1 invoice of the DIAN technical annex. The document is unsigned,
+/// signing it belongs to whatever submits it.
+pub struct UblInvoiceBuilder {
+    pub settings: DianSettings,
+}
+impl UblInvoiceBuilder {
+    /// SHA-384 of the invoice fields in the order set by the DIAN
+    fn cufe(&self, fields: &CufeFields) -> String {
+        format!("{:x}", Sha384::digest(self.cufe_input(fields).as_bytes()))
+    }
+    /// Invoice fields concatenated in the order set by the DIAN, with the IVA (01), consumption
+    /// tax (04) and ICA (03) codes before their amounts
+    fn cufe_input(&self, fields: &CufeFields) -> String {
+        format!(
+            "{}{}{}{}01{}04{}03{}{}{}{}{}{}",
+            fields.invoice_number,
+            fields.issue_date,
+            fields.issue_time,
+            fields.base_amount,
+            fields.iva_amount,
+            Money::zero(),
+            Money::zero(),
+            fields.total_amount,
+            self.settings.nit,
+            fields.customer_id,
+            self.settings.technical_key,
+            self.settings.environment
+        )
+    }
+}
+
+/// Fields of the invoice the CUFE is computed from
+struct CufeFields {
+    invoice_number: String,
+    issue_date: String,
+    issue_time: String,
+    base_amount: Money,
+    iva_amount: Money,
+    total_amount: Money,
+    customer_id: String,
+}
+
+impl ElectronicInvoiceBuilder for UblInvoiceBuilder {
+    fn build(&self, sale: &Sale) -> Result<ElectronicInvoice, SharedError> {
+        let Some(invoice_number) = sale.invoice_number() else {
+            return Err(SharedError::new(
+                format!("Sale with ID {} has no invoice number", sale.id()).as_str(),
+                409,
+            ));
+        };
+        // Colombian time, the DIAN rejects invoices dated in another offset
+        let colombia = FixedOffset::west_opt(5 * 3600).unwrap();
+        let generated_at = sale.generated_at().with_timezone(&colombia);
+        let currency = sale.total_amount()?.currency().code();
+        // Totals are added up from the lines so base plus IVA always matches the total
+        let line_taxes = sale.line_taxes()?;
+        let base_amount = Money::checked_sum(line_taxes.iter().map(|t| t.base()))?;
+        let iva_amount = Money::checked_sum(line_taxes.iter().map(|t| t.tax()))?;
+        let fields = CufeFields {
+            invoice_number: invoice_number.to_string(),
+            issue_date: generated_at.format("%Y-%m-%d").to_string(),
+            issue_time: generated_at.format("%H:%M:%S%:z").to_string(),
+            base_amount,
+            iva_amount,
+            total_amount: base_amount.checked_add(iva_amount)?,
+            customer_id: sale.customer().cc().value().clone(),
+        };
+        let cufe = self.cufe(&fields);
+
+        let mut xml =
+            String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n");
+        xml.push_str("<Invoice xmlns=\"urn:oasis:names:specification:ubl:schema:xsd:Invoice-2\" xmlns:cac=\"urn:oasis:names:specification:ubl:schema:xsd:CommonAggregateComponents-2\" xmlns:cbc=\"urn:oasis:names:specification:ubl:schema:xsd:CommonBasicComponents-2\">\n");
+        xml.push_str("  <cbc:UBLVersionID>UBL 2.1</cbc:UBLVersionID>\n");
+        xml.push_str("  <cbc:CustomizationID>10</cbc:CustomizationID>\n");
+        xml.push_str("  <cbc:ProfileID>DIAN 2.1: Factura Electrónica de Venta</cbc:ProfileID>\n");
+        xml.push_str(&format!(
+            "  <cbc:ProfileExecutionID>{}</cbc:ProfileExecutionID>\n",
+            escape_xml(&self.settings.environment)
+        ));
+        xml.push_str(&format!("  <cbc:ID>{}</cbc:ID>\n", fields.invoice_number));
+        xml.push_str(&format!(
+            "  <cbc:UUID schemeID=\"{}\" schemeName=\"CUFE-SHA384\">{}</cbc:UUID>\n",
+            escape_xml(&self.settings.environment),
+            cufe
+        ));
+        xml.push_str(&format!(
+            "  <cbc:IssueDate>{}</cbc:IssueDate>\n",
+            fields.issue_date
+        ));
+        xml.push_str(&format!(
+            "  <cbc:IssueTime>{}</cbc:IssueTime>\n",
+            fields.issue_time
+        ));
+        xml.push_str("  <cbc:InvoiceTypeCode>01</cbc:InvoiceTypeCode>\n");
+        xml.push_str(&format!(
+            "  <cbc:DocumentCurrencyCode>{}</cbc:DocumentCurrencyCode>\n",
+            currency
+        ));
+        xml.push_str(&format!(
+            "  <cbc:LineCountNumeric>{}</cbc:LineCountNumeric>\n",
+            sale.lines().len()
+        ));
+
+        // Issuer, a company responsible for IVA
+        xml.push_str("  <cac:AccountingSupplierParty>\n");
+        xml.push_str("    <cbc:AdditionalAccountID>1</cbc:AdditionalAccountID>\n");
+        xml.push_str("    <cac:Party>\n");
+        xml.push_str(&format!(
+            "      <cac:PartyName><cbc:Name>{}</cbc:Name></cac:PartyName>\n",
+            escape_xml(&self.settings.name)
+        ));
+        xml.push_str("      <cac:PartyTaxScheme>\n");
+        xml.push_str(&format!(
+            "        <cbc:RegistrationName>{}</cbc:RegistrationName>\n",
+            escape_xml(&self.settings.name)
+        ));
+        xml.push_str(&format!(
+            "        <cbc:CompanyID schemeAgencyID=\"195\" schemeID=\"{}\" schemeName=\"31\">{}</cbc:CompanyID>\n",
+            escape_xml(&self.settings.check_digit),
+            escape_xml(&self.settings.nit)
+        ));
+        xml.push_str(
+            "        <cac:TaxScheme><cbc:ID>01</cbc:ID><cbc:Name>IVA</cbc:Name></cac:TaxScheme>\n",
+        );
+        xml.push_str("      </cac:PartyTaxScheme>\n");
+        xml.push_str("    </cac:Party>\n");
+        xml.push_str("  </cac:AccountingSupplierParty>\n");
+
+        // Customer, a person identified by their CC
+        let customer = sale.customer();
+        xml.push_str("  <cac:AccountingCustomerParty>\n");
+        xml.push_str("    <cbc:AdditionalAccountID>2</cbc:AdditionalAccountID>\n");
+        xml.push_str("    <cac:Party>\n");
+        xml.push_str("      <cac:PartyTaxScheme>\n");
+        xml.push_str(&format!(
+            "        <cbc:RegistrationName>{}</cbc:RegistrationName>\n",
+            escape_xml(customer.name())
+        ));
+        xml.push_str(&format!(
+            "        <cbc:CompanyID schemeAgencyID=\"195\" schemeName=\"13\">{}</cbc:CompanyID>\n",
+            escape_xml(&fields.customer_id)
+        ));
+        xml.push_str("        <cbc:TaxLevelCode>R-99-PN</cbc:TaxLevelCode>\n");
+        xml.push_str("        <cac:TaxScheme><cbc:ID>ZZ</cbc:ID><cbc:Name>No aplica</cbc:Name></cac:TaxScheme>\n");
+        xml.push_str("      </cac:PartyTaxScheme>\n");
+        xml.push_str(&format!(
+            "      <cac:Contact><cbc:ElectronicMail>{}</cbc:ElectronicMail></cac:Contact>\n",
+            escape_xml(customer.email().value())
+        ));
+        xml.push_str("    </cac:Party>\n");
+        xml.push_str("  </cac:AccountingCustomerParty>\n");
+
+        // Paid in full at the sale, one means per payment method used
+        let mut means_codes: Vec<&str> = Vec::new();
+        for payment in sale.payments() {
+            let code = payment_means_code(payment.method());
+            if !means_codes.contains(&code) {
+                means_codes.push(code);
+            }
+        }
+        for code in means_codes {
+            xml.push_str(&format!(
+                "  <cac:PaymentMeans><cbc:ID>1</cbc:ID><cbc:PaymentMeansCode>{}</cbc:PaymentMeansCode></cac:PaymentMeans>\n",
+                code
+            ));
+        }
+
+        xml.push_str("  <cac:TaxTotal>\n");
+        xml.push_str(&format!(
+            "    <cbc:TaxAmount currencyID=\"{}\">{}</cbc:TaxAmount>\n",
+            currency, fields.iva_amount
+        ));
+        for tax in sale.tax_breakdown()? {
+            xml.push_str(&tax_subtotal(
+                currency,
+                tax.base(),
+                tax.tax(),
+                tax.tax_rate(),
+                "    ",
+            ));
+        }
+        xml.push_str("  </cac:TaxTotal>\n");
+
+        xml.push_str("  <cac:LegalMonetaryTotal>\n");
+        xml.push_str(&format!(
+            "    <cbc:LineExtensionAmount currencyID=\"{}\">{}</cbc:LineExtensionAmount>\n",
+            currency, fields.base_amount
+        ));
+        xml.push_str(&format!(
+            "    <cbc:TaxExclusiveAmount currencyID=\"{}\">{}</cbc:TaxExclusiveAmount>\n",
+            currency, fields.base_amount
+        ));
+        xml.push_str(&format!(
+            "    <cbc:TaxInclusiveAmount currencyID=\"{}\">{}</cbc:TaxInclusiveAmount>\n",
+            currency, fields.total_amount
+        ));
+        xml.push_str(&format!(
+            "    <cbc:PayableAmount currencyID=\"{}\">{}</cbc:PayableAmount>\n",
+            currency, fields.total_amount
+        ));
+        xml.push_str("  </cac:LegalMonetaryTotal>\n");
+
+        // Line amounts are what is paid for them, discounts already taken off
+        for (index, (line, line_tax)) in sale.lines().iter().zip(line_taxes).enumerate() {
+            xml.push_str("  <cac:InvoiceLine>\n");
+            xml.push_str(&format!("    <cbc:ID>{}</cbc:ID>\n", index + 1));
+            xml.push_str(&format!(
+                "    <cbc:InvoicedQuantity unitCode=\"94\">{}</cbc:InvoicedQuantity>\n",
+                line.quantity()
+            ));
+            xml.push_str(&format!(
+                "    <cbc:LineExtensionAmount currencyID=\"{}\">{}</cbc:LineExtensionAmount>\n",
+                currency,
+                line_tax.base()
+            ));
+            xml.push_str("    <cac:TaxTotal>\n");
+            xml.push_str(&format!(
+                "      <cbc:TaxAmount currencyID=\"{}\">{}</cbc:TaxAmount>\n",
+                currency,
+                line_tax.tax()
+            ));
+            xml.push_str(&tax_subtotal(
+                currency,
+                line_tax.base(),
+                line_tax.tax(),
+                line.tax_rate(),
+                "      ",
+            ));
+            xml.push_str("    </cac:TaxTotal>\n");
+            xml.push_str("    <cac:Item>\n");
+            xml.push_str(&format!(
+                "      <cbc:Description>{}</cbc:Description>\n",
+                escape_xml(line.product_name())
+            ));
+            xml.push_str(&format!(
+                "      <cac:SellersItemIdentification><cbc:ID>{}</cbc:ID></cac:SellersItemIdentification>\n",
+                escape_xml(line.product().sku())
+            ));
+            xml.push_str("    </cac:Item>\n");
+            // Price of the whole quantity, so it matches the line amount without rounding
+            xml.push_str(&format!(
+                "    <cac:Price><cbc:PriceAmount currencyID=\"{}\">{}</cbc:PriceAmount><cbc:BaseQuantity unitCode=\"94\">{}</cbc:BaseQuantity></cac:Price>\n",
+                currency,
+                line_tax.base(),
+                line.quantity()
+            ));
+            xml.push_str("  </cac:InvoiceLine>\n");
+        }
+        xml.push_str("</Invoice>\n");
+
+        Ok(ElectronicInvoice::new(
+            sale.id(),
+            fields.invoice_number,
+            cufe,
+            xml,
+            ElectronicInvoiceStatus::Generated,
+            Utc::now(),
+            None,
+        ))
+    }
+}
+
+fn tax_subtotal(currency: &str, base: Money, tax: Money, rate: f64, indent: &str) -> String {
+    format!(
+        "{indent}<cac:TaxSubtotal>\n{indent}  <cbc:TaxableAmount currencyID=\"{currency}\">{base}</cbc:TaxableAmount>\n{indent}  <cbc:TaxAmount currencyID=\"{currency}\">{tax}</cbc:TaxAmount>\n{indent}  <cac:TaxCategory><cbc:Percent>{rate:.2}</cbc:Percent><cac:TaxScheme><cbc:ID>01</cbc:ID><cbc:Name>IVA</cbc:Name></cac:TaxScheme></cac:TaxCategory>\n{indent}</cac:TaxSubtotal>\n"
+    )
+}
+
+/// Code of the payment method in the DIAN list of payment means
+fn payment_means_code(method: PaymentMethod) -> &'static str {
+    match method {
+        PaymentMethod::Cash => "10",
+        PaymentMethod::Card => "48",
+        PaymentMethod::BankTransfer | PaymentMethod::Nequi | PaymentMethod::Daviplata => "47",
+        PaymentMethod::StoreCredit => "ZZZ",
+    }
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Stand-in for the DIAN web service, writes each document to a local directory
+pub struct FileInvoiceSubmitter {
+    pub directory: PathBuf,
+    pub logger: Arc<dyn Logger + Send + Sync>,
+}
+#[async_trait::async_trait]
+impl ElectronicInvoiceSubmitter for FileInvoiceSubmitter {
+    async fn submit(&self, invoice: &ElectronicInvoice) -> Result<(), SharedError> {
+        let path = self
+            .directory
+            .join(format!("{}.xml", invoice.invoice_number()));
+        let result = match tokio::fs::create_dir_all(&self.directory).await {
+            Ok(_) => tokio::fs::write(&path, invoice.xml()).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(_) => {
+                self.logger.info(&format!(
+                    "Electronic invoice {} written to {}",
+                    invoice.invoice_number(),
+                    path.display()
+                ));
+                Ok(())
+            }
+            Err(e) => {
+                self.logger.error(&format!(
+                    "Failed to write electronic invoice {}: {}",
+                    invoice.invoice_number(),
+                    e
+                ));
+                Err(SharedError::new("Failed to submit electronic invoice", 502))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Invoice of the CUFE example in the DIAN technical annex
+    fn annex_example() -> (UblInvoiceBuilder, CufeFields) {
+        let builder = UblInvoiceBuilder {
+            settings: DianSettings {
+                nit: "700085371".to_string(),
+                check_digit: "0".to_string(),
+                name: "Example".to_string(),
+                technical_key: "693ff6f2a553c3646a063436fd4dd9ded0311471".to_string(),
+                environment: "1".to_string(),
+            },
+        };
+        let fields = CufeFields {
+            invoice_number: "323200000129".to_string(),
+            issue_date: "2019-01-16".to_string(),
+            issue_time: "10:53:10-05:00".to_string(),
+            base_amount: Money::from_minor(150_000_000),
+            iva_amount: Money::from_minor(28_500_000),
+            total_amount: Money::from_minor(178_500_000),
+            customer_id: "800199436".to_string(),
+        };
+        (builder, fields)
+    }
+
+    #[test]
+    fn cufe_input_follows_the_dian_order() {
+        let (builder, fields) = annex_example();
+        assert_eq!(
+            builder.cufe_input(&fields),
+            "3232000001292019-01-1610:53:10-05:001500000.0001285000.00040.00030.001785000.00700085371800199436693ff6f2a553c3646a063436fd4dd9ded03114711"
+        );
+    }
+
+    #[test]
+    fn cufe_matches_the_dian_example() {
+        let (builder, fields) = annex_example();
+        assert_eq!(
+            builder.cufe(&fields),
+            "8bb918b19ba22a694f1da11c643b5e9de39adf60311cf179179e9b33381030bcd4c3c3f156c506ed5908f9276f5bd9b4"
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use chrono::Utc;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, MultiPart},
+};
+use std::{
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use crate::{
+    application::services::{EmailMessage, Logger, Mailer},
+    shared::SharedError,
+};
+
+/// Build the MIME message, failing with 400 on addresses that cannot be parsed
+fn build_message(from: &Mailbox, message: &EmailMessage) -> Result<Message, SharedError> {
+    let to: Mailbox = message
+        .to
+        .parse()
+        .map_err(|_| SharedError::new(&format!("Invalid email recipient {}", message.to), 400))?;
+    let builder = Message::builder()
+        .from(from.clone())
+        .to(to)
+        .subject(message.subject.clone());
+    let result = match &message.html_body {
+        Some(html_body) => builder.multipart(MultiPart::alternative_plain_html(
+            message.text_body.clone(),
+            html_body.clone(),
+        )),
+        None => builder.body(message.text_body.clone()),
+    };
+    result.map_err(|e| SharedError::new(&format!("Invalid email: {}", e), 400))
+}
+
+pub struct SmtpMailer {
+    pub transport: AsyncSmtpTransport<Tokio1Executor>,
+    pub from: Mailbox,
+    pub logger: Arc<dyn Logger + Send + Sync>,
+}
+impl SmtpMailer {
+    /// Mailer over STARTTLS, authenticated when a user is given
+    pub fn new(
+        host: &str,
+        port: u16,
+        user: Option<String>,
+        pass: Option<String>,
+        from: Mailbox,
+        logger: Arc<dyn Logger + Send + Sync>,
+    ) -> Result<Self, lettre::transport::smtp::Error> {
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?.port(port);
+        if let Some(user) = user {
+            builder = builder.credentials((user, pass.unwrap_or_default()).into());
+        }
+        Ok(SmtpMailer {
+            transport: builder.build(),
+            from,
+            logger,
+        })
+    }
+}
+#[async_trait::async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, message: &EmailMessage) -> Result<(), SharedError> {
+        let email = build_message(&self.from, message)?;
+        match self.transport.send(email).await {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                self.logger
+                    .error(&format!("Failed to send email to {}: {}", message.to, e));
+                Err(SharedError::new("Failed to send email", 502))
+            }
+        }
+    }
+}
+
+/// Development mailer delivering every email to a local maildir instead of sending it
+pub struct MaildirMailer {
+    pub directory: PathBuf,
+    pub from: Mailbox,
+    pub logger: Arc<dyn Logger + Send + Sync>,
+    /// Tells apart emails delivered within the same millisecond
+    pub delivered: AtomicU64,
+}
+#[async_trait::async_trait]
+impl Mailer for MaildirMailer {
+    async fn send(&self, message: &EmailMessage) -> Result<(), SharedError> {
+        let email = build_message(&self.from, message)?;
+        let name = format!(
+            "{}.{}_{}.eml",
+            Utc::now().timestamp_millis(),
+            std::process::id(),
+            self.delivered.fetch_add(1, Ordering::Relaxed)
+        );
+        // Written to tmp and moved to new, so readers never see a half written email
+        let tmp_path = self.directory.join("tmp").join(&name);
+        let new_path = self.directory.join("new").join(&name);
+        let result = async {
+            for folder in ["tmp", "new", "cur"] {
+                tokio::fs::create_dir_all(self.directory.join(folder)).await?;
+            }
+            tokio::fs::write(&tmp_path, email.formatted()).await?;
+            tokio::fs::rename(&tmp_path, &new_path).await
+        }
+        .await;
+        match result {
+            Ok(_) => {
+                self.logger.info(&format!(
+                    "Email to {} delivered to {}",
+                    message.to,
+                    new_path.display()
+                ));
+                Ok(())
+            }
+            Err(e) => {
+                self.logger
+                    .error(&format!("Failed to deliver email to {}: {}", message.to, e));
+                Err(SharedError::new("Failed to send email", 500))
+            }
+        }
+    }
+}
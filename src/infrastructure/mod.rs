@@ -1,4 +1,7 @@
+mod electronic_invoices;
+mod mailers;
 mod mysql_repositories;
+mod receipts;
 mod services;
 
 use actix_web::web::{Data, ServiceConfig};
@@ -10,9 +13,19 @@ use std::{
 use crate::{
     application::{
         commands, queries,
-        services::{CredentialsValidator, LogLevel, Logger},
+        services::{
+            CredentialsValidator, ElectronicInvoiceBuilder, ElectronicInvoiceSubmitter, LogLevel,
+            Logger, Mailer, ReceiptRenderer,
+        },
+    },
+    domain::{
+        repositories::{
+            CategoryRepository, CustomerRepository, DraftSaleRepository, ElectronicInvoiceRepository,
+            IdempotencyRepository, InvoiceSeriesRepository, ProductRepository, PromotionRepository, SaleRepository,
+            StockMovementRepository,
+        },
+        unit_of_work::UnitOfWork,
     },
-    domain::repositories::{CustomerRepository, ProductRepository, SaleRepository},
     shared::{Resolver, input_handler::InputHandler, input_handler::Sender},
 };
 
@@ -74,6 +87,18 @@ impl Resolver<dyn ProductRepository + Send + Sync> for DependenciesResolver {
     }
 }
 
+static CATEGORY_REPOSITORY: LazyLock<Arc<dyn CategoryRepository + Send + Sync>> =
+    LazyLock::new(|| {
+        Arc::new(mysql_repositories::MySqlCategoryRepository {
+            pool: RESOLVER.resolve(),
+            logger: RESOLVER.resolve(),
+        })
+    });
+impl Resolver<dyn CategoryRepository + Send + Sync> for DependenciesResolver {
+    fn resolve(&self) -> Arc<dyn CategoryRepository + Send + Sync> {
+        CATEGORY_REPOSITORY.clone()
+    }
+}
 static SALE_REPOSITORY: LazyLock<Arc<dyn SaleRepository + Send + Sync>> = LazyLock::new(|| {
     Arc::new(mysql_repositories::MySqlSaleRepository {
         pool: RESOLVER.resolve(),
@@ -87,6 +112,96 @@ impl Resolver<dyn SaleRepository + Send + Sync> for DependenciesResolver {
         SALE_REPOSITORY.clone()
     }
 }
+static PROMOTION_REPOSITORY: LazyLock<Arc<dyn PromotionRepository + Send + Sync>> =
+    LazyLock::new(|| {
+        Arc::new(mysql_repositories::MySqlPromotionRepository {
+            pool: RESOLVER.resolve(),
+            logger: RESOLVER.resolve(),
+        })
+    });
+impl Resolver<dyn PromotionRepository + Send + Sync> for DependenciesResolver {
+    fn resolve(&self) -> Arc<dyn PromotionRepository + Send + Sync> {
+        PROMOTION_REPOSITORY.clone()
+    }
+}
+static DRAFT_SALE_REPOSITORY: LazyLock<Arc<dyn DraftSaleRepository + Send + Sync>> =
+    LazyLock::new(|| {
+        Arc::new(mysql_repositories::MySqlDraftSaleRepository {
+            pool: RESOLVER.resolve(),
+            logger: RESOLVER.resolve(),
+            customer_repository: RESOLVER.resolve(),
+        })
+    });
+impl Resolver<dyn DraftSaleRepository + Send + Sync> for DependenciesResolver {
+    fn resolve(&self) -> Arc<dyn DraftSaleRepository + Send + Sync> {
+        DRAFT_SALE_REPOSITORY.clone()
+    }
+}
+static INVOICE_SERIES_REPOSITORY: LazyLock<Arc<dyn InvoiceSeriesRepository + Send + Sync>> =
+    LazyLock::new(|| {
+        Arc::new(mysql_repositories::MySqlInvoiceSeriesRepository {
+            pool: RESOLVER.resolve(),
+            logger: RESOLVER.resolve(),
+        })
+    });
+impl Resolver<dyn InvoiceSeriesRepository + Send + Sync> for DependenciesResolver {
+    fn resolve(&self) -> Arc<dyn InvoiceSeriesRepository + Send + Sync> {
+        INVOICE_SERIES_REPOSITORY.clone()
+    }
+}
+static ELECTRONIC_INVOICE_REPOSITORY: LazyLock<
+    Arc<dyn ElectronicInvoiceRepository + Send + Sync>,
+> = LazyLock::new(|| {
+    Arc::new(mysql_repositories::MySqlElectronicInvoiceRepository {
+        pool: RESOLVER.resolve(),
+        logger: RESOLVER.resolve(),
+    })
+});
+impl Resolver<dyn ElectronicInvoiceRepository + Send + Sync> for DependenciesResolver {
+    fn resolve(&self) -> Arc<dyn ElectronicInvoiceRepository + Send + Sync> {
+        ELECTRONIC_INVOICE_REPOSITORY.clone()
+    }
+}
+static STOCK_MOVEMENT_REPOSITORY: LazyLock<Arc<dyn StockMovementRepository + Send + Sync>> =
+    LazyLock::new(|| {
+        Arc::new(mysql_repositories::MySqlStockMovementRepository {
+            pool: RESOLVER.resolve(),
+            logger: RESOLVER.resolve(),
+        })
+    });
+impl Resolver<dyn StockMovementRepository + Send + Sync> for DependenciesResolver {
+    fn resolve(&self) -> Arc<dyn StockMovementRepository + Send + Sync> {
+        STOCK_MOVEMENT_REPOSITORY.clone()
+    }
+}
+static IDEMPOTENCY_REPOSITORY: LazyLock<Arc<dyn IdempotencyRepository + Send + Sync>> =
+    LazyLock::new(|| {
+        let ttl_hours = env::var("IDEMPOTENCY_KEY_TTL_HOURS")
+            .ok()
+            .and_then(|hours| hours.parse::<i64>().ok())
+            .unwrap_or(24);
+        Arc::new(mysql_repositories::MySqlIdempotencyRepository {
+            pool: RESOLVER.resolve(),
+            logger: RESOLVER.resolve(),
+            ttl: chrono::Duration::hours(ttl_hours),
+        })
+    });
+impl Resolver<dyn IdempotencyRepository + Send + Sync> for DependenciesResolver {
+    fn resolve(&self) -> Arc<dyn IdempotencyRepository + Send + Sync> {
+        IDEMPOTENCY_REPOSITORY.clone()
+    }
+}
+static UNIT_OF_WORK: LazyLock<Arc<dyn UnitOfWork + Send + Sync>> = LazyLock::new(|| {
+    Arc::new(mysql_repositories::MySqlUnitOfWork {
+        pool: RESOLVER.resolve(),
+        logger: RESOLVER.resolve(),
+    })
+});
+impl Resolver<dyn UnitOfWork + Send + Sync> for DependenciesResolver {
+    fn resolve(&self) -> Arc<dyn UnitOfWork + Send + Sync> {
+        UNIT_OF_WORK.clone()
+    }
+}
 static CREDENTIALS_VALIDATOR: LazyLock<Arc<dyn CredentialsValidator + Send + Sync>> =
     LazyLock::new(|| {
         let config = services::CredentialsValidatorOneUserConfig {
@@ -103,6 +218,126 @@ impl Resolver<dyn CredentialsValidator + Send + Sync> for DependenciesResolver {
     }
 }
 
+static RECEIPT_RENDERER: LazyLock<Arc<dyn ReceiptRenderer + Send + Sync>> =
+    LazyLock::new(|| {
+        let nit = env::var("STORE_NIT").unwrap_or_default();
+        let settings = receipts::StoreSettings {
+            name: env::var("STORE_NAME").unwrap_or("Store".to_string()),
+            // Printed with its check digit, as the NIT is written on paper
+            nit: match env::var("STORE_NIT_CHECK_DIGIT") {
+                Ok(check_digit) if !nit.is_empty() && !check_digit.is_empty() => {
+                    format!("{}-{}", nit, check_digit)
+                }
+                _ => nit,
+            },
+            address: env::var("STORE_ADDRESS").unwrap_or_default(),
+            phone: env::var("STORE_PHONE").unwrap_or_default(),
+        };
+        let width = env::var("RECEIPT_WIDTH")
+            .ok()
+            .and_then(|width| width.parse::<usize>().ok())
+            .filter(|width| *width >= 24)
+            .unwrap_or(42);
+        Arc::new(receipts::ReceiptPrinter { settings, width })
+    });
+
+impl Resolver<dyn ReceiptRenderer + Send + Sync> for DependenciesResolver {
+    fn resolve(&self) -> Arc<dyn ReceiptRenderer + Send + Sync> {
+        RECEIPT_RENDERER.clone()
+    }
+}
+
+static ELECTRONIC_INVOICE_BUILDER: LazyLock<Arc<dyn ElectronicInvoiceBuilder + Send + Sync>> =
+    LazyLock::new(|| {
+        let settings = electronic_invoices::DianSettings {
+            nit: env::var("STORE_NIT").unwrap_or_default(),
+            check_digit: env::var("STORE_NIT_CHECK_DIGIT").unwrap_or_default(),
+            name: env::var("STORE_NAME").unwrap_or("Store".to_string()),
+            technical_key: env::var("DIAN_TECHNICAL_KEY").unwrap_or_default(),
+            environment: env::var("DIAN_ENVIRONMENT").unwrap_or("2".to_string()),
+        };
+        Arc::new(electronic_invoices::UblInvoiceBuilder { settings })
+    });
+
+impl Resolver<dyn ElectronicInvoiceBuilder + Send + Sync> for DependenciesResolver {
+    fn resolve(&self) -> Arc<dyn ElectronicInvoiceBuilder + Send + Sync> {
+        ELECTRONIC_INVOICE_BUILDER.clone()
+    }
+}
+
+static ELECTRONIC_INVOICE_SUBMITTER: LazyLock<
+    Arc<dyn ElectronicInvoiceSubmitter + Send + Sync>,
+> = LazyLock::new(|| {
+    Arc::new(electronic_invoices::FileInvoiceSubmitter {
+        directory: env::var("EINVOICE_OUTBOX_DIR")
+            .unwrap_or("einvoices".to_string())
+            .into(),
+        logger: RESOLVER.resolve(),
+    })
+});
+
+impl Resolver<dyn ElectronicInvoiceSubmitter + Send + Sync> for DependenciesResolver {
+    fn resolve(&self) -> Arc<dyn ElectronicInvoiceSubmitter + Send + Sync> {
+        ELECTRONIC_INVOICE_SUBMITTER.clone()
+    }
+}
+
+static MAILER: LazyLock<Arc<dyn Mailer + Send + Sync>> = LazyLock::new(|| {
+    let logger: Arc<dyn Logger + Send + Sync> = RESOLVER.resolve();
+    let from: lettre::message::Mailbox = env::var("MAIL_FROM")
+        .ok()
+        .and_then(|from| from.parse().ok())
+        .unwrap_or_else(|| "Store <no-reply@localhost>".parse().unwrap());
+    if env::var("MAIL_TRANSPORT").as_deref() == Ok("smtp") {
+        let host = env::var("SMTP_HOST").unwrap_or("localhost".to_string());
+        let port = env::var("SMTP_PORT")
+            .ok()
+            .and_then(|port| port.parse::<u16>().ok())
+            .unwrap_or(587);
+        match mailers::SmtpMailer::new(
+            &host,
+            port,
+            env::var("SMTP_USER").ok(),
+            env::var("SMTP_PASS").ok(),
+            from.clone(),
+            logger.clone(),
+        ) {
+            Ok(mailer) => return Arc::new(mailer),
+            Err(e) => logger.error(&format!(
+                "Invalid SMTP configuration, emails go to the maildir: {}",
+                e
+            )),
+        }
+    }
+    Arc::new(mailers::MaildirMailer {
+        directory: env::var("MAIL_DIR").unwrap_or("maildir".to_string()).into(),
+        from,
+        logger,
+        delivered: Default::default(),
+    })
+});
+
+impl Resolver<dyn Mailer + Send + Sync> for DependenciesResolver {
+    fn resolve(&self) -> Arc<dyn Mailer + Send + Sync> {
+        MAILER.clone()
+    }
+}
+
+static RECEIPT_EMAILER: LazyLock<Arc<commands::ReceiptEmailer>> = LazyLock::new(|| {
+    Arc::new(commands::ReceiptEmailer {
+        sale_repository: RESOLVER.resolve(),
+        receipt_renderer: RESOLVER.resolve(),
+        mailer: RESOLVER.resolve(),
+        logger: RESOLVER.resolve(),
+    })
+});
+
+impl Resolver<commands::ReceiptEmailer> for DependenciesResolver {
+    fn resolve(&self) -> Arc<commands::ReceiptEmailer> {
+        RECEIPT_EMAILER.clone()
+    }
+}
+
 pub struct Mediator;
 
 impl Sender<commands::RegisterCustomerCommand> for Mediator {
@@ -123,6 +358,159 @@ impl Sender<commands::RegisterSaleCommand> for Mediator {
             customer_repository: RESOLVER.resolve(),
             product_repository: RESOLVER.resolve(),
             sale_repository: RESOLVER.resolve(),
+            promotion_repository: RESOLVER.resolve(),
+            invoice_series_repository: RESOLVER.resolve(),
+            idempotency_repository: RESOLVER.resolve(),
+            receipt_emailer: RESOLVER.resolve(),
+            logger: RESOLVER.resolve(),
+            unit_of_work: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<commands::CancelSaleCommand> for Mediator {
+    fn get_input_handler(&self) -> Arc<dyn InputHandler<commands::CancelSaleCommand> + Send + Sync> {
+        Arc::new(commands::CancelSaleCommandHandler {
+            product_repository: RESOLVER.resolve(),
+            sale_repository: RESOLVER.resolve(),
+            unit_of_work: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<commands::RegisterReturnCommand> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<commands::RegisterReturnCommand> + Send + Sync> {
+        Arc::new(commands::RegisterReturnCommandHandler {
+            product_repository: RESOLVER.resolve(),
+            sale_repository: RESOLVER.resolve(),
+            unit_of_work: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<commands::ExchangeCommand> for Mediator {
+    fn get_input_handler(&self) -> Arc<dyn InputHandler<commands::ExchangeCommand> + Send + Sync> {
+        Arc::new(commands::ExchangeCommandHandler {
+            product_repository: RESOLVER.resolve(),
+            sale_repository: RESOLVER.resolve(),
+            promotion_repository: RESOLVER.resolve(),
+            invoice_series_repository: RESOLVER.resolve(),
+            logger: RESOLVER.resolve(),
+            unit_of_work: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<commands::RegisterPromotionCommand> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<commands::RegisterPromotionCommand> + Send + Sync> {
+        Arc::new(commands::RegisterPromotionCommandHandler {
+            promotion_repository: RESOLVER.resolve(),
+            category_repository: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<commands::SetPromotionActiveCommand> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<commands::SetPromotionActiveCommand> + Send + Sync> {
+        Arc::new(commands::SetPromotionActiveCommandHandler {
+            promotion_repository: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<commands::OpenDraftSaleCommand> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<commands::OpenDraftSaleCommand> + Send + Sync> {
+        Arc::new(commands::OpenDraftSaleCommandHandler {
+            customer_repository: RESOLVER.resolve(),
+            draft_sale_repository: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<commands::AddDraftItemCommand> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<commands::AddDraftItemCommand> + Send + Sync> {
+        Arc::new(commands::AddDraftItemCommandHandler {
+            product_repository: RESOLVER.resolve(),
+            draft_sale_repository: RESOLVER.resolve(),
+            unit_of_work: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<commands::RemoveDraftItemCommand> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<commands::RemoveDraftItemCommand> + Send + Sync> {
+        Arc::new(commands::RemoveDraftItemCommandHandler {
+            draft_sale_repository: RESOLVER.resolve(),
+            unit_of_work: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<commands::SetDraftSaleParkedCommand> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<commands::SetDraftSaleParkedCommand> + Send + Sync> {
+        Arc::new(commands::SetDraftSaleParkedCommandHandler {
+            draft_sale_repository: RESOLVER.resolve(),
+            unit_of_work: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<commands::CheckoutDraftSaleCommand> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<commands::CheckoutDraftSaleCommand> + Send + Sync> {
+        Arc::new(commands::CheckoutDraftSaleCommandHandler {
+            product_repository: RESOLVER.resolve(),
+            sale_repository: RESOLVER.resolve(),
+            promotion_repository: RESOLVER.resolve(),
+            invoice_series_repository: RESOLVER.resolve(),
+            draft_sale_repository: RESOLVER.resolve(),
+            receipt_emailer: RESOLVER.resolve(),
+            logger: RESOLVER.resolve(),
+            unit_of_work: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<commands::RegisterInvoiceSeriesCommand> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<commands::RegisterInvoiceSeriesCommand> + Send + Sync> {
+        Arc::new(commands::RegisterInvoiceSeriesCommandHandler {
+            invoice_series_repository: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<commands::ActivateInvoiceSeriesCommand> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<commands::ActivateInvoiceSeriesCommand> + Send + Sync> {
+        Arc::new(commands::ActivateInvoiceSeriesCommandHandler {
+            invoice_series_repository: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<commands::GenerateElectronicInvoiceCommand> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<commands::GenerateElectronicInvoiceCommand> + Send + Sync> {
+        Arc::new(commands::GenerateElectronicInvoiceCommandHandler {
+            sale_repository: RESOLVER.resolve(),
+            electronic_invoice_repository: RESOLVER.resolve(),
+            electronic_invoice_builder: RESOLVER.resolve(),
+            electronic_invoice_submitter: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<commands::SendSaleReceiptCommand> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<commands::SendSaleReceiptCommand> + Send + Sync> {
+        Arc::new(commands::SendSaleReceiptCommandHandler {
+            receipt_emailer: RESOLVER.resolve(),
         })
     }
 }
@@ -132,6 +520,8 @@ impl Sender<commands::RegisterProductCommand> for Mediator {
     ) -> Arc<dyn InputHandler<commands::RegisterProductCommand> + Send + Sync> {
         Arc::new(commands::RegisterProductCommandHandler {
             product_repository: RESOLVER.resolve(),
+            category_repository: RESOLVER.resolve(),
+            unit_of_work: RESOLVER.resolve(),
         })
     }
 }
@@ -146,12 +536,58 @@ impl Sender<commands::EditCustomerCommand> for Mediator {
     }
 }
 
+impl Sender<commands::AdjustStockCommand> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<commands::AdjustStockCommand> + Send + Sync> {
+        Arc::new(commands::AdjustStockCommandHandler {
+            product_repository: RESOLVER.resolve(),
+            logger: RESOLVER.resolve(),
+            unit_of_work: RESOLVER.resolve(),
+        })
+    }
+}
+
+impl Sender<commands::GenerateProductVariantsCommand> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<commands::GenerateProductVariantsCommand> + Send + Sync> {
+        Arc::new(commands::GenerateProductVariantsCommandHandler {
+            product_repository: RESOLVER.resolve(),
+            unit_of_work: RESOLVER.resolve(),
+        })
+    }
+}
+
 impl Sender<commands::EditProductCommand> for Mediator {
     fn get_input_handler(
         &self,
     ) -> Arc<dyn InputHandler<commands::EditProductCommand> + Send + Sync> {
         Arc::new(commands::EditProductCommandHandler {
             product_repository: RESOLVER.resolve(),
+            category_repository: RESOLVER.resolve(),
+        })
+    }
+}
+
+impl Sender<commands::SetProductArchivedCommand> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<commands::SetProductArchivedCommand> + Send + Sync> {
+        Arc::new(commands::SetProductArchivedCommandHandler {
+            product_repository: RESOLVER.resolve(),
+        })
+    }
+}
+
+impl Sender<commands::ImportProductsCommand> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<commands::ImportProductsCommand> + Send + Sync> {
+        Arc::new(commands::ImportProductsCommandHandler {
+            product_repository: RESOLVER.resolve(),
+            category_repository: RESOLVER.resolve(),
+            unit_of_work: RESOLVER.resolve(),
         })
     }
 }
@@ -166,6 +602,16 @@ impl Sender<queries::GenerateCsvReportQuery> for Mediator {
     }
 }
 
+impl Sender<queries::GetPaymentTotalsQuery> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<queries::GetPaymentTotalsQuery> + Send + Sync> {
+        Arc::new(queries::GetPaymentTotalsQueryHandler {
+            sale_repository: RESOLVER.resolve(),
+        })
+    }
+}
+
 impl Sender<queries::GetCustomersQuery> for Mediator {
     fn get_input_handler(&self) -> Arc<dyn InputHandler<queries::GetCustomersQuery> + Send + Sync> {
         Arc::new(queries::GetCustomersQueryHandler {
@@ -177,6 +623,47 @@ impl Sender<queries::GetProductsQuery> for Mediator {
     fn get_input_handler(&self) -> Arc<dyn InputHandler<queries::GetProductsQuery> + Send + Sync> {
         Arc::new(queries::GetProductsQueryHandler {
             product_repository: RESOLVER.resolve(),
+            category_repository: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<queries::AutocompleteProductsQuery> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<queries::AutocompleteProductsQuery> + Send + Sync> {
+        Arc::new(queries::AutocompleteProductsQueryHandler {
+            product_repository: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<queries::GetPromotionsQuery> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<queries::GetPromotionsQuery> + Send + Sync> {
+        Arc::new(queries::GetPromotionsQueryHandler {
+            promotion_repository: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<queries::GetDraftSaleByIdQuery> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<queries::GetDraftSaleByIdQuery> + Send + Sync> {
+        Arc::new(queries::GetDraftSaleByIdQueryHandler {
+            product_repository: RESOLVER.resolve(),
+            promotion_repository: RESOLVER.resolve(),
+            draft_sale_repository: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<queries::GetDraftSalesQuery> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<queries::GetDraftSalesQuery> + Send + Sync> {
+        Arc::new(queries::GetDraftSalesQueryHandler {
+            product_repository: RESOLVER.resolve(),
+            promotion_repository: RESOLVER.resolve(),
+            draft_sale_repository: RESOLVER.resolve(),
         })
     }
 }
@@ -187,6 +674,52 @@ impl Sender<queries::GetSalesQuery> for Mediator {
         })
     }
 }
+impl Sender<queries::PreviewSaleQuery> for Mediator {
+    fn get_input_handler(&self) -> Arc<dyn InputHandler<queries::PreviewSaleQuery> + Send + Sync> {
+        Arc::new(queries::PreviewSaleQueryHandler {
+            customer_repository: RESOLVER.resolve(),
+            product_repository: RESOLVER.resolve(),
+            promotion_repository: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<queries::GetInvoiceSeriesQuery> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<queries::GetInvoiceSeriesQuery> + Send + Sync> {
+        Arc::new(queries::GetInvoiceSeriesQueryHandler {
+            invoice_series_repository: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<queries::GetSaleByInvoiceNumberQuery> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<queries::GetSaleByInvoiceNumberQuery> + Send + Sync> {
+        Arc::new(queries::GetSaleByInvoiceNumberQueryHandler {
+            sale_repository: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<queries::GetSaleReceiptQuery> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<queries::GetSaleReceiptQuery> + Send + Sync> {
+        Arc::new(queries::GetSaleReceiptQueryHandler {
+            sale_repository: RESOLVER.resolve(),
+            receipt_renderer: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<queries::GetElectronicInvoiceQuery> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<queries::GetElectronicInvoiceQuery> + Send + Sync> {
+        Arc::new(queries::GetElectronicInvoiceQueryHandler {
+            electronic_invoice_repository: RESOLVER.resolve(),
+        })
+    }
+}
 impl Sender<queries::LoginQuery> for Mediator {
     fn get_input_handler(&self) -> Arc<dyn InputHandler<queries::LoginQuery> + Send + Sync> {
         Arc::new(queries::LoginQueryHandler {
@@ -212,6 +745,70 @@ impl Sender<queries::GetProductBySkuQuery> for Mediator {
         })
     }
 }
+impl Sender<queries::GetProductDetailQuery> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<queries::GetProductDetailQuery> + Send + Sync> {
+        Arc::new(queries::GetProductDetailQueryHandler {
+            product_repository: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<queries::GetLowStockProductsQuery> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<queries::GetLowStockProductsQuery> + Send + Sync> {
+        Arc::new(queries::GetLowStockProductsQueryHandler {
+            product_repository: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<queries::GetStockMovementsQuery> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<queries::GetStockMovementsQuery> + Send + Sync> {
+        Arc::new(queries::GetStockMovementsQueryHandler {
+            product_repository: RESOLVER.resolve(),
+            stock_movement_repository: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<commands::RegisterCategoryCommand> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<commands::RegisterCategoryCommand> + Send + Sync> {
+        Arc::new(commands::RegisterCategoryCommandHandler {
+            category_repository: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<commands::EditCategoryCommand> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<commands::EditCategoryCommand> + Send + Sync> {
+        Arc::new(commands::EditCategoryCommandHandler {
+            category_repository: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<commands::DeleteCategoryCommand> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<commands::DeleteCategoryCommand> + Send + Sync> {
+        Arc::new(commands::DeleteCategoryCommandHandler {
+            category_repository: RESOLVER.resolve(),
+        })
+    }
+}
+impl Sender<queries::GetCategoriesQuery> for Mediator {
+    fn get_input_handler(
+        &self,
+    ) -> Arc<dyn InputHandler<queries::GetCategoriesQuery> + Send + Sync> {
+        Arc::new(queries::GetCategoriesQueryHandler {
+            category_repository: RESOLVER.resolve(),
+        })
+    }
+}
 impl Sender<queries::GetSaleByIdQuery> for Mediator {
     fn get_input_handler(&self) -> Arc<dyn InputHandler<queries::GetSaleByIdQuery> + Send + Sync> {
         Arc::new(queries::GetSaleByIdQueryHandler {
@@ -0,0 +1,162 @@
+use sqlx::FromRow;
+use std::sync::Arc;
+
+use crate::{
+    application::services::Logger,
+    domain::{entities::Category, repositories::CategoryRepository},
+    shared::SharedError,
+};
+
+#[derive(FromRow, Debug, Clone)]
+struct CategoryModel {
+    pub id: u32,
+    pub name: String,
+    pub parent_id: Option<u32>,
+}
+impl From<CategoryModel> for Category {
+    fn from(model: CategoryModel) -> Self {
+        Category::new(model.id, model.name, model.parent_id)
+    }
+}
+
+pub struct MySqlCategoryRepository {
+    pub pool: Arc<sqlx::MySqlPool>,
+    pub logger: Arc<dyn Logger + Send + Sync>,
+}
+
+#[async_trait::async_trait]
+impl CategoryRepository for MySqlCategoryRepository {
+    async fn get_all(&self) -> Vec<Category> {
+        let result = sqlx::query_as::<_, CategoryModel>(
+            "SELECT id, name, parent_id FROM categories ORDER BY name",
+        )
+        .fetch_all(self.pool.as_ref())
+        .await;
+        match result {
+            Ok(models) => models.into_iter().map(|model| model.into()).collect(),
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error fetching categories: {}", e));
+                Vec::new()
+            }
+        }
+    }
+    async fn get_by_id(&self, id: u32) -> Option<Category> {
+        let result = sqlx::query_as::<_, CategoryModel>(
+            "SELECT id, name, parent_id FROM categories WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(self.pool.as_ref())
+        .await;
+        match result {
+            Ok(model) => model.map(|model| model.into()),
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error fetching category id {}: {}", id, e));
+                None
+            }
+        }
+    }
+    async fn get_many_by_ids(&self, ids: &[u32]) -> Vec<Category> {
+        if ids.is_empty() {
+            return Vec::new();
+        }
+        let sql = format!(
+            "SELECT id, name, parent_id FROM categories WHERE id IN ({})",
+            vec!["?"; ids.len()].join(", ")
+        );
+        let mut query = sqlx::query_as::<_, CategoryModel>(&sql);
+        for id in ids {
+            query = query.bind(id);
+        }
+        match query.fetch_all(self.pool.as_ref()).await {
+            Ok(models) => models.into_iter().map(|model| model.into()).collect(),
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error fetching categories by ids: {}", e));
+                Vec::new()
+            }
+        }
+    }
+    async fn create(&self, name: &str, parent_id: Option<u32>) -> Result<Category, SharedError> {
+        let result = sqlx::query("INSERT INTO categories (name, parent_id) VALUES (?, ?)")
+            .bind(name)
+            .bind(parent_id)
+            .execute(self.pool.as_ref())
+            .await;
+        match result {
+            Ok(res) => Ok(Category::new(
+                res.last_insert_id() as u32,
+                name.to_string(),
+                parent_id,
+            )),
+            Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => Err(
+                SharedError::new(format!("Category {} already exists", name).as_str(), 409),
+            ),
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error creating category {}: {}", name, e));
+                Err(SharedError::new("Failed to create category", 500))
+            }
+        }
+    }
+    async fn save(&self, category: &Category) -> Result<(), SharedError> {
+        let result = sqlx::query("UPDATE categories SET name = ?, parent_id = ? WHERE id = ?")
+            .bind(category.name())
+            .bind(category.parent_id())
+            .bind(category.id())
+            .execute(self.pool.as_ref())
+            .await;
+        match result {
+            Ok(_) => Ok(()),
+            Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+                Err(SharedError::new(
+                    format!("Category {} already exists", category.name()).as_str(),
+                    409,
+                ))
+            }
+            Err(e) => {
+                self.logger.error(&format!(
+                    "Error saving category id {}: {}",
+                    category.id(),
+                    e
+                ));
+                Err(SharedError::new("Failed to save category", 500))
+            }
+        }
+    }
+    async fn delete(&self, id: u32) -> Result<(), SharedError> {
+        // Subcategories, assigned products and promotions keep the category from being deleted
+        let result = sqlx::query(
+            "DELETE FROM categories WHERE id = ? AND NOT EXISTS (SELECT 1 FROM (SELECT id FROM categories WHERE parent_id = ?) AS children) AND NOT EXISTS (SELECT 1 FROM product_categories WHERE category_id = ?) AND NOT EXISTS (SELECT 1 FROM promotions WHERE category_id = ?)",
+        )
+        .bind(id)
+        .bind(id)
+        .bind(id)
+        .bind(id)
+        .execute(self.pool.as_ref())
+        .await;
+        match result {
+            Ok(res) if res.rows_affected() > 0 => Ok(()),
+            Ok(_) => match self.get_by_id(id).await {
+                Some(_) => Err(SharedError::new(
+                    format!(
+                        "Category with ID {} has subcategories, products or promotions assigned",
+                        id
+                    )
+                    .as_str(),
+                    409,
+                )),
+                None => Err(SharedError::new(
+                    format!("Category with ID {} not found", id).as_str(),
+                    404,
+                )),
+            },
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error deleting category id {}: {}", id, e));
+                Err(SharedError::new("Failed to delete category", 500))
+            }
+        }
+    }
+}
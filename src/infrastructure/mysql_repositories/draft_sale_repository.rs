@@ -0,0 +1,256 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use std::sync::Arc;
+
+use super::unit_of_work::MySqlTransaction;
+use crate::{
+    application::services::Logger,
+    domain::{
+        entities::{Customer, DraftItem, DraftSale, DraftSaleStatus},
+        repositories::{CustomerRepository, DraftSaleRepository},
+        unit_of_work::Transaction,
+        value_objects::{CC, ValueObject},
+    },
+    shared::SharedError,
+};
+
+#[derive(FromRow, Debug, Clone)]
+struct DraftSaleModel {
+    id: u32,
+    customer_cc: String,
+    status: String,
+    sale_id: Option<u32>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+#[derive(FromRow, Debug, Clone)]
+struct DraftSaleItemModel {
+    draft_sale_id: u32,
+    product_sku: String,
+    quantity: u32,
+}
+
+pub struct MySqlDraftSaleRepository {
+    pub pool: Arc<sqlx::MySqlPool>,
+    pub logger: Arc<dyn Logger + Send + Sync>,
+    pub customer_repository: Arc<dyn CustomerRepository + Send + Sync>,
+}
+
+impl MySqlDraftSaleRepository {
+    /// Build the drafts of the given models with their customers and items, skipping the
+    /// ones whose data could not be fetched
+    async fn build_drafts(&self, models: Vec<DraftSaleModel>) -> Vec<DraftSale> {
+        if models.is_empty() {
+            return vec![];
+        }
+        let drafts_ids_str = models
+            .iter()
+            .map(|m| m.id.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        let items = match sqlx::query_as::<_, DraftSaleItemModel>(&format!(
+            "SELECT * FROM draft_sale_items WHERE draft_sale_id IN ({}) ORDER BY product_sku",
+            drafts_ids_str
+        ))
+        .fetch_all(self.pool.as_ref())
+        .await
+        {
+            Ok(items) => items,
+            Err(e) => {
+                self.logger
+                    .error(format!("Failed to fetch draft sale items: {}", e).as_str());
+                return vec![];
+            }
+        };
+        let many_cc: Vec<CC> = models
+            .iter()
+            .filter_map(|m| CC::new(m.customer_cc.clone()).ok())
+            .collect();
+        let customers = self
+            .customer_repository
+            .get_many_by_cc(many_cc.iter().collect())
+            .await;
+        models
+            .into_iter()
+            .filter_map(|model| {
+                let customer = customers
+                    .iter()
+                    .find(|c| *c.cc().value() == model.customer_cc);
+                let Some(customer) = customer else {
+                    self.logger.error(
+                        format!(
+                            "Customer with CC {} not found for draft sale {}",
+                            model.customer_cc, model.id
+                        )
+                        .as_str(),
+                    );
+                    return None;
+                };
+                let draft_items = items
+                    .iter()
+                    .filter(|i| i.draft_sale_id == model.id)
+                    .map(|i| DraftItem::new(i.product_sku.clone(), i.quantity))
+                    .collect();
+                Some(DraftSale::new(
+                    model.id,
+                    customer.clone(),
+                    draft_items,
+                    DraftSaleStatus::parse(&model.status).unwrap_or(DraftSaleStatus::Open),
+                    model.sale_id,
+                    model.created_at,
+                    model.updated_at,
+                ))
+            })
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl DraftSaleRepository for MySqlDraftSaleRepository {
+    async fn get_by_id(&self, id: u32) -> Option<DraftSale> {
+        let model =
+            match sqlx::query_as::<_, DraftSaleModel>("SELECT * FROM draft_sales WHERE id = ?")
+                .bind(id)
+                .fetch_optional(self.pool.as_ref())
+                .await
+            {
+                Ok(model) => model?,
+                Err(e) => {
+                    self.logger
+                        .error(format!("Failed to fetch draft sale by id {}: {}", id, e).as_str());
+                    return None;
+                }
+            };
+        self.build_drafts(vec![model]).await.into_iter().next()
+    }
+    async fn get_all_by_status(&self, statuses: &[DraftSaleStatus]) -> Vec<DraftSale> {
+        if statuses.is_empty() {
+            return vec![];
+        }
+        let placeholders = vec!["?"; statuses.len()].join(", ");
+        let query = format!(
+            "SELECT * FROM draft_sales WHERE status IN ({}) ORDER BY updated_at DESC, id DESC",
+            placeholders
+        );
+        let mut query = sqlx::query_as::<_, DraftSaleModel>(&query);
+        for status in statuses {
+            query = query.bind(status.as_str());
+        }
+        match query.fetch_all(self.pool.as_ref()).await {
+            Ok(models) => self.build_drafts(models).await,
+            Err(e) => {
+                self.logger
+                    .error(format!("Failed to fetch draft sales: {}", e).as_str());
+                vec![]
+            }
+        }
+    }
+    async fn create(&self, customer: &Customer) -> Result<DraftSale, SharedError> {
+        let now = Utc::now();
+        let result = sqlx::query(
+            "INSERT INTO draft_sales (customer_cc, status, created_at, updated_at) VALUES (?, ?, ?, ?)",
+        )
+        .bind(customer.cc().value())
+        .bind(DraftSaleStatus::Open.as_str())
+        .bind(now)
+        .bind(now)
+        .execute(self.pool.as_ref())
+        .await;
+        match result {
+            Ok(res) => Ok(DraftSale::new(
+                res.last_insert_id() as u32,
+                customer.clone(),
+                vec![],
+                DraftSaleStatus::Open,
+                None,
+                now,
+                now,
+            )),
+            Err(e) => {
+                self.logger
+                    .error(format!("Failed to create draft sale: {}", e).as_str());
+                Err(SharedError::new("Failed to create draft sale", 500))
+            }
+        }
+    }
+    async fn lock(&self, tx: &mut dyn Transaction, id: u32) -> Result<(), SharedError> {
+        let tx = MySqlTransaction::from_dyn(tx)?;
+        let result =
+            sqlx::query_scalar::<_, u32>("SELECT id FROM draft_sales WHERE id = ? FOR UPDATE")
+                .bind(id)
+                .fetch_optional(tx.connection())
+                .await;
+        match tx.check(result) {
+            Ok(Some(_)) => Ok(()),
+            Ok(None) => Err(SharedError::new(
+                format!("Draft sale with ID {} not found", id).as_str(),
+                404,
+            )),
+            Err(e) => {
+                self.logger
+                    .error(format!("Failed to lock draft sale {}: {}", id, e).as_str());
+                Err(SharedError::new("Failed to lock draft sale", 500))
+            }
+        }
+    }
+    async fn save(&self, tx: &mut dyn Transaction, draft: &DraftSale) -> Result<(), SharedError> {
+        let tx = MySqlTransaction::from_dyn(tx)?;
+        // Waits for a concurrent checkout of the draft, then fails if it went through
+        let result = sqlx::query(
+            "UPDATE draft_sales SET status = ?, sale_id = ?, updated_at = ? WHERE id = ? AND status <> ?",
+        )
+        .bind(draft.status().as_str())
+        .bind(draft.sale_id())
+        .bind(draft.updated_at())
+        .bind(draft.id())
+        .bind(DraftSaleStatus::CheckedOut.as_str())
+        .execute(tx.connection())
+        .await;
+        match tx.check(result) {
+            Ok(res) if res.rows_affected() > 0 => {}
+            Ok(_) => {
+                return Err(SharedError::new(
+                    format!("Draft sale with ID {} is already checked out", draft.id()).as_str(),
+                    409,
+                ));
+            }
+            Err(e) => {
+                self.logger
+                    .error(format!("Failed to save draft sale {}: {}", draft.id(), e).as_str());
+                return Err(SharedError::new("Failed to save draft sale", 500));
+            }
+        }
+        let result = sqlx::query("DELETE FROM draft_sale_items WHERE draft_sale_id = ?")
+            .bind(draft.id())
+            .execute(tx.connection())
+            .await;
+        if let Err(e) = tx.check(result) {
+            self.logger
+                .error(format!("Failed to clear draft sale {} items: {}", draft.id(), e).as_str());
+            return Err(SharedError::new("Failed to save draft sale", 500));
+        }
+        for item in draft.items() {
+            let result = sqlx::query(
+                "INSERT INTO draft_sale_items (draft_sale_id, product_sku, quantity) VALUES (?, ?, ?)",
+            )
+            .bind(draft.id())
+            .bind(item.product_sku())
+            .bind(item.quantity())
+            .execute(tx.connection())
+            .await;
+            if let Err(e) = tx.check(result) {
+                self.logger.error(
+                    format!(
+                        "Failed to save item {} of draft sale {}: {}",
+                        item.product_sku(),
+                        draft.id(),
+                        e
+                    )
+                    .as_str(),
+                );
+                return Err(SharedError::new("Failed to save draft sale", 500));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use std::sync::Arc;
+
+use crate::{
+    application::services::Logger,
+    domain::{
+        entities::{ElectronicInvoice, ElectronicInvoiceStatus},
+        repositories::ElectronicInvoiceRepository,
+    },
+    shared::SharedError,
+};
+
+#[derive(FromRow, Debug, Clone)]
+struct ElectronicInvoiceModel {
+    pub sale_id: u32,
+    pub invoice_number: String,
+    pub cufe: String,
+    pub xml: String,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub submitted_at: Option<DateTime<Utc>>,
+}
+impl From<ElectronicInvoiceModel> for ElectronicInvoice {
+    fn from(model: ElectronicInvoiceModel) -> Self {
+        ElectronicInvoice::new(
+            model.sale_id,
+            model.invoice_number,
+            model.cufe,
+            model.xml,
+            ElectronicInvoiceStatus::parse(&model.status)
+                .unwrap_or(ElectronicInvoiceStatus::Generated),
+            model.created_at,
+            model.submitted_at,
+        )
+    }
+}
+
+pub struct MySqlElectronicInvoiceRepository {
+    pub pool: Arc<sqlx::MySqlPool>,
+    pub logger: Arc<dyn Logger + Send + Sync>,
+}
+
+#[async_trait::async_trait]
+impl ElectronicInvoiceRepository for MySqlElectronicInvoiceRepository {
+    async fn get_by_sale_id(&self, sale_id: u32) -> Option<ElectronicInvoice> {
+        let result = sqlx::query_as::<_, ElectronicInvoiceModel>(
+            "SELECT * FROM electronic_invoices WHERE sale_id = ?",
+        )
+        .bind(sale_id)
+        .fetch_optional(self.pool.as_ref())
+        .await;
+        match result {
+            Ok(opt) => opt.map(|model| model.into()),
+            Err(e) => {
+                self.logger.error(&format!(
+                    "Error fetching electronic invoice of sale {}: {}",
+                    sale_id, e
+                ));
+                None
+            }
+        }
+    }
+    async fn create(&self, invoice: &ElectronicInvoice) -> Result<(), SharedError> {
+        let result = sqlx::query(
+            "INSERT INTO electronic_invoices (sale_id, invoice_number, cufe, xml, status, created_at, submitted_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(invoice.sale_id())
+        .bind(invoice.invoice_number())
+        .bind(invoice.cufe())
+        .bind(invoice.xml())
+        .bind(invoice.status().as_str())
+        .bind(invoice.created_at())
+        .bind(invoice.submitted_at())
+        .execute(self.pool.as_ref())
+        .await;
+        match result {
+            Ok(_) => Ok(()),
+            Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+                Err(SharedError::new(
+                    format!(
+                        "Sale with ID {} already has an electronic invoice",
+                        invoice.sale_id()
+                    )
+                    .as_str(),
+                    409,
+                ))
+            }
+            Err(e) => {
+                self.logger.error(&format!(
+                    "Error creating electronic invoice of sale {}: {}",
+                    invoice.sale_id(),
+                    e
+                ));
+                Err(SharedError::new("Failed to store electronic invoice", 500))
+            }
+        }
+    }
+    async fn save_status(&self, invoice: &ElectronicInvoice) -> Result<(), SharedError> {
+        let result = sqlx::query(
+            "UPDATE electronic_invoices SET status = ?, submitted_at = ? WHERE sale_id = ?",
+        )
+        .bind(invoice.status().as_str())
+        .bind(invoice.submitted_at())
+        .bind(invoice.sale_id())
+        .execute(self.pool.as_ref())
+        .await;
+        match result {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                self.logger.error(&format!(
+                    "Error saving electronic invoice of sale {}: {}",
+                    invoice.sale_id(),
+                    e
+                ));
+                Err(SharedError::new("Failed to save electronic invoice", 500))
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use chrono::{Duration, Utc};
+use sqlx::FromRow;
+use std::sync::Arc;
+
+use super::unit_of_work::MySqlTransaction;
+use crate::{
+    application::services::Logger,
+    domain::{
+        entities::IdempotencyRecord, repositories::IdempotencyRepository, unit_of_work::Transaction,
+    },
+    shared::SharedError,
+};
+
+#[derive(FromRow, Debug, Clone)]
+struct IdempotencyKeyModel {
+    pub idempotency_key: String,
+    pub request_hash: String,
+    pub response: String,
+}
+impl From<IdempotencyKeyModel> for IdempotencyRecord {
+    fn from(model: IdempotencyKeyModel) -> Self {
+        IdempotencyRecord::new(model.idempotency_key, model.request_hash, model.response)
+    }
+}
+
+pub struct MySqlIdempotencyRepository {
+    pub pool: Arc<sqlx::MySqlPool>,
+    pub logger: Arc<dyn Logger + Send + Sync>,
+    /// Time a key is kept, once expired it can be used again
+    pub ttl: Duration,
+}
+
+#[async_trait::async_trait]
+impl IdempotencyRepository for MySqlIdempotencyRepository {
+    async fn get_by_key(&self, key: &str) -> Option<IdempotencyRecord> {
+        let result = sqlx::query_as::<_, IdempotencyKeyModel>(
+            "SELECT idempotency_key, request_hash, response FROM idempotency_keys WHERE idempotency_key = ? AND created_at > ?",
+        )
+        .bind(key)
+        .bind(Utc::now() - self.ttl)
+        .fetch_optional(self.pool.as_ref())
+        .await;
+        match result {
+            Ok(opt) => opt.map(|model| model.into()),
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error fetching idempotency key {}: {}", key, e));
+                None
+            }
+        }
+    }
+    async fn create(
+        &self,
+        tx: &mut dyn Transaction,
+        key: &str,
+        request_hash: &str,
+        response: &str,
+    ) -> Result<IdempotencyRecord, SharedError> {
+        let tx = MySqlTransaction::from_dyn(tx)?;
+        let now = Utc::now();
+        let result = sqlx::query(
+            "DELETE FROM idempotency_keys WHERE idempotency_key = ? AND created_at <= ?",
+        )
+        .bind(key)
+        .bind(now - self.ttl)
+        .execute(tx.connection())
+        .await;
+        if let Err(e) = tx.check(result) {
+            self.logger
+                .error(format!("Failed to expire idempotency key {}: {}", key, e).as_str());
+            return Err(SharedError::new("Failed to store idempotency key", 500));
+        }
+        // A concurrent request with the same key waits here until the first one ends
+        let result = sqlx::query(
+            "INSERT INTO idempotency_keys (idempotency_key, request_hash, response, created_at) VALUES (?, ?, ?, ?)",
+        )
+        .bind(key)
+        .bind(request_hash)
+        .bind(response)
+        .bind(now)
+        .execute(tx.connection())
+        .await;
+        match tx.check(result) {
+            Ok(_) => Ok(IdempotencyRecord::new(
+                key.to_string(),
+                request_hash.to_string(),
+                response.to_string(),
+            )),
+            Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+                Err(SharedError::new(
+                    format!("Idempotency key {} is already in use", key).as_str(),
+                    409,
+                ))
+            }
+            Err(e) => {
+                self.logger
+                    .error(format!("Failed to store idempotency key {}: {}", key, e).as_str());
+                Err(SharedError::new("Failed to store idempotency key", 500))
+            }
+        }
+    }
+}
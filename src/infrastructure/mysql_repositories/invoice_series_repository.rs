@@ -0,0 +1,173 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use std::sync::Arc;
+
+use super::unit_of_work::MySqlTransaction;
+use crate::{
+    application::services::Logger,
+    domain::{
+        entities::{InvoiceSeries, InvoiceSeriesAlert, InvoiceSeriesParams},
+        repositories::InvoiceSeriesRepository,
+        unit_of_work::Transaction,
+        value_objects::InvoiceNumber,
+    },
+    shared::SharedError,
+};
+
+#[derive(FromRow, Debug, Clone)]
+struct InvoiceSeriesModel {
+    pub id: u32,
+    pub prefix: String,
+    pub range_from: u64,
+    pub range_to: u64,
+    pub next_number: u64,
+    pub warning_threshold: u64,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+impl From<InvoiceSeriesModel> for InvoiceSeries {
+    fn from(model: InvoiceSeriesModel) -> Self {
+        InvoiceSeries::new(InvoiceSeriesParams {
+            id: model.id,
+            prefix: model.prefix,
+            range_from: model.range_from,
+            range_to: model.range_to,
+            next_number: model.next_number,
+            warning_threshold: model.warning_threshold,
+            active: model.active,
+            created_at: model.created_at,
+        })
+    }
+}
+
+pub struct MySqlInvoiceSeriesRepository {
+    pub pool: Arc<sqlx::MySqlPool>,
+    pub logger: Arc<dyn Logger + Send + Sync>,
+}
+
+#[async_trait::async_trait]
+impl InvoiceSeriesRepository for MySqlInvoiceSeriesRepository {
+    async fn get_all(&self) -> Vec<InvoiceSeries> {
+        let result =
+            sqlx::query_as::<_, InvoiceSeriesModel>("SELECT * FROM invoice_series ORDER BY id")
+                .fetch_all(self.pool.as_ref())
+                .await;
+        match result {
+            Ok(models) => models.into_iter().map(|model| model.into()).collect(),
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error fetching invoice series: {}", e));
+                Vec::new()
+            }
+        }
+    }
+    async fn create(
+        &self,
+        prefix: &str,
+        range_from: u64,
+        range_to: u64,
+        warning_threshold: u64,
+    ) -> Result<InvoiceSeries, SharedError> {
+        let result = sqlx::query(
+            "INSERT INTO invoice_series (prefix, range_from, range_to, next_number, warning_threshold) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(prefix)
+        .bind(range_from)
+        .bind(range_to)
+        .bind(range_from)
+        .bind(warning_threshold)
+        .execute(self.pool.as_ref())
+        .await;
+        match result {
+            Ok(res) => Ok(InvoiceSeries::new(InvoiceSeriesParams {
+                id: res.last_insert_id() as u32,
+                prefix: prefix.to_string(),
+                range_from,
+                range_to,
+                next_number: range_from,
+                warning_threshold,
+                active: false,
+                created_at: Utc::now(),
+            })),
+            Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+                Err(SharedError::new(
+                    format!("Invoice series {} already exists", prefix).as_str(),
+                    409,
+                ))
+            }
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error creating invoice series {}: {}", prefix, e));
+                Err(SharedError::new("Failed to create invoice series", 500))
+            }
+        }
+    }
+    async fn activate(&self, id: u32) -> Result<(), SharedError> {
+        let result = sqlx::query_scalar::<_, u32>("SELECT id FROM invoice_series WHERE id = ?")
+            .bind(id)
+            .fetch_optional(self.pool.as_ref())
+            .await;
+        match result {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return Err(SharedError::new(
+                    format!("Invoice series with ID {} not found", id).as_str(),
+                    404,
+                ));
+            }
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error fetching invoice series {}: {}", id, e));
+                return Err(SharedError::new("Failed to activate invoice series", 500));
+            }
+        }
+        // A single statement so there is always exactly one active series
+        let result = sqlx::query("UPDATE invoice_series SET active = (id = ?)")
+            .bind(id)
+            .execute(self.pool.as_ref())
+            .await;
+        match result {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error activating invoice series {}: {}", id, e));
+                Err(SharedError::new("Failed to activate invoice series", 500))
+            }
+        }
+    }
+    async fn next_number(
+        &self,
+        tx: &mut dyn Transaction,
+    ) -> Result<(InvoiceNumber, Option<InvoiceSeriesAlert>), SharedError> {
+        let tx = MySqlTransaction::from_dyn(tx)?;
+        let result = sqlx::query_as::<_, InvoiceSeriesModel>(
+            "SELECT * FROM invoice_series WHERE active = TRUE ORDER BY id LIMIT 1 FOR UPDATE",
+        )
+        .fetch_optional(tx.connection())
+        .await;
+        let mut series: InvoiceSeries = match tx.check(result) {
+            Ok(Some(model)) => model.into(),
+            Ok(None) => {
+                return Err(SharedError::new("There is no active invoice series", 409));
+            }
+            Err(e) => {
+                self.logger
+                    .error(format!("Failed to lock invoice series: {}", e).as_str());
+                return Err(SharedError::new("Failed to assign invoice number", 500));
+            }
+        };
+        let invoice_number = series.assign_next()?;
+        let result = sqlx::query("UPDATE invoice_series SET next_number = ? WHERE id = ?")
+            .bind(series.next_number())
+            .bind(series.id())
+            .execute(tx.connection())
+            .await;
+        if let Err(e) = tx.check(result) {
+            self.logger.error(
+                format!("Failed to update invoice series {}: {}", series.prefix(), e).as_str(),
+            );
+            return Err(SharedError::new("Failed to assign invoice number", 500));
+        }
+        Ok((invoice_number, series.near_end_alert()))
+    }
+}
@@ -1,9 +1,25 @@
 mod pool_connection;
 mod customer_repository;
 mod product_repository;
+mod category_repository;
 mod sale_repository;
+mod promotion_repository;
+mod idempotency_repository;
+mod draft_sale_repository;
+mod invoice_series_repository;
+mod electronic_invoice_repository;
+mod stock_movement_repository;
+mod unit_of_work;
 
 pub use product_repository::MySQLProductRepository;
+pub use category_repository::MySqlCategoryRepository;
 pub use customer_repository::MysqlCustomerRepository;
 pub use sale_repository::MySqlSaleRepository;
+pub use promotion_repository::MySqlPromotionRepository;
+pub use idempotency_repository::MySqlIdempotencyRepository;
+pub use draft_sale_repository::MySqlDraftSaleRepository;
+pub use invoice_series_repository::MySqlInvoiceSeriesRepository;
+pub use electronic_invoice_repository::MySqlElectronicInvoiceRepository;
+pub use stock_movement_repository::MySqlStockMovementRepository;
 pub use pool_connection::GuardPool;
+pub use unit_of_work::MySqlUnitOfWork;
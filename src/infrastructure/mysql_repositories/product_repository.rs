@@ -1,50 +1,83 @@
 use chrono::{DateTime, Utc};
-use sqlx::FromRow;
+use serde::Deserialize;
+use sqlx::{FromRow, MySql, MySqlConnection, QueryBuilder};
 use std::sync::Arc;
 
+use super::unit_of_work::MySqlTransaction;
 use crate::{
     application::services::Logger,
     domain::{
-        entities::Product,
-        repositories::ProductRepository,
-        value_objects::{Url, ValueObject},
+        entities::{Category, Product, ProductParams, ProductVariant, StockChange, TaxClass},
+        repositories::{ProductFilter, ProductRepository, ProductSort},
+        unit_of_work::Transaction,
+        value_objects::{Money, Url, ValueObject},
     },
     shared::{Pagination, PaginationResult, SharedError},
 };
 
+/// Products with their categories as a JSON array, filtered and sorted by the caller
+const SELECT_PRODUCTS: &str = "SELECT p.*, (SELECT CAST(JSON_ARRAYAGG(JSON_OBJECT('id', c.id, 'name', c.name, 'parent_id', c.parent_id)) AS CHAR) FROM product_categories pc JOIN categories c ON c.id = pc.category_id WHERE pc.product_id = p.id) AS categories FROM products p";
+
+#[derive(Deserialize, Debug, Clone)]
+struct CategoryModel {
+    pub id: u32,
+    pub name: String,
+    pub parent_id: Option<u32>,
+}
+
 #[derive(FromRow, Debug, Clone)]
 struct ProductModel {
     pub id: u32,
     pub sku: String,
     pub name: String,
     pub price: u64,
+    pub tax_class: String,
     pub stock: u32,
-    pub flags: Option<String>,
+    pub reorder_point: Option<u32>,
+    pub reorder_quantity: Option<u32>,
+    pub parent_id: Option<u32>,
+    pub size: Option<String>,
+    pub color: Option<String>,
+    pub price_override: bool,
+    pub has_variants: bool,
+    pub categories: Option<String>,
     pub img_url: Option<String>,
     pub description: Option<String>,
+    pub archived_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 impl Into<Product> for ProductModel {
     fn into(self) -> Product {
-        Product::new(
-            self.id,
-            self.sku,
-            self.name,
-            (self.price as f64) / 100.0,
-            self.stock,
-            match self.flags {
-                Some(f) => f.split(',').map(|s| s.to_string()).collect(),
-                None => Vec::new(),
-            },
-            match self.img_url {
+        Product::new(ProductParams {
+            id: self.id,
+            sku: self.sku,
+            name: self.name,
+            price: Money::from_minor(self.price as i64),
+            tax_class: TaxClass::parse(&self.tax_class).unwrap_or(TaxClass::General),
+            stock: self.stock,
+            reorder_point: self.reorder_point,
+            reorder_quantity: self.reorder_quantity,
+            variant: self.parent_id.map(|parent_id| {
+                ProductVariant::new(parent_id, self.size, self.color, self.price_override)
+            }),
+            has_variants: self.has_variants,
+            categories: self
+                .categories
+                .and_then(|json| serde_json::from_str::<Vec<CategoryModel>>(&json).ok())
+                .unwrap_or_default()
+                .into_iter()
+                .map(|c| Category::new(c.id, c.name, c.parent_id))
+                .collect(),
+            img_url: match self.img_url {
                 Some(url) => Some(Url::new(url).unwrap()),
                 None => None,
             },
-            self.description,
-            self.created_at,
-            self.updated_at,
-        )
+            description: self.description,
+            archived_at: self.archived_at,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        })
     }
 }
 pub struct MySQLProductRepository {
@@ -58,15 +91,129 @@ impl MySQLProductRepository {
             logger: logger,
         }
     }
+
+    /// Append the change to the stock ledger with the stock left by it, returning that stock
+    async fn record_movement(
+        &self,
+        tx: &mut MySqlTransaction,
+        product: &Product,
+        delta: i64,
+        change: &StockChange,
+    ) -> Result<u32, SharedError> {
+        // The row stays locked by the stock update, so this is the stock left by this change
+        let stock = sqlx::query_scalar::<_, u32>("SELECT stock FROM products WHERE id = ?")
+            .bind(product.id())
+            .fetch_one(tx.connection())
+            .await;
+        let stock = match tx.check(stock) {
+            Ok(stock) => stock,
+            Err(e) => {
+                self.logger.error(&format!(
+                    "Error reading stock of product id {}: {}",
+                    product.id(),
+                    e
+                ));
+                return Err(SharedError::new("Failed to record stock movement", 500));
+            }
+        };
+        let result = sqlx::query(
+            "INSERT INTO stock_movements (product_id, delta, resulting_stock, reason, adjustment_reason, reference_id, user, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, NOW())",
+        )
+        .bind(product.id())
+        .bind(delta)
+        .bind(stock)
+        .bind(change.reason.as_str())
+        .bind(change.adjustment_reason.map(|reason| reason.as_str()))
+        .bind(change.reference_id)
+        .bind(&change.user)
+        .execute(tx.connection())
+        .await;
+        match tx.check(result) {
+            Ok(_) => Ok(stock),
+            Err(e) => {
+                self.logger.error(&format!(
+                    "Error recording stock movement of product id {}: {}",
+                    product.id(),
+                    e
+                ));
+                Err(SharedError::new("Failed to record stock movement", 500))
+            }
+        }
+    }
+}
+
+/// Escape the wildcards of LIKE so the term is matched as typed
+fn escape_like(term: &str) -> String {
+    term.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Append the WHERE clause of the filter to a query on the products aliased as p
+fn push_product_filter(query: &mut QueryBuilder<MySql>, filter: &ProductFilter) {
+    query.push(match filter.archived {
+        true => " WHERE p.archived_at IS NOT NULL",
+        false => " WHERE p.archived_at IS NULL",
+    });
+    if let Some(search) = &filter.search {
+        let pattern = format!("%{}%", escape_like(search));
+        query
+            .push(" AND (p.sku LIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR p.name COLLATE utf8mb4_0900_ai_ci LIKE ")
+            .push_bind(pattern)
+            .push(")");
+    }
+    if let Some(category_ids) = &filter.category_ids {
+        query
+            .push(" AND p.id IN (SELECT product_id FROM product_categories WHERE category_id IN (");
+        let mut separated = query.separated(", ");
+        for id in category_ids {
+            separated.push_bind(*id);
+        }
+        query.push("))");
+    }
+    if let Some(min_price) = filter.min_price {
+        query.push(" AND p.price >= ").push_bind(min_price.minor());
+    }
+    if let Some(max_price) = filter.max_price {
+        query.push(" AND p.price <= ").push_bind(max_price.minor());
+    }
+    if filter.in_stock {
+        query.push(" AND p.stock > 0");
+    }
+}
+
+/// Replace the categories of the product with the given ones
+async fn assign_categories(
+    connection: &mut MySqlConnection,
+    product_id: u32,
+    categories: &[Category],
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM product_categories WHERE product_id = ?")
+        .bind(product_id)
+        .execute(&mut *connection)
+        .await?;
+    for category in categories {
+        sqlx::query(
+            "INSERT IGNORE INTO product_categories (product_id, category_id) VALUES (?, ?)",
+        )
+        .bind(product_id)
+        .bind(category.id())
+        .execute(&mut *connection)
+        .await?;
+    }
+    Ok(())
 }
 
 #[async_trait::async_trait]
 impl ProductRepository for MySQLProductRepository {
     async fn get_by_id(&self, id: u32) -> Option<Product> {
-        let result = sqlx::query_as::<_, ProductModel>("SELECT * FROM products WHERE id = ?")
-            .bind(id)
-            .fetch_optional(self.pool.as_ref())
-            .await;
+        let result =
+            sqlx::query_as::<_, ProductModel>(&format!("{} WHERE p.id = ?", SELECT_PRODUCTS))
+                .bind(id)
+                .fetch_optional(self.pool.as_ref())
+                .await;
         match result {
             Ok(opt) => opt.map(|model| model.into()),
             Err(e) => {
@@ -77,10 +224,11 @@ impl ProductRepository for MySQLProductRepository {
         }
     }
     async fn get_by_sku(&self, sku: &str) -> Option<Product> {
-        let result = sqlx::query_as::<_, ProductModel>("SELECT * FROM products WHERE sku = ?")
-            .bind(sku)
-            .fetch_optional(self.pool.as_ref())
-            .await;
+        let result =
+            sqlx::query_as::<_, ProductModel>(&format!("{} WHERE p.sku = ?", SELECT_PRODUCTS))
+                .bind(sku)
+                .fetch_optional(self.pool.as_ref())
+                .await;
         match result {
             Ok(opt) => opt.map(|model| model.into()),
             Err(e) => {
@@ -90,90 +238,244 @@ impl ProductRepository for MySQLProductRepository {
             }
         }
     }
-    async fn get_many_by_skus(&self, skus: Vec<&str>) -> Vec<Product> {
+    async fn get_many_by_skus(&self, skus: Vec<&str>) -> Result<Vec<Product>, SharedError> {
         if skus.is_empty() {
-            return Vec::new();
+            return Ok(Vec::new());
         }
-        let skus_str: String = skus
-            .iter()
-            .map(|s| format!("'{}'", s))
-            .collect::<Vec<_>>()
-            .join(", ");
-        let result = sqlx::query_as::<_, ProductModel>(&format!(
-            "SELECT * FROM products WHERE sku IN ({})",
-            skus_str
-        ))
-        .fetch_all(self.pool.as_ref())
-        .await;
-        self.logger.debug(&format!("resutl: {}", result.is_ok()));
+        let mut query = QueryBuilder::<MySql>::new(SELECT_PRODUCTS);
+        query.push(" WHERE p.sku IN (");
+        let mut separated = query.separated(", ");
+        for sku in &skus {
+            separated.push_bind(*sku);
+        }
+        separated.push_unseparated(")");
+        let result = query
+            .build_query_as::<ProductModel>()
+            .fetch_all(self.pool.as_ref())
+            .await;
         match result {
-            Ok(models) => models.iter().map(|model| model.clone().into()).collect(),
+            Ok(models) => Ok(models.into_iter().map(|model| model.into()).collect()),
             Err(e) => {
                 self.logger.error(&format!(
                     "Error fetching products by skus {:?}: {}",
                     skus, e
                 ));
-                Vec::new()
+                Err(SharedError::new("Failed to fetch products", 500))
             }
         }
     }
     async fn create(
         &self,
+        tx: &mut dyn Transaction,
         sku: &str,
         name: &str,
-        price: i64,
-        stock: u32,
-        flags: &Vec<String>,
+        price: Money,
+        tax_class: TaxClass,
+        reorder_point: Option<u32>,
+        reorder_quantity: Option<u32>,
+        categories: &[Category],
         img_url: Option<&Url>,
         description: Option<&String>,
     ) -> Result<Product, SharedError> {
-        let flags_str = flags.join(",");
+        let tx = MySqlTransaction::from_dyn(tx)?;
         let img_url_str = img_url.map(|u| u.value().clone());
         let result = sqlx::query(
-            "INSERT INTO products (sku, name, price, stock, flags, img_url, description, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, NOW(), NOW())"
+            "INSERT INTO products (sku, name, price, tax_class, stock, reorder_point, reorder_quantity, img_url, description, created_at, updated_at) VALUES (?, ?, ?, ?, 0, ?, ?, ?, ?, NOW(), NOW())"
         )
         .bind(sku)
         .bind(name)
-        .bind(price)
-        .bind(stock)
-        .bind(flags_str)
+        .bind(price.minor())
+        .bind(tax_class.as_str())
+        .bind(reorder_point)
+        .bind(reorder_quantity)
         .bind(img_url_str)
         .bind(description)
-        .execute(self.pool.as_ref())
+        .execute(tx.connection())
         .await;
-        match result {
-            Ok(res) => {
-                let id = res.last_insert_id() as u32;
-                match self.get_by_id(id).await {
-                    Some(product) => Ok(product),
-                    None => Err(SharedError::new("Failed to retrieve created product", 500)),
-                }
-            }
+        let id = match tx.check(result) {
+            Ok(res) => res.last_insert_id() as u32,
             Err(e) => {
                 self.logger
                     .error(&format!("Error creating product with sku {}: {}", sku, e));
-                Err(SharedError::new("Failed to create product", 500))
+                return Err(SharedError::new("Failed to create product", 500));
+            }
+        };
+        let result = assign_categories(tx.connection(), id, categories).await;
+        if let Err(e) = tx.check(result) {
+            self.logger.error(&format!(
+                "Error assigning categories to product id {}: {}",
+                id, e
+            ));
+            return Err(SharedError::new("Failed to create product", 500));
+        }
+        // Read through the transaction, the row is not visible to the pool until commit
+        let result =
+            sqlx::query_as::<_, ProductModel>(&format!("{} WHERE p.id = ?", SELECT_PRODUCTS))
+                .bind(id)
+                .fetch_one(tx.connection())
+                .await;
+        match tx.check(result) {
+            Ok(model) => Ok(model.into()),
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error fetching created product id {}: {}", id, e));
+                Err(SharedError::new("Failed to retrieve created product", 500))
+            }
+        }
+    }
+    async fn create_variant(
+        &self,
+        tx: &mut dyn Transaction,
+        parent: &Product,
+        sku: &str,
+        name: &str,
+        size: Option<&String>,
+        color: Option<&String>,
+    ) -> Result<Product, SharedError> {
+        let tx = MySqlTransaction::from_dyn(tx)?;
+        let result = sqlx::query(
+            "INSERT INTO products (sku, name, price, tax_class, stock, parent_id, size, color, img_url, description, created_at, updated_at) SELECT ?, ?, price, tax_class, 0, id, ?, ?, img_url, description, NOW(), NOW() FROM products WHERE id = ?",
+        )
+        .bind(sku)
+        .bind(name)
+        .bind(size)
+        .bind(color)
+        .bind(parent.id())
+        .execute(tx.connection())
+        .await;
+        let id = match tx.check(result) {
+            Ok(res) => res.last_insert_id() as u32,
+            Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+                return Err(SharedError::new(
+                    &format!("Product with SKU {} already exists", sku),
+                    409,
+                ));
+            }
+            Err(e) => {
+                self.logger.error(&format!(
+                    "Error creating variant {} of product id {}: {}",
+                    sku,
+                    parent.id(),
+                    e
+                ));
+                return Err(SharedError::new("Failed to create product variant", 500));
+            }
+        };
+        let result = sqlx::query(
+            "INSERT INTO product_categories (product_id, category_id) SELECT ?, category_id FROM product_categories WHERE product_id = ?",
+        )
+        .bind(id)
+        .bind(parent.id())
+        .execute(tx.connection())
+        .await;
+        if let Err(e) = tx.check(result) {
+            self.logger.error(&format!(
+                "Error copying categories of product id {} to variant {}: {}",
+                parent.id(),
+                sku,
+                e
+            ));
+            return Err(SharedError::new("Failed to create product variant", 500));
+        }
+        let result = sqlx::query("UPDATE products SET has_variants = TRUE WHERE id = ?")
+            .bind(parent.id())
+            .execute(tx.connection())
+            .await;
+        if let Err(e) = tx.check(result) {
+            self.logger.error(&format!(
+                "Error marking product id {} as parent: {}",
+                parent.id(),
+                e
+            ));
+            return Err(SharedError::new("Failed to create product variant", 500));
+        }
+        // Read through the transaction, the row is not visible to the pool until commit
+        let result =
+            sqlx::query_as::<_, ProductModel>(&format!("{} WHERE p.id = ?", SELECT_PRODUCTS))
+                .bind(id)
+                .fetch_one(tx.connection())
+                .await;
+        match tx.check(result) {
+            Ok(model) => Ok(model.into()),
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error fetching created variant id {}: {}", id, e));
+                Err(SharedError::new(
+                    "Failed to retrieve created product variant",
+                    500,
+                ))
+            }
+        }
+    }
+    async fn get_variants(&self, parent_id: u32) -> Vec<Product> {
+        let result = sqlx::query_as::<_, ProductModel>(&format!(
+            "{} WHERE p.parent_id = ? ORDER BY p.id",
+            SELECT_PRODUCTS
+        ))
+        .bind(parent_id)
+        .fetch_all(self.pool.as_ref())
+        .await;
+        match result {
+            Ok(models) => models.into_iter().map(|model| model.into()).collect(),
+            Err(e) => {
+                self.logger.error(&format!(
+                    "Error fetching variants of product id {}: {}",
+                    parent_id, e
+                ));
+                Vec::new()
             }
         }
     }
     async fn save(&self, product: &Product) -> Result<(), SharedError> {
-        let flags = product.flags().join(",");
-        let price = (product.price() * 100.0) as i64;
+        let price = product.price().minor();
         let img_url = match product.img_url() {
             Some(url) => Some(url.value().clone()),
             None => None,
         };
-        let result = sqlx::query(
-            "UPDATE products SET name = ?, price = ?, stock = ?, flags = ?, img_url = ?, description = ?, updated_at = NOW() WHERE id = ?"
-        ).bind(product.name())
+        // Details, categories and the variants following them are saved together
+        let result: Result<(), sqlx::Error> = async {
+            let mut tx = self.pool.begin().await?;
+            sqlx::query(
+                "UPDATE products SET name = ?, price = ?, tax_class = ?, reorder_point = ?, reorder_quantity = ?, img_url = ?, description = ?, updated_at = NOW() WHERE id = ?",
+            )
+            .bind(product.name())
             .bind(price)
-            .bind(product.stock())
-            .bind(flags)
+            .bind(product.tax_class().as_str())
+            .bind(product.reorder_point())
+            .bind(product.reorder_quantity())
             .bind(img_url)
             .bind(product.description())
             .bind(product.id())
-            .execute(self.pool.as_ref())
-            .await;
+            .execute(&mut *tx)
+            .await?;
+            assign_categories(&mut tx, product.id(), product.categories()).await?;
+            if product.has_variants() {
+                // Variants follow the tax class and categories of the parent, and its price
+                // unless they override it
+                sqlx::query(
+                    "UPDATE products SET price = IF(price_override, price, ?), tax_class = ?, updated_at = NOW() WHERE parent_id = ?",
+                )
+                .bind(price)
+                .bind(product.tax_class().as_str())
+                .bind(product.id())
+                .execute(&mut *tx)
+                .await?;
+                sqlx::query(
+                    "DELETE pc FROM product_categories pc JOIN products v ON v.id = pc.product_id WHERE v.parent_id = ?",
+                )
+                .bind(product.id())
+                .execute(&mut *tx)
+                .await?;
+                sqlx::query(
+                    "INSERT INTO product_categories (product_id, category_id) SELECT v.id, pc.category_id FROM products v JOIN product_categories pc ON pc.product_id = v.parent_id WHERE v.parent_id = ?",
+                )
+                .bind(product.id())
+                .execute(&mut *tx)
+                .await?;
+            }
+            tx.commit().await
+        }
+        .await;
         match result {
             Ok(_) => Ok(()),
             Err(e) => {
@@ -183,26 +485,178 @@ impl ProductRepository for MySQLProductRepository {
             }
         }
     }
-    async fn get_paginated(&self, pagination: &Pagination) -> PaginationResult<Product> {
-        let total_count_result = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM products")
-            .fetch_one(self.pool.as_ref())
-            .await;
-        if let Err(e) = total_count_result {
-            self.logger
-                .error(&format!("Error counting products: {}", e));
+    async fn save_archived(&self, product: &Product) -> Result<(), SharedError> {
+        // Variants archived on their own before the parent keep their archive date, and only
+        // the ones archived along with the parent are restored with it
+        let query = match product.archived_at() {
+            Some(archived_at) => sqlx::query(
+                "UPDATE products SET archived_at = ?, updated_at = NOW() WHERE id = ? OR (parent_id = ? AND archived_at IS NULL)",
+            )
+            .bind(archived_at)
+            .bind(product.id())
+            .bind(product.id()),
+            None => sqlx::query(
+                "UPDATE products SET archived_at = NULL, updated_at = NOW() WHERE id = ? OR (parent_id = ? AND archived_at = (SELECT archived_at FROM (SELECT archived_at FROM products WHERE id = ?) AS parent))",
+            )
+            .bind(product.id())
+            .bind(product.id())
+            .bind(product.id()),
+        };
+        let result = query.execute(self.pool.as_ref()).await;
+        match result {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                self.logger.error(&format!(
+                    "Error saving archived status of product id {}: {}",
+                    product.id(),
+                    e
+                ));
+                Err(SharedError::new("Failed to save product", 500))
+            }
+        }
+    }
+    async fn decrement_stock(
+        &self,
+        tx: &mut dyn Transaction,
+        product: &Product,
+        quantity: u32,
+        change: &StockChange,
+    ) -> Result<u32, SharedError> {
+        let tx = MySqlTransaction::from_dyn(tx)?;
+        let result = sqlx::query(
+            "UPDATE products SET stock = stock - ?, updated_at = NOW() WHERE id = ? AND stock >= ?",
+        )
+        .bind(quantity)
+        .bind(product.id())
+        .bind(quantity)
+        .execute(tx.connection())
+        .await;
+        match tx.check(result) {
+            Ok(res) if res.rows_affected() > 0 => {
+                self.record_movement(tx, product, -(quantity as i64), change)
+                    .await
+            }
+            Ok(_) => {
+                // Another sale took the stock after it was checked
+                let stock = sqlx::query_scalar::<_, u32>("SELECT stock FROM products WHERE id = ?")
+                    .bind(product.id())
+                    .fetch_one(tx.connection())
+                    .await
+                    .unwrap_or(0);
+                Err(SharedError::new(
+                    &format!(
+                        "Insufficient stock for: {} (short by {})",
+                        product.sku(),
+                        quantity.saturating_sub(stock)
+                    ),
+                    409,
+                ))
+            }
+            Err(e) => {
+                self.logger.error(&format!(
+                    "Error decrementing stock of product id {}: {}",
+                    product.id(),
+                    e
+                ));
+                Err(SharedError::new("Failed to update product stock", 500))
+            }
+        }
+    }
+    async fn increment_stock(
+        &self,
+        tx: &mut dyn Transaction,
+        product: &Product,
+        quantity: u32,
+        change: &StockChange,
+    ) -> Result<u32, SharedError> {
+        let tx = MySqlTransaction::from_dyn(tx)?;
+        let result =
+            sqlx::query("UPDATE products SET stock = stock + ?, updated_at = NOW() WHERE id = ?")
+                .bind(quantity)
+                .bind(product.id())
+                .execute(tx.connection())
+                .await;
+        match tx.check(result) {
+            Ok(_) => {
+                self.record_movement(tx, product, quantity as i64, change)
+                    .await
+            }
+            Err(e) => {
+                self.logger.error(&format!(
+                    "Error incrementing stock of product id {}: {}",
+                    product.id(),
+                    e
+                ));
+                Err(SharedError::new("Failed to update product stock", 500))
+            }
+        }
+    }
+    async fn get_low_stock(&self) -> Vec<Product> {
+        let result = sqlx::query_as::<_, ProductModel>(
+            &format!(
+                "{} WHERE p.archived_at IS NULL AND p.reorder_point IS NOT NULL AND p.stock <= p.reorder_point ORDER BY p.stock, p.sku",
+                SELECT_PRODUCTS
+            ),
+        )
+        .fetch_all(self.pool.as_ref())
+        .await;
+        match result {
+            Ok(models) => models.into_iter().map(|model| model.into()).collect(),
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error fetching low stock products: {}", e));
+                Vec::new()
+            }
+        }
+    }
+    async fn get_paginated(
+        &self,
+        pagination: &Pagination,
+        filter: &ProductFilter,
+    ) -> PaginationResult<Product> {
+        if filter
+            .category_ids
+            .as_ref()
+            .is_some_and(|ids| ids.is_empty())
+        {
             return PaginationResult::from((pagination, 0));
         }
-        let total_count = total_count_result.unwrap() as u32;
+        let mut count_query = QueryBuilder::<MySql>::new("SELECT COUNT(*) FROM products p");
+        push_product_filter(&mut count_query, filter);
+        let total_count = match count_query
+            .build_query_scalar::<i64>()
+            .fetch_one(self.pool.as_ref())
+            .await
+        {
+            Ok(count) => count as u32,
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error counting products: {}", e));
+                return PaginationResult::from((pagination, 0));
+            }
+        };
         if total_count == 0 {
             return PaginationResult::from((pagination, 0));
         }
         let offset = pagination.per_page * (pagination.page - 1);
-        let items_result =
-            sqlx::query_as::<_, ProductModel>("SELECT * FROM products LIMIT ? OFFSET ?")
-                .bind(pagination.per_page as i64)
-                .bind(offset as i64)
-                .fetch_all(self.pool.as_ref())
-                .await;
+        let mut items_query = QueryBuilder::<MySql>::new(SELECT_PRODUCTS);
+        push_product_filter(&mut items_query, filter);
+        items_query.push(match filter.sort {
+            Some(ProductSort::Name) => " ORDER BY p.name, p.id",
+            Some(ProductSort::Price) => " ORDER BY p.price, p.id",
+            Some(ProductSort::Stock) => " ORDER BY p.stock, p.id",
+            Some(ProductSort::Newest) => " ORDER BY p.created_at DESC, p.id DESC",
+            None => " ORDER BY p.id",
+        });
+        items_query
+            .push(" LIMIT ")
+            .push_bind(pagination.per_page as i64)
+            .push(" OFFSET ")
+            .push_bind(offset as i64);
+        let items_result = items_query
+            .build_query_as::<ProductModel>()
+            .fetch_all(self.pool.as_ref())
+            .await;
         match items_result {
             Ok(models) => PaginationResult::from((pagination, total_count))
                 .with_data(models.into_iter().map(|model| model.into()).collect()),
@@ -213,4 +667,30 @@ impl ProductRepository for MySQLProductRepository {
             }
         }
     }
+    async fn autocomplete(&self, term: &str, limit: u32) -> Vec<Product> {
+        let contains = format!("%{}%", escape_like(term));
+        let starts_with = format!("{}%", escape_like(term));
+        // Exact SKU, then SKUs and names starting with the term, then the rest. Parents with
+        // variants are left out as only their variants can be sold.
+        let result = sqlx::query_as::<_, ProductModel>(&format!(
+            "{} WHERE p.archived_at IS NULL AND p.has_variants = FALSE AND (p.sku LIKE ? OR p.name COLLATE utf8mb4_0900_ai_ci LIKE ?) ORDER BY CASE WHEN p.sku = ? THEN 0 WHEN p.sku LIKE ? THEN 1 WHEN p.name COLLATE utf8mb4_0900_ai_ci LIKE ? THEN 2 ELSE 3 END, p.name LIMIT ?",
+            SELECT_PRODUCTS
+        ))
+        .bind(&contains)
+        .bind(&contains)
+        .bind(term)
+        .bind(&starts_with)
+        .bind(&starts_with)
+        .bind(limit)
+        .fetch_all(self.pool.as_ref())
+        .await;
+        match result {
+            Ok(models) => models.into_iter().map(|model| model.into()).collect(),
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error autocompleting products by {}: {}", term, e));
+                Vec::new()
+            }
+        }
+    }
 }
@@ -0,0 +1,242 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use std::sync::Arc;
+
+use crate::{
+    application::services::Logger,
+    domain::{
+        entities::{Category, Promotion, PromotionParams, PromotionRule, PromotionScope},
+        repositories::PromotionRepository,
+        value_objects::Money,
+    },
+    shared::{Pagination, PaginationResult, SharedError},
+};
+
+#[derive(FromRow, Debug, Clone)]
+struct PromotionModel {
+    pub id: u32,
+    pub name: String,
+    pub code: Option<String>,
+    pub scope: String,
+    pub kind: String,
+    pub value: u64,
+    pub buy_quantity: Option<u32>,
+    pub pay_quantity: Option<u32>,
+    pub category_id: Option<u32>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+impl From<PromotionModel> for Promotion {
+    fn from(model: PromotionModel) -> Self {
+        let rule = match model.kind.as_str() {
+            "percentage" => PromotionRule::Percentage((model.value as f64) / 100.0),
+            "fixed_amount" => PromotionRule::FixedAmount(Money::from_minor(model.value as i64)),
+            _ => PromotionRule::BuyXPayY {
+                buy: model.buy_quantity.unwrap_or(1),
+                pay: model.pay_quantity.unwrap_or(1),
+            },
+        };
+        Promotion::new(PromotionParams {
+            id: model.id,
+            name: model.name,
+            code: model.code,
+            scope: match model.scope.as_str() {
+                "sale" => PromotionScope::Sale,
+                _ => PromotionScope::Line,
+            },
+            rule,
+            category_id: model.category_id,
+            starts_at: model.starts_at,
+            ends_at: model.ends_at,
+            active: model.active,
+            created_at: model.created_at,
+        })
+    }
+}
+
+pub struct MySqlPromotionRepository {
+    pub pool: Arc<sqlx::MySqlPool>,
+    pub logger: Arc<dyn Logger + Send + Sync>,
+}
+
+impl MySqlPromotionRepository {
+    /// Load the category hierarchy to extend the categories targeted by the promotions
+    async fn include_subcategories(&self, promotions: &mut [Promotion]) {
+        if promotions.iter().all(|p| p.category_id().is_none()) {
+            return;
+        }
+        let result = sqlx::query_as::<_, (u32, String, Option<u32>)>(
+            "SELECT id, name, parent_id FROM categories",
+        )
+        .fetch_all(self.pool.as_ref())
+        .await;
+        match result {
+            Ok(rows) => {
+                let categories: Vec<Category> = rows
+                    .into_iter()
+                    .map(|(id, name, parent_id)| Category::new(id, name, parent_id))
+                    .collect();
+                for promotion in promotions.iter_mut() {
+                    promotion.include_subcategories(&categories);
+                }
+            }
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error fetching categories of promotions: {}", e));
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PromotionRepository for MySqlPromotionRepository {
+    async fn get_by_code(&self, code: &str) -> Option<Promotion> {
+        let result = sqlx::query_as::<_, PromotionModel>("SELECT * FROM promotions WHERE code = ?")
+            .bind(code)
+            .fetch_optional(self.pool.as_ref())
+            .await;
+        match result {
+            Ok(Some(model)) => {
+                let mut promotions = vec![model.into()];
+                self.include_subcategories(&mut promotions).await;
+                promotions.pop()
+            }
+            Ok(None) => None,
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error fetching promotion by code {}: {}", code, e));
+                None
+            }
+        }
+    }
+    async fn get_automatic_available_at(&self, at: &DateTime<Utc>) -> Vec<Promotion> {
+        let result = sqlx::query_as::<_, PromotionModel>(
+            "SELECT * FROM promotions WHERE code IS NULL AND active = TRUE AND (starts_at IS NULL OR starts_at <= ?) AND (ends_at IS NULL OR ends_at >= ?) ORDER BY id",
+        )
+        .bind(at)
+        .bind(at)
+        .fetch_all(self.pool.as_ref())
+        .await;
+        match result {
+            Ok(models) => {
+                let mut promotions: Vec<Promotion> =
+                    models.into_iter().map(|model| model.into()).collect();
+                self.include_subcategories(&mut promotions).await;
+                promotions
+            }
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error fetching available promotions: {}", e));
+                Vec::new()
+            }
+        }
+    }
+    async fn create(
+        &self,
+        name: &str,
+        code: Option<&str>,
+        scope: PromotionScope,
+        rule: PromotionRule,
+        category_id: Option<u32>,
+        starts_at: Option<&DateTime<Utc>>,
+        ends_at: Option<&DateTime<Utc>>,
+    ) -> Result<Promotion, SharedError> {
+        let (kind, value, buy_quantity, pay_quantity) = match rule {
+            PromotionRule::Percentage(percentage) => {
+                ("percentage", (percentage * 100.0).round() as i64, None, None)
+            }
+            PromotionRule::FixedAmount(amount) => {
+                ("fixed_amount", amount.minor(), None, None)
+            }
+            PromotionRule::BuyXPayY { buy, pay } => ("buy_x_pay_y", 0, Some(buy), Some(pay)),
+        };
+        let result = sqlx::query(
+            "INSERT INTO promotions (name, code, scope, kind, value, buy_quantity, pay_quantity, category_id, starts_at, ends_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(name)
+        .bind(code)
+        .bind(scope.as_str())
+        .bind(kind)
+        .bind(value)
+        .bind(buy_quantity)
+        .bind(pay_quantity)
+        .bind(category_id)
+        .bind(starts_at)
+        .bind(ends_at)
+        .execute(self.pool.as_ref())
+        .await;
+        match result {
+            Ok(res) => Ok(Promotion::new(PromotionParams {
+                id: res.last_insert_id() as u32,
+                name: name.to_string(),
+                code: code.map(|c| c.to_string()),
+                scope,
+                rule,
+                category_id,
+                starts_at: starts_at.cloned(),
+                ends_at: ends_at.cloned(),
+                active: true,
+                created_at: Utc::now(),
+            })),
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error creating promotion {}: {}", name, e));
+                Err(SharedError::new("Failed to create promotion", 500))
+            }
+        }
+    }
+    async fn set_active(&self, id: u32, active: bool) -> Result<(), SharedError> {
+        let result = sqlx::query("UPDATE promotions SET active = ? WHERE id = ?")
+            .bind(active)
+            .bind(id)
+            .execute(self.pool.as_ref())
+            .await;
+        match result {
+            Ok(res) if res.rows_affected() > 0 => Ok(()),
+            Ok(_) => Err(SharedError::new(
+                &format!("Promotion with ID {} not found", id),
+                404,
+            )),
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error updating promotion id {}: {}", id, e));
+                Err(SharedError::new("Failed to update promotion", 500))
+            }
+        }
+    }
+    async fn get_paginated(&self, pagination: &Pagination) -> PaginationResult<Promotion> {
+        let total_count = match sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM promotions")
+            .fetch_one(self.pool.as_ref())
+            .await
+        {
+            Ok(count) => count as u32,
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error counting promotions: {}", e));
+                return PaginationResult::from((pagination, 0));
+            }
+        };
+        if total_count == 0 {
+            return PaginationResult::from((pagination, 0));
+        }
+        let offset = pagination.per_page * (pagination.page - 1);
+        let items_result = sqlx::query_as::<_, PromotionModel>(
+            "SELECT * FROM promotions ORDER BY created_at DESC LIMIT ? OFFSET ?",
+        )
+        .bind(pagination.per_page as i64)
+        .bind(offset as i64)
+        .fetch_all(self.pool.as_ref())
+        .await;
+        match items_result {
+            Ok(models) => PaginationResult::from((pagination, total_count))
+                .with_data(models.into_iter().map(|model| model.into()).collect()),
+            Err(e) => {
+                self.logger
+                    .error(&format!("Error fetching paginated promotions: {}", e));
+                PaginationResult::from((pagination, 0))
+            }
+        }
+    }
+}
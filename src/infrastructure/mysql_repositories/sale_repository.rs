@@ -1,35 +1,69 @@
 use chrono::{DateTime, Utc};
-use sqlx::FromRow;
+use sqlx::{FromRow, MySqlConnection};
 use std::sync::Arc;
 
+use super::unit_of_work::MySqlTransaction;
 use crate::{
     application::services::Logger,
     domain::{
-        entities::{Customer, Product, Sale},
+        entities::{
+            AppliedDiscount, Customer, Exchange, Payment, PaymentMethod, Product, ReturnLine, Sale,
+            SaleLine, SaleParams, SaleReturn, SaleStatus, TaxClass,
+        },
         repositories::{CustomerRepository, ProductRepository, SaleRepository},
-        value_objects::{CC, ValueObject},
+        unit_of_work::Transaction,
+        value_objects::{CC, InvoiceNumber, Money, ValueObject},
     },
     shared::{Pagination, PaginationResult, SharedError},
 };
 
+/// Sales joined with the sale they replace when made by an exchange
+const SELECT_SALES: &str = "SELECT s.*, e.original_sale_id AS exchange_of FROM sales s LEFT JOIN sale_exchanges e ON e.replacement_sale_id = s.id";
+
 #[derive(FromRow, Debug, Clone)]
 struct SaleModel {
     id: u32,
     customer_cc: String,
     generated_at: DateTime<Utc>,
+    status: String,
+    cancel_reason: Option<String>,
+    cancelled_at: Option<DateTime<Utc>>,
+    exchange_of: Option<u32>,
+    invoice_prefix: Option<String>,
+    invoice_number: Option<u64>,
 }
-impl Into<Sale> for (SaleModel, Customer, Vec<(&Product, u32)>) {
+impl Into<Sale>
+    for (
+        SaleModel,
+        Customer,
+        Vec<SaleLine>,
+        Vec<SaleReturn>,
+        Vec<AppliedDiscount>,
+        Vec<Payment>,
+    )
+{
     fn into(self) -> Sale {
-        let (sale_model, customer, products_sale) = self;
-        Sale::new(
-            sale_model.id,
-            products_sale
-                .into_iter()
-                .map(|(p, q)| (p.clone(), q))
-                .collect(),
+        let (sale_model, customer, lines, returns, discounts, payments) = self;
+        Sale::new(SaleParams {
+            id: sale_model.id,
+            lines,
             customer,
-            sale_model.generated_at,
-        )
+            generated_at: sale_model.generated_at,
+            status: match sale_model.status.as_str() {
+                "cancelled" => SaleStatus::Cancelled,
+                _ => SaleStatus::Active,
+            },
+            cancel_reason: sale_model.cancel_reason,
+            cancelled_at: sale_model.cancelled_at,
+            returns,
+            exchange_of: sale_model.exchange_of,
+            discounts,
+            payments,
+            invoice_number: match (sale_model.invoice_prefix, sale_model.invoice_number) {
+                (Some(prefix), Some(number)) => InvoiceNumber::new(prefix, number).ok(),
+                _ => None,
+            },
+        })
     }
 }
 #[derive(FromRow, Debug, Clone)]
@@ -37,6 +71,83 @@ struct SaleProductModel {
     sale_id: u32,
     product_sku: String,
     quantity: u32,
+    product_name: String,
+    unit_price: u64,
+    tax_class: String,
+    tax_rate: u32,
+}
+impl SaleProductModel {
+    fn to_line(&self, product: &Product) -> SaleLine {
+        SaleLine::new(
+            product.clone(),
+            self.quantity,
+            self.product_name.clone(),
+            Money::from_minor(self.unit_price as i64),
+            TaxClass::parse(&self.tax_class).unwrap_or(TaxClass::General),
+            (self.tax_rate as f64) / 100.0,
+        )
+    }
+}
+
+#[derive(FromRow, Debug, Clone)]
+struct SaleReturnModel {
+    id: u32,
+    sale_id: u32,
+    reason: Option<String>,
+    created_at: DateTime<Utc>,
+}
+#[derive(FromRow, Debug, Clone)]
+struct SaleReturnProductModel {
+    return_id: u32,
+    product_sku: String,
+    quantity: u32,
+    unit_price: u64,
+}
+
+#[derive(FromRow, Debug, Clone)]
+struct SaleDiscountModel {
+    sale_id: u32,
+    promotion_id: Option<u32>,
+    product_sku: Option<String>,
+    description: String,
+    amount: u64,
+}
+impl From<SaleDiscountModel> for AppliedDiscount {
+    fn from(model: SaleDiscountModel) -> Self {
+        AppliedDiscount::new(
+            model.promotion_id,
+            model.product_sku,
+            model.description,
+            Money::from_minor(model.amount as i64),
+        )
+    }
+}
+
+#[derive(FromRow, Debug, Clone)]
+struct SalePaymentModel {
+    sale_id: u32,
+    method: String,
+    amount: u64,
+    tendered: Option<u64>,
+}
+impl SalePaymentModel {
+    fn into_payment(self) -> Result<Payment, SharedError> {
+        let method = PaymentMethod::parse(&self.method)?;
+        Payment::new(
+            method,
+            Money::from_minor(self.amount as i64),
+            self.tendered.map(|t| Money::from_minor(t as i64)),
+        )
+    }
+}
+
+/// Rows of a sale and of what belongs to it, read with a single connection
+struct SaleRows {
+    sale: SaleModel,
+    products: Vec<SaleProductModel>,
+    returns: Vec<SaleReturn>,
+    discounts: Vec<AppliedDiscount>,
+    payments: Vec<Payment>,
 }
 
 pub struct MySqlSaleRepository {
@@ -61,13 +172,126 @@ impl MySqlSaleRepository {
     }
 }
 
-#[async_trait::async_trait]
-impl SaleRepository for MySqlSaleRepository {
-    async fn get_by_id(&self, id: &u32) -> Option<Sale> {
-        let sale_model =
-            match sqlx::query_as::<_, SaleModel>("SELECT * FROM sales WHERE id = ? LIMIT 1")
+impl MySqlSaleRepository {
+    /// Fetch the returns of the given sales as (sale id, return), None if they could not be fetched
+    async fn get_returns(
+        &self,
+        conn: &mut MySqlConnection,
+        sales_ids: &[u32],
+    ) -> Option<Vec<(u32, SaleReturn)>> {
+        if sales_ids.is_empty() {
+            return Some(vec![]);
+        }
+        let sales_ids_str = sales_ids
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        let returns = match sqlx::query_as::<_, SaleReturnModel>(&format!(
+            "SELECT * FROM sale_returns WHERE sale_id IN ({})",
+            sales_ids_str
+        ))
+        .fetch_all(&mut *conn)
+        .await
+        {
+            Ok(returns) => returns,
+            Err(e) => {
+                self.logger
+                    .error(format!("Failed to fetch sale returns: {}", e).as_str());
+                return None;
+            }
+        };
+        if returns.is_empty() {
+            return Some(vec![]);
+        }
+        let returns_ids_str = returns
+            .iter()
+            .map(|r| r.id.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        let returns_products = match sqlx::query_as::<_, SaleReturnProductModel>(&format!(
+            "SELECT * FROM sale_return_product WHERE return_id IN ({})",
+            returns_ids_str
+        ))
+        .fetch_all(&mut *conn)
+        .await
+        {
+            Ok(returns_products) => returns_products,
+            Err(e) => {
+                self.logger
+                    .error(format!("Failed to fetch sale returns products: {}", e).as_str());
+                return None;
+            }
+        };
+        Some(
+            returns
+                .into_iter()
+                .map(|r| {
+                    let lines = returns_products
+                        .iter()
+                        .filter(|rp| rp.return_id == r.id)
+                        .map(|rp| {
+                            ReturnLine::new(
+                                rp.product_sku.clone(),
+                                rp.quantity,
+                                Money::from_minor(rp.unit_price as i64),
+                            )
+                        })
+                        .collect();
+                    (r.sale_id, SaleReturn::new(r.id, lines, r.reason, r.created_at))
+                })
+                .collect(),
+        )
+    }
+
+    /// Fetch the discounts of the given sales as (sale id, discount), None if they could not be fetched
+    async fn get_discounts(
+        &self,
+        conn: &mut MySqlConnection,
+        sales_ids: &[u32],
+    ) -> Option<Vec<(u32, AppliedDiscount)>> {
+        if sales_ids.is_empty() {
+            return Some(vec![]);
+        }
+        let sales_ids_str = sales_ids
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        match sqlx::query_as::<_, SaleDiscountModel>(&format!(
+            "SELECT * FROM sale_discounts WHERE sale_id IN ({}) ORDER BY id",
+            sales_ids_str
+        ))
+        .fetch_all(&mut *conn)
+        .await
+        {
+            Ok(discounts) => Some(
+                discounts
+                    .into_iter()
+                    .map(|d| (d.sale_id, d.into()))
+                    .collect(),
+            ),
+            Err(e) => {
+                self.logger
+                    .error(format!("Failed to fetch sale discounts: {}", e).as_str());
+                None
+            }
+        }
+    }
+}
+
+impl MySqlSaleRepository {
+    /// Read the rows of the sale with a single connection, None if it does not exist or could
+    /// not be read
+    async fn get_sale_rows(
+        &self,
+        conn: &mut MySqlConnection,
+        id: &u32,
+    ) -> Option<SaleRows> {
+        let sale =
+            match sqlx::query_as::<_, SaleModel>(&format!("{} WHERE s.id = ? LIMIT 1", SELECT_SALES))
                 .bind(id)
-                .fetch_one(self.pool.as_ref())
+                .fetch_one(&mut *conn)
                 .await
             {
                 Ok(sale) => sale,
@@ -77,53 +301,85 @@ impl SaleRepository for MySqlSaleRepository {
                     return None;
                 }
             };
-        let customer = match self
-            .customer_repository
-            .get_by_cc(&CC::new(sale_model.customer_cc.clone()).unwrap())
-            .await
+        let products = match sqlx::query_as::<_, SaleProductModel>(
+            "SELECT * FROM sale_product WHERE sale_id = ?",
+        )
+        .bind(sale.id)
+        .fetch_all(&mut *conn)
+        .await
         {
-            Some(cust) => cust,
-            None => {
+            Ok(products) => products,
+            Err(e) => {
                 self.logger.error(
-                    format!(
-                        "Customer with CC {} not found for sale {}",
-                        sale_model.customer_cc, sale_model.id
-                    )
-                    .as_str(),
+                    format!("Failed to fetch sale products for sale {}: {}", sale.id, e).as_str(),
                 );
                 return None;
             }
         };
-        let sales_products = match sqlx::query_as::<_, SaleProductModel>(
-            "SELECT * FROM sale_product WHERE sale_id = ?",
-        )
-        .bind(sale_model.id)
-        .fetch_all(self.pool.as_ref())
-        .await
+        let returns = self
+            .get_returns(&mut *conn, &[sale.id])
+            .await?
+            .into_iter()
+            .map(|(_, r)| r)
+            .collect();
+        let discounts = self
+            .get_discounts(&mut *conn, &[sale.id])
+            .await?
+            .into_iter()
+            .map(|(_, d)| d)
+            .collect();
+        let payments = self
+            .get_payments(&mut *conn, &[sale.id])
+            .await?
+            .into_iter()
+            .map(|(_, p)| p)
+            .collect();
+        Some(SaleRows {
+            sale,
+            products,
+            returns,
+            discounts,
+            payments,
+        })
+    }
+
+    /// Build the sale from its rows along with its customer and products
+    async fn to_sale(&self, rows: SaleRows) -> Option<Sale> {
+        let SaleRows {
+            sale,
+            products,
+            returns,
+            discounts,
+            payments,
+        } = rows;
+        let customer = match self
+            .customer_repository
+            .get_by_cc(&CC::new(sale.customer_cc.clone()).unwrap())
+            .await
         {
-            Ok(sales_products) => sales_products,
-            Err(e) => {
+            Some(cust) => cust,
+            None => {
                 self.logger.error(
                     format!(
-                        "Failed to fetch sale products for sale {}: {}",
-                        sale_model.id, e
+                        "Customer with CC {} not found for sale {}",
+                        sale.customer_cc, sale.id
                     )
                     .as_str(),
                 );
                 return None;
             }
         };
-        let mut products_sale: Vec<(Product, u32)> = Vec::new();
-        for sp in sales_products.iter() {
+        let mut lines: Vec<SaleLine> = Vec::new();
+        for sp in products.iter() {
             match self.product_repository.get_by_sku(&sp.product_sku).await {
                 Some(prod) => {
-                    products_sale.push((prod, sp.quantity));
+                    lines.push(sp.to_line(&prod));
                 }
                 None => {
                     self.logger.error(
                         format!(
                             "Product with SKU {} not found for sale {}",
-                            sp.product_sku, sale_model.id
+                            sp.product_sku, sale.id
                         )
                         .as_str(),
                     );
@@ -131,26 +387,120 @@ impl SaleRepository for MySqlSaleRepository {
                 }
             }
         }
-        Some(
-            (
-                sale_model,
-                customer,
-                products_sale.iter().map(|(p, q)| (p, *q)).collect(),
-            )
-                .into(),
+        Some((sale, customer, lines, returns, discounts, payments).into())
+    }
+
+    /// Fetch the payments of the given sales as (sale id, payment), None if they could not be
+    /// fetched or any of them is invalid
+    async fn get_payments(
+        &self,
+        conn: &mut MySqlConnection,
+        sales_ids: &[u32],
+    ) -> Option<Vec<(u32, Payment)>> {
+        if sales_ids.is_empty() {
+            return Some(vec![]);
+        }
+        let sales_ids_str = sales_ids
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        let models = match sqlx::query_as::<_, SalePaymentModel>(&format!(
+            "SELECT * FROM sale_payments WHERE sale_id IN ({}) ORDER BY id",
+            sales_ids_str
+        ))
+        .fetch_all(&mut *conn)
+        .await
+        {
+            Ok(models) => models,
+            Err(e) => {
+                self.logger
+                    .error(format!("Failed to fetch sale payments: {}", e).as_str());
+                return None;
+            }
+        };
+        // Every invalid row is logged before failing, a sale must not show fewer payments
+        let mut payments = Vec::new();
+        let mut invalid = false;
+        for model in models {
+            let sale_id = model.sale_id;
+            let method = model.method.clone();
+            match model.into_payment() {
+                Ok(payment) => payments.push((sale_id, payment)),
+                Err(e) => {
+                    self.logger.error(
+                        format!(
+                            "Invalid {} payment for sale {}: {}",
+                            method, sale_id, e.message
+                        )
+                        .as_str(),
+                    );
+                    invalid = true;
+                }
+            }
+        }
+        if invalid {
+            return None;
+        }
+        Some(payments)
+    }
+}
+
+#[async_trait::async_trait]
+impl SaleRepository for MySqlSaleRepository {
+    async fn get_by_invoice_number(&self, invoice_number: &InvoiceNumber) -> Option<Sale> {
+        let result = sqlx::query_scalar::<_, u32>(
+            "SELECT id FROM sales WHERE invoice_prefix = ? AND invoice_number = ?",
         )
+        .bind(invoice_number.prefix())
+        .bind(invoice_number.number())
+        .fetch_optional(self.pool.as_ref())
+        .await;
+        match result {
+            Ok(Some(id)) => self.get_by_id(&id).await,
+            Ok(None) => None,
+            Err(e) => {
+                self.logger.error(
+                    format!("Failed to fetch sale by invoice {}: {}", invoice_number, e).as_str(),
+                );
+                None
+            }
+        }
+    }
+    async fn get_by_id(&self, id: &u32) -> Option<Sale> {
+        let mut conn = match self.pool.acquire().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                self.logger
+                    .error(format!("Failed to fetch sale by id {}: {}", id, e).as_str());
+                return None;
+            }
+        };
+        let rows = self.get_sale_rows(&mut conn, id).await;
+        // Released before the customer and products are read with connections of their own
+        drop(conn);
+        self.to_sale(rows?).await
     }
     async fn create(
         &self,
+        tx: &mut dyn Transaction,
         customer: &Customer,
-        products_sale: Vec<(&Product, u32)>,
+        invoice_number: InvoiceNumber,
+        lines: Vec<SaleLine>,
+        discounts: Vec<AppliedDiscount>,
+        payments: Vec<Payment>,
     ) -> Result<Sale, SharedError> {
+        let tx = MySqlTransaction::from_dyn(tx)?;
         let customer_cc = customer.cc().value().to_string();
-        let result_sale = sqlx::query("INSERT INTO sales (customer_cc) VALUES(?)")
-            .bind(&customer_cc)
-            .execute(self.pool.as_ref())
-            .await;
-        let sale_id = match result_sale {
+        let result_sale = sqlx::query(
+            "INSERT INTO sales (customer_cc, invoice_prefix, invoice_number) VALUES(?, ?, ?)",
+        )
+        .bind(&customer_cc)
+        .bind(invoice_number.prefix())
+        .bind(invoice_number.number())
+        .execute(tx.connection())
+        .await;
+        let sale_id = match tx.check(result_sale) {
             Ok(res) => res.last_insert_id() as u32,
             Err(e) => {
                 self.logger
@@ -158,38 +508,237 @@ impl SaleRepository for MySqlSaleRepository {
                 return Err(SharedError::new("Failed to create sale", 500));
             }
         };
-        let mut values = String::new();
-        for (p, quantity) in products_sale.iter() {
-            values.push_str(&format!("({}, '{}', {}),", sale_id, p.sku(), quantity));
+        // Built first so the tax breakdown of the lines is stored along with them
+        let sale: Sale = (
+            SaleModel {
+                id: sale_id,
+                customer_cc: customer_cc,
+                generated_at: Utc::now(),
+                status: SaleStatus::Active.as_str().to_string(),
+                cancel_reason: None,
+                cancelled_at: None,
+                exchange_of: None,
+                invoice_prefix: Some(invoice_number.prefix().clone()),
+                invoice_number: Some(invoice_number.number()),
+            },
+            customer.clone(),
+            lines,
+            vec![],
+            discounts,
+            payments,
+        )
+            .into();
+        let line_taxes = sale.line_taxes()?;
+        let mut query_builder = sqlx::QueryBuilder::<sqlx::MySql>::new(
+            "INSERT INTO sale_product (sale_id, product_sku, quantity, product_name, unit_price, tax_class, tax_rate, base_amount, tax_amount) ",
+        );
+        query_builder.push_values(
+            sale.lines().iter().zip(line_taxes),
+            |mut row, (line, line_tax)| {
+                row.push_bind(sale_id)
+                    .push_bind(line.product().sku())
+                    .push_bind(line.quantity())
+                    .push_bind(line.product_name())
+                    .push_bind(line.unit_price().minor())
+                    .push_bind(line.tax_class().as_str())
+                    .push_bind((line.tax_rate() * 100.0).round() as i64)
+                    .push_bind(line_tax.base().minor())
+                    .push_bind(line_tax.tax().minor());
+            },
+        );
+        let result = query_builder.build().execute(tx.connection()).await;
+        if let Err(e) = tx.check(result) {
+            self.logger
+                .error(format!("Failed to create sale products: {}", e).as_str());
+            return Err(SharedError::new("Failed to create sale products", 500));
         }
-        values.pop();
-
-        let result = sqlx::query(&format!(
-            "INSERT INTO sale_product (sale_id, product_sku, quantity) VALUES {}",
-            values
-        ))
-        .execute(self.pool.as_ref())
-        .await;
-        match result {
-            Ok(_) => Ok((
-                SaleModel {
-                    id: sale_id,
-                    customer_cc: customer_cc,
-                    generated_at: Utc::now(),
+        if !sale.discounts().is_empty() {
+            let mut query_builder = sqlx::QueryBuilder::<sqlx::MySql>::new(
+                "INSERT INTO sale_discounts (sale_id, promotion_id, product_sku, description, amount) ",
+            );
+            query_builder.push_values(sale.discounts().iter(), |mut row, discount| {
+                row.push_bind(sale_id)
+                    .push_bind(discount.promotion_id())
+                    .push_bind(discount.product_sku())
+                    .push_bind(discount.description())
+                    .push_bind(discount.amount().minor());
+            });
+            let result = query_builder.build().execute(tx.connection()).await;
+            if let Err(e) = tx.check(result) {
+                self.logger
+                    .error(format!("Failed to create sale discounts: {}", e).as_str());
+                return Err(SharedError::new("Failed to create sale discounts", 500));
+            }
+        }
+        if !sale.payments().is_empty() {
+            let changes = sale
+                .payments()
+                .iter()
+                .map(|payment| payment.change())
+                .collect::<Result<Vec<Money>, SharedError>>()?;
+            let mut query_builder = sqlx::QueryBuilder::<sqlx::MySql>::new(
+                "INSERT INTO sale_payments (sale_id, method, amount, tendered, change_amount) ",
+            );
+            query_builder.push_values(
+                sale.payments().iter().zip(changes),
+                |mut row, (payment, change)| {
+                    row.push_bind(sale_id)
+                        .push_bind(payment.method().as_str())
+                        .push_bind(payment.amount().minor())
+                        .push_bind(payment.tendered().map(|t| t.minor()))
+                        .push_bind(change.minor());
                 },
-                customer.clone(),
-                products_sale,
-            )
-                .into()),
+            );
+            let result = query_builder.build().execute(tx.connection()).await;
+            if let Err(e) = tx.check(result) {
+                self.logger
+                    .error(format!("Failed to create sale payments: {}", e).as_str());
+                return Err(SharedError::new("Failed to create sale payments", 500));
+            }
+        }
+        Ok(sale)
+    }
+    async fn create_exchange(
+        &self,
+        tx: &mut dyn Transaction,
+        original_sale: &Sale,
+        sale_return: &SaleReturn,
+        replacement_sale: &Sale,
+    ) -> Result<Exchange, SharedError> {
+        let tx = MySqlTransaction::from_dyn(tx)?;
+        let price_difference = Exchange::price_difference_of(sale_return, replacement_sale)?;
+        let result = sqlx::query(
+            "INSERT INTO sale_exchanges (original_sale_id, return_id, replacement_sale_id, price_difference) VALUES (?, ?, ?, ?)",
+        )
+        .bind(original_sale.id())
+        .bind(sale_return.id())
+        .bind(replacement_sale.id())
+        .bind(price_difference.minor())
+        .execute(tx.connection())
+        .await;
+        match tx.check(result) {
+            Ok(res) => Ok(Exchange::new(
+                res.last_insert_id() as u32,
+                original_sale.id(),
+                sale_return.id(),
+                replacement_sale.id(),
+                price_difference,
+                Utc::now(),
+            )),
             Err(e) => {
                 self.logger
-                    .error(format!("Failed to create sale products: {}", e).as_str());
-                Err(SharedError::new("Failed to create sale products", 500))
+                    .error(format!("Failed to create sale exchange: {}", e).as_str());
+                Err(SharedError::new("Failed to create sale exchange", 500))
+            }
+        }
+    }
+    async fn save_cancellation(
+        &self,
+        tx: &mut dyn Transaction,
+        sale: &Sale,
+    ) -> Result<(), SharedError> {
+        let tx = MySqlTransaction::from_dyn(tx)?;
+        let result = sqlx::query(
+            "UPDATE sales SET status = ?, cancel_reason = ?, cancelled_at = ? WHERE id = ? AND status = ?",
+        )
+        .bind(sale.status().as_str())
+        .bind(sale.cancel_reason())
+        .bind(sale.cancelled_at())
+        .bind(sale.id())
+        .bind(SaleStatus::Active.as_str())
+        .execute(tx.connection())
+        .await;
+        match tx.check(result) {
+            Ok(res) if res.rows_affected() > 0 => Ok(()),
+            Ok(_) => Err(SharedError::new(
+                format!("Sale with ID {} is already cancelled", sale.id()).as_str(),
+                409,
+            )),
+            Err(e) => {
+                self.logger
+                    .error(format!("Failed to cancel sale {}: {}", sale.id(), e).as_str());
+                Err(SharedError::new("Failed to cancel sale", 500))
+            }
+        }
+    }
+    async fn get_by_id_for_update(
+        &self,
+        tx: &mut dyn Transaction,
+        id: &u32,
+    ) -> Result<Sale, SharedError> {
+        let tx = MySqlTransaction::from_dyn(tx)?;
+        let result = sqlx::query_scalar::<_, u32>("SELECT id FROM sales WHERE id = ? FOR UPDATE")
+            .bind(id)
+            .fetch_optional(tx.connection())
+            .await;
+        match tx.check(result) {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return Err(SharedError::new(
+                    format!("Sale with ID {} not found", id).as_str(),
+                    404,
+                ));
+            }
+            Err(e) => {
+                self.logger
+                    .error(format!("Failed to lock sale {}: {}", id, e).as_str());
+                return Err(SharedError::new("Failed to lock sale", 500));
+            }
+        }
+        // Read through the transaction so the sale checked is the one locked
+        let sale = match self.get_sale_rows(tx.connection(), id).await {
+            Some(rows) => self.to_sale(rows).await,
+            None => None,
+        };
+        sale.ok_or_else(|| SharedError::new("Failed to fetch sale", 500))
+    }
+    async fn create_return(
+        &self,
+        tx: &mut dyn Transaction,
+        sale: &Sale,
+        lines: Vec<ReturnLine>,
+        reason: Option<&str>,
+    ) -> Result<SaleReturn, SharedError> {
+        let tx = MySqlTransaction::from_dyn(tx)?;
+        let result_return = sqlx::query("INSERT INTO sale_returns (sale_id, reason) VALUES (?, ?)")
+            .bind(sale.id())
+            .bind(reason)
+            .execute(tx.connection())
+            .await;
+        let return_id = match tx.check(result_return) {
+            Ok(res) => res.last_insert_id() as u32,
+            Err(e) => {
+                self.logger
+                    .error(format!("Failed to create sale return: {}", e).as_str());
+                return Err(SharedError::new("Failed to create sale return", 500));
+            }
+        };
+        let mut query_builder = sqlx::QueryBuilder::<sqlx::MySql>::new(
+            "INSERT INTO sale_return_product (return_id, product_sku, quantity, unit_price) ",
+        );
+        query_builder.push_values(lines.iter(), |mut row, line| {
+            row.push_bind(return_id)
+                .push_bind(line.product_sku())
+                .push_bind(line.quantity())
+                .push_bind(line.unit_price().minor());
+        });
+        let result = query_builder.build().execute(tx.connection()).await;
+        match tx.check(result) {
+            Ok(_) => Ok(SaleReturn::new(
+                return_id,
+                lines,
+                reason.map(|r| r.to_string()),
+                Utc::now(),
+            )),
+            Err(e) => {
+                self.logger
+                    .error(format!("Failed to create sale return products: {}", e).as_str());
+                Err(SharedError::new("Failed to create sale return products", 500))
             }
         }
     }
     async fn get_all(&self) -> Vec<Sale> {
-        let sales = match sqlx::query_as::<_, SaleModel>("SELECT * FROM sales")
+        let sales = match sqlx::query_as::<_, SaleModel>(SELECT_SALES)
             .fetch_all(self.pool.as_ref())
             .await
         {
@@ -213,7 +762,7 @@ impl SaleRepository for MySqlSaleRepository {
                     return vec![];
                 }
             };
-        let mut products = self
+        let mut products = match self
             .product_repository
             .get_many_by_skus(
                 sales_products
@@ -221,8 +770,51 @@ impl SaleRepository for MySqlSaleRepository {
                     .map(|sp| sp.product_sku.as_str())
                     .collect(),
             )
-            .await;
+            .await
+        {
+            Ok(products) => products,
+            Err(_) => return vec![],
+        };
 
+        let mut conn = match self.pool.acquire().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                self.logger
+                    .error(format!("Failed to fetch sales: {}", e).as_str());
+                return vec![];
+            }
+        };
+        let returns = match self
+            .get_returns(
+                &mut conn,
+                &sales.iter().map(|sale| sale.id).collect::<Vec<u32>>(),
+            )
+            .await
+        {
+            Some(returns) => returns,
+            None => return vec![],
+        };
+        let discounts = match self
+            .get_discounts(
+                &mut conn,
+                &sales.iter().map(|sale| sale.id).collect::<Vec<u32>>(),
+            )
+            .await
+        {
+            Some(discounts) => discounts,
+            None => return vec![],
+        };
+        let payments = match self
+            .get_payments(
+                &mut conn,
+                &sales.iter().map(|sale| sale.id).collect::<Vec<u32>>(),
+            )
+            .await
+        {
+            Some(payments) => payments,
+            None => return vec![],
+        };
+        drop(conn);
         let mut result_sales: Vec<Sale> = Vec::new();
         for sale in sales {
             let customer = match customers
@@ -241,11 +833,11 @@ impl SaleRepository for MySqlSaleRepository {
                     continue;
                 }
             };
-            let mut products_sale: Vec<(&Product, u32)> = Vec::new();
+            let mut lines: Vec<SaleLine> = Vec::new();
             for sp in sales_products.iter().filter(|sp| sp.sale_id == sale.id) {
                 match products.iter().find(|p| p.sku().clone() == sp.product_sku) {
                     Some(prod) => {
-                        products_sale.push((prod, sp.quantity));
+                        lines.push(sp.to_line(prod));
                     }
                     None => {
                         self.logger.error(
@@ -259,7 +851,30 @@ impl SaleRepository for MySqlSaleRepository {
                     }
                 }
             }
-            let sale = ((sale, customer.clone(), products_sale)).into();
+            let sale_returns = returns
+                .iter()
+                .filter(|(sale_id, _)| *sale_id == sale.id)
+                .map(|(_, r)| r.clone())
+                .collect();
+            let sale_discounts = discounts
+                .iter()
+                .filter(|(sale_id, _)| *sale_id == sale.id)
+                .map(|(_, d)| d.clone())
+                .collect();
+            let sale_payments = payments
+                .iter()
+                .filter(|(sale_id, _)| *sale_id == sale.id)
+                .map(|(_, p)| p.clone())
+                .collect();
+            let sale = ((
+                sale,
+                customer.clone(),
+                lines,
+                sale_returns,
+                sale_discounts,
+                sale_payments,
+            ))
+                .into();
             result_sales.push(sale);
         }
         result_sales
@@ -274,7 +889,7 @@ impl SaleRepository for MySqlSaleRepository {
             }
         };
         let sales =
-            match sqlx::query_as::<_, SaleModel>("SELECT * FROM sales WHERE customer_cc = ?")
+            match sqlx::query_as::<_, SaleModel>(&format!("{} WHERE s.customer_cc = ?", SELECT_SALES))
                 .bind(cc.value())
                 .fetch_all(self.pool.as_ref())
                 .await
@@ -305,13 +920,52 @@ impl SaleRepository for MySqlSaleRepository {
                 return vec![];
             }
         };
+        let mut conn = match self.pool.acquire().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                self.logger
+                    .error(format!("Failed to fetch sales: {}", e).as_str());
+                return vec![];
+            }
+        };
+        let returns = match self
+            .get_returns(
+                &mut conn,
+                &sales.iter().map(|sale| sale.id).collect::<Vec<u32>>(),
+            )
+            .await
+        {
+            Some(returns) => returns,
+            None => return vec![],
+        };
+        let discounts = match self
+            .get_discounts(
+                &mut conn,
+                &sales.iter().map(|sale| sale.id).collect::<Vec<u32>>(),
+            )
+            .await
+        {
+            Some(discounts) => discounts,
+            None => return vec![],
+        };
+        let payments = match self
+            .get_payments(
+                &mut conn,
+                &sales.iter().map(|sale| sale.id).collect::<Vec<u32>>(),
+            )
+            .await
+        {
+            Some(payments) => payments,
+            None => return vec![],
+        };
+        drop(conn);
         let mut result_sales: Vec<Sale> = Vec::new();
         for sale in sales {
-            let mut products_sale: Vec<(Product, u32)> = Vec::new();
+            let mut lines: Vec<SaleLine> = Vec::new();
             for sp in sales_products.iter().filter(|sp| sp.sale_id == sale.id) {
                 match self.product_repository.get_by_sku(&sp.product_sku).await {
                     Some(prod) => {
-                        products_sale.push((prod.clone(), sp.quantity));
+                        lines.push(sp.to_line(&prod));
                     }
                     None => {
                         self.logger.error(
@@ -325,10 +979,28 @@ impl SaleRepository for MySqlSaleRepository {
                     }
                 }
             }
+            let sale_returns = returns
+                .iter()
+                .filter(|(sale_id, _)| *sale_id == sale.id)
+                .map(|(_, r)| r.clone())
+                .collect();
+            let sale_discounts = discounts
+                .iter()
+                .filter(|(sale_id, _)| *sale_id == sale.id)
+                .map(|(_, d)| d.clone())
+                .collect();
+            let sale_payments = payments
+                .iter()
+                .filter(|(sale_id, _)| *sale_id == sale.id)
+                .map(|(_, p)| p.clone())
+                .collect();
             let sale = ((
                 sale,
                 customer.clone(),
-                products_sale.iter().map(|(p, q)| (p, *q)).collect(),
+                lines,
+                sale_returns,
+                sale_discounts,
+                sale_payments,
             ))
                 .into();
             result_sales.push(sale);
@@ -349,9 +1021,10 @@ impl SaleRepository for MySqlSaleRepository {
                 return PaginationResult::from((pagination, 0));
             }
         };
-        let sales = match sqlx::query_as::<_, SaleModel>(
-            "SELECT * FROM sales ORDER BY generated_at DESC LIMIT ? OFFSET ?",
-        )
+        let sales = match sqlx::query_as::<_, SaleModel>(&format!(
+            "{} ORDER BY s.generated_at DESC LIMIT ? OFFSET ?",
+            SELECT_SALES
+        ))
         .bind(pagination.per_page as i64)
         .bind(offset as i64)
         .fetch_all(self.pool.as_ref())
@@ -411,7 +1084,49 @@ impl SaleRepository for MySqlSaleRepository {
             .customer_repository
             .get_many_by_cc(customers_cc.iter().map(|c| c).collect())
             .await;
-        let products = self.product_repository.get_many_by_skus(products_sku).await;
+        let products = match self.product_repository.get_many_by_skus(products_sku).await {
+            Ok(products) => products,
+            Err(_) => return PaginationResult::from((pagination, 0)),
+        };
+        let mut conn = match self.pool.acquire().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                self.logger
+                    .error(format!("Failed to fetch sales: {}", e).as_str());
+                return PaginationResult::from((pagination, 0));
+            }
+        };
+        let returns = match self
+            .get_returns(
+                &mut conn,
+                &sales.iter().map(|sale| sale.id).collect::<Vec<u32>>(),
+            )
+            .await
+        {
+            Some(returns) => returns,
+            None => return PaginationResult::from((pagination, 0)),
+        };
+        let discounts = match self
+            .get_discounts(
+                &mut conn,
+                &sales.iter().map(|sale| sale.id).collect::<Vec<u32>>(),
+            )
+            .await
+        {
+            Some(discounts) => discounts,
+            None => return PaginationResult::from((pagination, 0)),
+        };
+        let payments = match self
+            .get_payments(
+                &mut conn,
+                &sales.iter().map(|sale| sale.id).collect::<Vec<u32>>(),
+            )
+            .await
+        {
+            Some(payments) => payments,
+            None => return PaginationResult::from((pagination, 0)),
+        };
+        drop(conn);
         let mut result_sales: Vec<Sale> = Vec::new();
         for sale in sales {
             let customer = match customers
@@ -430,11 +1145,11 @@ impl SaleRepository for MySqlSaleRepository {
                     continue;
                 }
             };
-            let mut products_sale: Vec<(&Product, u32)> = Vec::new();
+            let mut lines: Vec<SaleLine> = Vec::new();
             for sp in sales_product.iter().filter(|sp| sp.sale_id == sale.id) {
                 match products.iter().find(|p| p.sku().clone() == sp.product_sku) {
                     Some(prod) => {
-                        products_sale.push((prod, sp.quantity));
+                        lines.push(sp.to_line(prod));
                     }
                     None => {
                         self.logger.error(
@@ -448,7 +1163,30 @@ impl SaleRepository for MySqlSaleRepository {
                     }
                 }
             }
-            let sale = ((sale, customer.clone(), products_sale)).into();
+            let sale_returns = returns
+                .iter()
+                .filter(|(sale_id, _)| *sale_id == sale.id)
+                .map(|(_, r)| r.clone())
+                .collect();
+            let sale_discounts = discounts
+                .iter()
+                .filter(|(sale_id, _)| *sale_id == sale.id)
+                .map(|(_, d)| d.clone())
+                .collect();
+            let sale_payments = payments
+                .iter()
+                .filter(|(sale_id, _)| *sale_id == sale.id)
+                .map(|(_, p)| p.clone())
+                .collect();
+            let sale = ((
+                sale,
+                customer.clone(),
+                lines,
+                sale_returns,
+                sale_discounts,
+                sale_payments,
+            ))
+                .into();
             result_sales.push(sale);
         }
         PaginationResult::from((pagination, total_count)).with_data(result_sales)
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use std::sync::Arc;
+
+use crate::{
+    application::services::Logger,
+    domain::{
+        entities::{
+            StockAdjustmentReason, StockMovement, StockMovementParams, StockMovementReason,
+        },
+        repositories::StockMovementRepository,
+    },
+    shared::{Pagination, PaginationResult},
+};
+
+#[derive(FromRow, Debug, Clone)]
+struct StockMovementModel {
+    pub id: u32,
+    pub product_id: u32,
+    pub delta: i32,
+    pub resulting_stock: u32,
+    pub reason: String,
+    pub adjustment_reason: Option<String>,
+    pub reference_id: Option<u32>,
+    pub user: String,
+    pub created_at: DateTime<Utc>,
+}
+impl From<StockMovementModel> for StockMovement {
+    fn from(model: StockMovementModel) -> Self {
+        StockMovement::new(StockMovementParams {
+            id: model.id,
+            product_id: model.product_id,
+            delta: model.delta as i64,
+            resulting_stock: model.resulting_stock,
+            reason: StockMovementReason::parse(&model.reason)
+                .unwrap_or(StockMovementReason::Adjustment),
+            adjustment_reason: model
+                .adjustment_reason
+                .and_then(|reason| StockAdjustmentReason::parse(&reason).ok()),
+            reference_id: model.reference_id,
+            user: model.user,
+            created_at: model.created_at,
+        })
+    }
+}
+
+pub struct MySqlStockMovementRepository {
+    pub pool: Arc<sqlx::MySqlPool>,
+    pub logger: Arc<dyn Logger + Send + Sync>,
+}
+
+#[async_trait::async_trait]
+impl StockMovementRepository for MySqlStockMovementRepository {
+    async fn get_paginated_by_product(
+        &self,
+        product_id: u32,
+        pagination: &Pagination,
+    ) -> PaginationResult<StockMovement> {
+        let total_count_result = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM stock_movements WHERE product_id = ?",
+        )
+        .bind(product_id)
+        .fetch_one(self.pool.as_ref())
+        .await;
+        let total_count = match total_count_result {
+            Ok(count) => count as u32,
+            Err(e) => {
+                self.logger.error(&format!(
+                    "Error counting stock movements of product id {}: {}",
+                    product_id, e
+                ));
+                return PaginationResult::from((pagination, 0));
+            }
+        };
+        if total_count == 0 {
+            return PaginationResult::from((pagination, 0));
+        }
+        let offset = pagination.per_page * (pagination.page - 1);
+        let items_result = sqlx::query_as::<_, StockMovementModel>(
+            "SELECT * FROM stock_movements WHERE product_id = ? ORDER BY id DESC LIMIT ? OFFSET ?",
+        )
+        .bind(product_id)
+        .bind(pagination.per_page as i64)
+        .bind(offset as i64)
+        .fetch_all(self.pool.as_ref())
+        .await;
+        match items_result {
+            Ok(models) => PaginationResult::from((pagination, total_count))
+                .with_data(models.into_iter().map(|model| model.into()).collect()),
+            Err(e) => {
+                self.logger.error(&format!(
+                    "Error fetching stock movements of product id {}: {}",
+                    product_id, e
+                ));
+                PaginationResult::from((pagination, 0))
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use sqlx::{MySql, MySqlConnection, mysql::MySqlDatabaseError};
+use std::{any::Any, sync::Arc};
+
+use crate::{
+    application::services::Logger,
+    domain::unit_of_work::{Transaction, UnitOfWork},
+    shared::SharedError,
+};
+
+/// MySQL error numbers of failures solved by retrying the transaction
+const ER_LOCK_WAIT_TIMEOUT: u16 = 1205;
+const ER_LOCK_DEADLOCK: u16 = 1213;
+
+pub struct MySqlTransaction {
+    inner: sqlx::Transaction<'static, MySql>,
+    retryable: bool,
+    logger: Arc<dyn Logger + Send + Sync>,
+}
+impl MySqlTransaction {
+    /// Get the MySQL transaction behind a domain transaction
+    pub fn from_dyn(tx: &mut dyn Transaction) -> Result<&mut MySqlTransaction, SharedError> {
+        tx.as_any()
+            .downcast_mut::<MySqlTransaction>()
+            .ok_or(SharedError::new("Unsupported transaction", 500))
+    }
+
+    pub fn connection(&mut self) -> &mut MySqlConnection {
+        &mut self.inner
+    }
+
+    /// Inspect the result of a query run in the transaction, flagging deadlocks as retryable
+    pub fn check<T>(&mut self, result: Result<T, sqlx::Error>) -> Result<T, sqlx::Error> {
+        if let Err(sqlx::Error::Database(db_err)) = &result
+            && let Some(mysql_err) = db_err.try_downcast_ref::<MySqlDatabaseError>()
+            && [ER_LOCK_DEADLOCK, ER_LOCK_WAIT_TIMEOUT].contains(&mysql_err.number())
+        {
+            self.retryable = true;
+        }
+        result
+    }
+}
+
+#[async_trait::async_trait]
+impl Transaction for MySqlTransaction {
+    fn as_any(&mut self) -> &mut (dyn Any + Send) {
+        self
+    }
+    fn is_retryable(&self) -> bool {
+        self.retryable
+    }
+    async fn commit(self: Box<Self>) -> Result<(), SharedError> {
+        self.inner
+            .commit()
+            .await
+            .map_err(|e| SharedError::new(&format!("Failed to commit transaction: {}", e), 500))
+    }
+    async fn rollback(self: Box<Self>) -> Result<(), SharedError> {
+        self.inner.rollback().await.map_err(|e| {
+            self.logger
+                .error(format!("Failed to rollback transaction: {}", e).as_str());
+            SharedError::new("Failed to rollback transaction", 500)
+        })
+    }
+}
+
+pub struct MySqlUnitOfWork {
+    pub pool: Arc<sqlx::MySqlPool>,
+    pub logger: Arc<dyn Logger + Send + Sync>,
+}
+
+#[async_trait::async_trait]
+impl UnitOfWork for MySqlUnitOfWork {
+    async fn begin(&self) -> Result<Box<dyn Transaction>, SharedError> {
+        match self.pool.begin().await {
+            Ok(inner) => Ok(Box::new(MySqlTransaction {
+                inner,
+                retryable: false,
+                logger: self.logger.clone(),
+            })),
+            Err(e) => {
+                self.logger
+                    .error(format!("Failed to begin transaction: {}", e).as_str());
+                Err(SharedError::new("Failed to begin transaction", 500))
+            }
+        }
+    }
+}
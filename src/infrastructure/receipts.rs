@@ -0,0 +1,264 @@
+use crate::{
+    application::services::{ReceiptFormat, ReceiptRenderer},
+    domain::{entities::Sale, value_objects::ValueObject},
+    shared::SharedError,
+};
+
+/// Store data printed at the top of every receipt
+pub struct StoreSettings {
+    pub name: String,
+    pub nit: String,
+    pub address: String,
+    pub phone: String,
+}
+
+/// One line of a receipt, independent of the output format
+enum ReceiptRow {
+    Title(String),
+    Centered(String),
+    Text(String),
+    /// Label on the left, value on the right
+    Pair(String, String),
+    /// Pair printed in bold
+    Total(String, String),
+    Separator,
+}
+
+pub struct ReceiptPrinter {
+    pub settings: StoreSettings,
+    /// Characters per line of the text and ESC/POS receipts
+    pub width: usize,
+}
+impl ReceiptPrinter {
+    fn rows(&self, sale: &Sale, gift: bool) -> Result<Vec<ReceiptRow>, SharedError> {
+        let mut rows = vec![ReceiptRow::Title(self.settings.name.clone())];
+        if !self.settings.nit.is_empty() {
+            rows.push(ReceiptRow::Centered(format!("NIT {}", self.settings.nit)));
+        }
+        for info in [&self.settings.address, &self.settings.phone] {
+            if !info.is_empty() {
+                rows.push(ReceiptRow::Centered(info.clone()));
+            }
+        }
+        rows.push(ReceiptRow::Separator);
+        if gift {
+            rows.push(ReceiptRow::Title("GIFT RECEIPT".to_string()));
+        }
+        rows.push(match sale.invoice_number() {
+            Some(invoice_number) => {
+                ReceiptRow::Pair("Invoice".to_string(), invoice_number.to_string())
+            }
+            None => ReceiptRow::Pair("Sale".to_string(), format!("#{}", sale.id())),
+        });
+        rows.push(ReceiptRow::Pair(
+            "Date".to_string(),
+            sale.generated_at().format("%Y-%m-%d %H:%M").to_string(),
+        ));
+        rows.push(ReceiptRow::Pair(
+            "Customer".to_string(),
+            sale.customer().name().clone(),
+        ));
+        rows.push(ReceiptRow::Pair(
+            "CC".to_string(),
+            sale.customer().cc().value().clone(),
+        ));
+        if sale.is_cancelled() {
+            rows.push(ReceiptRow::Title("CANCELLED".to_string()));
+        }
+        rows.push(ReceiptRow::Separator);
+        for line in sale.lines() {
+            if gift {
+                rows.push(ReceiptRow::Pair(
+                    line.product_name().clone(),
+                    format!("x{}", line.quantity()),
+                ));
+            } else {
+                rows.push(ReceiptRow::Text(line.product_name().clone()));
+                rows.push(ReceiptRow::Pair(
+                    format!("  {} x {}", line.quantity(), line.unit_price()),
+                    line.amount()?.to_string(),
+                ));
+            }
+        }
+        rows.push(ReceiptRow::Separator);
+        if gift {
+            rows.push(ReceiptRow::Centered(
+                "Valid for exchange at the store".to_string(),
+            ));
+            return Ok(rows);
+        }
+        rows.push(ReceiptRow::Pair(
+            "Subtotal".to_string(),
+            sale.subtotal()?.to_string(),
+        ));
+        for discount in sale.discounts() {
+            rows.push(ReceiptRow::Pair(
+                discount.description().clone(),
+                discount.amount().checked_neg()?.to_string(),
+            ));
+        }
+        rows.push(ReceiptRow::Total(
+            "TOTAL".to_string(),
+            sale.total_amount()?.to_string(),
+        ));
+        for tax in sale.tax_breakdown()? {
+            rows.push(ReceiptRow::Pair(
+                format!("IVA {}% on {}", tax.tax_rate(), tax.base()),
+                tax.tax().to_string(),
+            ));
+        }
+        rows.push(ReceiptRow::Separator);
+        for payment in sale.payments() {
+            rows.push(ReceiptRow::Pair(
+                payment.method().as_str().to_string(),
+                payment.amount().to_string(),
+            ));
+        }
+        let change = sale.change()?;
+        if change.is_positive() {
+            rows.push(ReceiptRow::Pair("Change".to_string(), change.to_string()));
+        }
+        rows.push(ReceiptRow::Separator);
+        rows.push(ReceiptRow::Centered(
+            "Thank you for your purchase".to_string(),
+        ));
+        Ok(rows)
+    }
+
+    /// Label and value on the same line, cutting the label when both do not fit
+    fn pair_line(&self, label: &str, value: &str) -> String {
+        let value_len = value.chars().count();
+        let label_width = self.width.saturating_sub(value_len + 1);
+        let label: String = label.chars().take(label_width).collect();
+        let padding = self
+            .width
+            .saturating_sub(label.chars().count() + value_len)
+            .max(1);
+        format!("{}{}{}", label, " ".repeat(padding), value)
+    }
+    fn centered_line(&self, text: &str) -> String {
+        let text: String = text.chars().take(self.width).collect();
+        let padding = (self.width - text.chars().count()) / 2;
+        format!("{}{}", " ".repeat(padding), text)
+    }
+
+    fn render_text(&self, rows: &[ReceiptRow]) -> String {
+        let mut text = String::new();
+        for row in rows {
+            let line = match row {
+                ReceiptRow::Title(title) => self.centered_line(&title.to_uppercase()),
+                ReceiptRow::Centered(value) => self.centered_line(value),
+                ReceiptRow::Text(value) => value.chars().take(self.width).collect(),
+                ReceiptRow::Pair(label, value) | ReceiptRow::Total(label, value) => {
+                    self.pair_line(label, value)
+                }
+                ReceiptRow::Separator => "-".repeat(self.width),
+            };
+            text.push_str(&line);
+            text.push('\n');
+        }
+        text
+    }
+
+    fn render_escpos(&self, rows: &[ReceiptRow]) -> Vec<u8> {
+        const ESC: u8 = 0x1B;
+        const GS: u8 = 0x1D;
+        // Initialize and select the PC850 code page for Spanish characters
+        let mut bytes = vec![ESC, b'@', ESC, b't', 2];
+        for row in rows {
+            let (centered, bold, line) = match row {
+                ReceiptRow::Title(title) => (true, true, title.to_uppercase()),
+                ReceiptRow::Centered(value) => (true, false, value.clone()),
+                ReceiptRow::Text(value) => (false, false, value.chars().take(self.width).collect()),
+                ReceiptRow::Pair(label, value) => (false, false, self.pair_line(label, value)),
+                ReceiptRow::Total(label, value) => (false, true, self.pair_line(label, value)),
+                ReceiptRow::Separator => (false, false, "-".repeat(self.width)),
+            };
+            bytes.extend([ESC, b'a', centered as u8, ESC, b'E', bold as u8]);
+            bytes.extend(line.chars().map(pc850_byte));
+            bytes.push(b'\n');
+        }
+        // Back to defaults, feed the paper past the cutter and cut it
+        bytes.extend([ESC, b'a', 0, ESC, b'E', 0, GS, b'V', 66, 3]);
+        bytes
+    }
+
+    fn render_html(&self, rows: &[ReceiptRow]) -> String {
+        let mut body = String::new();
+        for row in rows {
+            let row_html = match row {
+                ReceiptRow::Title(title) => format!("<h1>{}</h1>", escape_html(title)),
+                ReceiptRow::Centered(value) => {
+                    format!("<p class=\"center\">{}</p>", escape_html(value))
+                }
+                ReceiptRow::Text(value) => format!("<p>{}</p>", escape_html(value)),
+                ReceiptRow::Pair(label, value) => format!(
+                    "<p class=\"pair\"><span>{}</span><span>{}</span></p>",
+                    escape_html(label),
+                    escape_html(value)
+                ),
+                ReceiptRow::Total(label, value) => format!(
+                    "<p class=\"pair total\"><span>{}</span><span>{}</span></p>",
+                    escape_html(label),
+                    escape_html(value)
+                ),
+                ReceiptRow::Separator => "<hr>".to_string(),
+            };
+            body.push_str(&row_html);
+            body.push('\n');
+        }
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\nbody {{ font-family: monospace; max-width: {}ch; margin: 0 auto; }}\nh1 {{ font-size: 1.2em; text-align: center; margin: 0.3em 0; }}\np {{ margin: 0.1em 0; }}\n.center {{ text-align: center; }}\n.pair {{ display: flex; justify-content: space-between; gap: 1ch; }}\n.total {{ font-weight: bold; }}\nhr {{ border: none; border-top: 1px dashed; }}\n</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+            escape_html(&self.settings.name),
+            self.width,
+            body
+        )
+    }
+}
+impl ReceiptRenderer for ReceiptPrinter {
+    fn render(
+        &self,
+        sale: &Sale,
+        format: ReceiptFormat,
+        gift: bool,
+    ) -> Result<Vec<u8>, SharedError> {
+        let rows = self.rows(sale, gift)?;
+        Ok(match format {
+            ReceiptFormat::EscPos => self.render_escpos(&rows),
+            ReceiptFormat::Text => self.render_text(&rows).into_bytes(),
+            ReceiptFormat::Html => self.render_html(&rows).into_bytes(),
+        })
+    }
+}
+
+/// Byte of the character in the PC850 code page, "?" for the ones it lacks
+fn pc850_byte(c: char) -> u8 {
+    match c {
+        ' '..='~' => c as u8,
+        'á' => 0xA0,
+        'é' => 0x82,
+        'í' => 0xA1,
+        'ó' => 0xA2,
+        'ú' => 0xA3,
+        'ñ' => 0xA4,
+        'Ñ' => 0xA5,
+        'ü' => 0x81,
+        'Ü' => 0x9A,
+        'Á' => 0xB5,
+        'É' => 0x90,
+        'Í' => 0xD6,
+        'Ó' => 0xE0,
+        'Ú' => 0xE9,
+        '¿' => 0xA8,
+        '¡' => 0xAD,
+        _ => b'?',
+    }
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -42,11 +42,11 @@ impl CredentialsValidator for CredentialsValidatorOneUser {
             Err(crate::shared::SharedError::new("INVALID CREDENTIALS", 401))
         }
     }
-    fn has_access(&self, api_key: &String) -> Result<(), crate::shared::SharedError> {
+    fn has_access(&self, api_key: &String) -> Result<String, crate::shared::SharedError> {
         if &self.config.key != api_key {
             Err(crate::shared::SharedError::new("NOT AUTHORIZED", 401))
         } else {
-            Ok(())
+            Ok(self.config.user.clone())
         }
     }
 }
@@ -2,6 +2,8 @@ use actix_web::{HttpResponse, http::StatusCode};
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::domain::value_objects::Money;
+
 #[derive(Debug, Serialize)]
 struct GenericError {
     message: String,
@@ -16,6 +18,43 @@ impl From<crate::shared::SharedError> for HttpResponse {
     }
 }
 
+/// Money amount as it goes over JSON, a decimal string such as "19900.50"
+#[derive(Debug, Clone, Copy)]
+pub struct MoneyAmount(pub Money);
+impl From<Money> for MoneyAmount {
+    fn from(money: Money) -> Self {
+        MoneyAmount(money)
+    }
+}
+impl From<MoneyAmount> for Money {
+    fn from(amount: MoneyAmount) -> Self {
+        amount.0
+    }
+}
+impl Serialize for MoneyAmount {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0.to_string())
+    }
+}
+/// Amounts are read from a decimal string or a JSON number
+impl<'de> Deserialize<'de> for MoneyAmount {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Amount {
+            Text(String),
+            Number(serde_json::Number),
+        }
+        let amount = match Amount::deserialize(deserializer)? {
+            Amount::Text(text) => text,
+            Amount::Number(number) => number.to_string(),
+        };
+        Money::parse(&amount)
+            .map(MoneyAmount)
+            .map_err(|e| serde::de::Error::custom(e.message))
+    }
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct Pagination {
     pub page: u32,
@@ -76,16 +115,42 @@ impl From<crate::application::dtos::CustomerDTO> for CustomerResponse {
     }
 }
 
+#[derive(Serialize, Debug, Clone)]
+pub struct CategoryResponse {
+    pub id: u32,
+    pub name: String,
+    pub parent_id: Option<u32>,
+}
+impl From<crate::application::dtos::CategoryDTO> for CategoryResponse {
+    fn from(value: crate::application::dtos::CategoryDTO) -> Self {
+        CategoryResponse {
+            id: value.id,
+            name: value.name,
+            parent_id: value.parent_id,
+        }
+    }
+}
+
 #[derive(Serialize, Debug, Clone)]
 pub struct ProductResponse {
     pub id: u32,
     pub sku: String,
     pub name: String,
-    pub price: f64,
+    pub price: MoneyAmount,
+    pub tax_class: String,
+    pub tax_rate: f64,
     pub stock: u32,
-    pub flags: Vec<String>,
+    pub reorder_point: Option<u32>,
+    pub reorder_quantity: Option<u32>,
+    pub parent_id: Option<u32>,
+    pub size: Option<String>,
+    pub color: Option<String>,
+    pub price_override: bool,
+    pub has_variants: bool,
+    pub categories: Vec<CategoryResponse>,
     pub img_url: Option<String>,
     pub description: Option<String>,
+    pub archived_at: Option<String>,
 }
 impl From<crate::application::dtos::ProductDTO> for ProductResponse {
     fn from(value: crate::application::dtos::ProductDTO) -> Self {
@@ -93,11 +158,67 @@ impl From<crate::application::dtos::ProductDTO> for ProductResponse {
             id: value.id,
             sku: value.sku,
             name: value.name,
-            price: value.price,
+            price: value.price.into(),
+            tax_class: value.tax_class,
+            tax_rate: value.tax_rate,
             stock: value.stock,
-            flags: value.flags,
+            reorder_point: value.reorder_point,
+            reorder_quantity: value.reorder_quantity,
+            parent_id: value.parent_id,
+            size: value.size,
+            color: value.color,
+            price_override: value.price_override,
+            has_variants: value.has_variants,
+            categories: value
+                .categories
+                .into_iter()
+                .map(CategoryResponse::from)
+                .collect(),
             img_url: value.img_url,
             description: value.description,
+            archived_at: value.archived_at,
+        }
+    }
+}
+
+/// Just what the POS shows while searching a product
+#[derive(Serialize, Debug, Clone)]
+pub struct ProductSuggestionResponse {
+    pub id: u32,
+    pub sku: String,
+    pub name: String,
+    pub price: MoneyAmount,
+    pub stock: u32,
+}
+impl From<crate::application::dtos::ProductDTO> for ProductSuggestionResponse {
+    fn from(value: crate::application::dtos::ProductDTO) -> Self {
+        ProductSuggestionResponse {
+            id: value.id,
+            sku: value.sku,
+            name: value.name,
+            price: value.price.into(),
+            stock: value.stock,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ProductDetailResponse {
+    #[serde(flatten)]
+    pub product: ProductResponse,
+    pub variants: Vec<ProductResponse>,
+    pub total_stock: u32,
+}
+impl From<crate::application::dtos::ProductDetailDTO> for ProductDetailResponse {
+    fn from(value: crate::application::dtos::ProductDetailDTO) -> Self {
+        ProductDetailResponse {
+            product: ProductResponse::from(value.product),
+            variants: value
+                .variants
+                .into_iter()
+                .map(ProductResponse::from)
+                .collect(),
+            total_stock: value.total_stock,
         }
     }
 }
@@ -106,31 +227,433 @@ impl From<crate::application::dtos::ProductDTO> for ProductResponse {
 pub struct ProductSaleResponse {
     pub product: ProductResponse,
     pub quantity: u32,
+    /// Name and unit price the product was sold with
+    pub product_name: String,
+    pub unit_price: MoneyAmount,
+    pub amount: MoneyAmount,
+    pub tax_class: String,
+    pub tax_rate: f64,
+    pub base_amount: MoneyAmount,
+    pub tax_amount: MoneyAmount,
+}
+impl From<crate::application::dtos::SaleLineDTO> for ProductSaleResponse {
+    fn from(line: crate::application::dtos::SaleLineDTO) -> Self {
+        ProductSaleResponse {
+            product: ProductResponse::from(line.product),
+            quantity: line.quantity,
+            product_name: line.product_name,
+            unit_price: line.unit_price.into(),
+            amount: line.amount.into(),
+            tax_class: line.tax_class,
+            tax_rate: line.tax_rate,
+            base_amount: line.base_amount.into(),
+            tax_amount: line.tax_amount.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TaxBreakdownResponse {
+    pub tax_class: String,
+    pub tax_rate: f64,
+    pub base_amount: MoneyAmount,
+    pub tax_amount: MoneyAmount,
+}
+impl From<crate::application::dtos::TaxBreakdownDTO> for TaxBreakdownResponse {
+    fn from(t: crate::application::dtos::TaxBreakdownDTO) -> Self {
+        TaxBreakdownResponse {
+            tax_class: t.tax_class,
+            tax_rate: t.tax_rate,
+            base_amount: t.base_amount.into(),
+            tax_amount: t.tax_amount.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ReturnLineResponse {
+    pub product_sku: String,
+    pub quantity: u32,
+    pub unit_price: MoneyAmount,
+    pub amount: MoneyAmount,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SaleReturnResponse {
+    pub id: u32,
+    pub products: Vec<ReturnLineResponse>,
+    pub reason: Option<String>,
+    pub refunded_amount: MoneyAmount,
+    pub created_at: String,
+}
+impl From<crate::application::dtos::SaleReturnDTO> for SaleReturnResponse {
+    fn from(value: crate::application::dtos::SaleReturnDTO) -> Self {
+        SaleReturnResponse {
+            id: value.id,
+            products: value
+                .products
+                .into_iter()
+                .map(|line| ReturnLineResponse {
+                    product_sku: line.product_sku,
+                    quantity: line.quantity,
+                    unit_price: line.unit_price.into(),
+                    amount: line.amount.into(),
+                })
+                .collect(),
+            reason: value.reason,
+            refunded_amount: value.refunded_amount.into(),
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AppliedDiscountResponse {
+    pub promotion_id: Option<u32>,
+    /// SKU of the discounted line, None for sale discounts
+    pub product_sku: Option<String>,
+    pub description: String,
+    pub amount: MoneyAmount,
+}
+impl From<crate::application::dtos::AppliedDiscountDTO> for AppliedDiscountResponse {
+    fn from(d: crate::application::dtos::AppliedDiscountDTO) -> Self {
+        AppliedDiscountResponse {
+            promotion_id: d.promotion_id,
+            product_sku: d.product_sku,
+            description: d.description,
+            amount: d.amount.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PaymentResponse {
+    pub method: String,
+    pub amount: MoneyAmount,
+    pub tendered: Option<MoneyAmount>,
+    pub change: MoneyAmount,
+}
+impl From<crate::application::dtos::PaymentDTO> for PaymentResponse {
+    fn from(p: crate::application::dtos::PaymentDTO) -> Self {
+        PaymentResponse {
+            method: p.method,
+            amount: p.amount.into(),
+            tendered: p.tendered.map(Into::into),
+            change: p.change.into(),
+        }
+    }
 }
 
 #[derive(Serialize, Debug, Clone)]
 pub struct SaleResponse {
     pub id: u32,
+    pub invoice_number: Option<String>,
     pub customer: CustomerResponse,
     pub products: Vec<ProductSaleResponse>,
-    pub total_amount: f64,
+    pub subtotal: MoneyAmount,
+    pub discount_amount: MoneyAmount,
+    pub discounts: Vec<AppliedDiscountResponse>,
+    pub total_amount: MoneyAmount,
+    /// Total amount split into its base and IVA
+    pub base_amount: MoneyAmount,
+    pub tax_amount: MoneyAmount,
+    pub taxes: Vec<TaxBreakdownResponse>,
+    pub payments: Vec<PaymentResponse>,
+    pub change: MoneyAmount,
+    pub refunded_amount: MoneyAmount,
+    pub net_amount: MoneyAmount,
+    pub returns: Vec<SaleReturnResponse>,
+    pub exchange_of: Option<u32>,
     pub generated_at: String,
+    pub status: String,
+    pub cancel_reason: Option<String>,
+    pub cancelled_at: Option<String>,
 }
 impl From<crate::application::dtos::SaleDTO> for SaleResponse {
     fn from(value: crate::application::dtos::SaleDTO) -> Self {
         SaleResponse {
             id: value.id,
+            invoice_number: value.invoice_number,
             customer: CustomerResponse::from(value.customer),
             products: value
                 .products
                 .into_iter()
-                .map(|(prod, qty)| ProductSaleResponse {
-                    product: ProductResponse::from(prod),
-                    quantity: qty,
-                })
+                .map(ProductSaleResponse::from)
                 .collect(),
-            total_amount: value.total_amount,
+            subtotal: value.subtotal.into(),
+            discount_amount: value.discount_amount.into(),
+            discounts: value
+                .discounts
+                .into_iter()
+                .map(AppliedDiscountResponse::from)
+                .collect(),
+            total_amount: value.total_amount.into(),
+            base_amount: value.base_amount.into(),
+            tax_amount: value.tax_amount.into(),
+            taxes: value
+                .taxes
+                .into_iter()
+                .map(TaxBreakdownResponse::from)
+                .collect(),
+            payments: value
+                .payments
+                .into_iter()
+                .map(PaymentResponse::from)
+                .collect(),
+            change: value.change.into(),
+            refunded_amount: value.refunded_amount.into(),
+            net_amount: value.net_amount.into(),
+            returns: value
+                .returns
+                .into_iter()
+                .map(SaleReturnResponse::from)
+                .collect(),
+            exchange_of: value.exchange_of,
             generated_at: value.generated_at,
+            status: value.status,
+            cancel_reason: value.cancel_reason,
+            cancelled_at: value.cancelled_at,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SalePricingResponse {
+    pub customer: CustomerResponse,
+    pub products: Vec<ProductSaleResponse>,
+    pub subtotal: MoneyAmount,
+    pub discount_amount: MoneyAmount,
+    pub discounts: Vec<AppliedDiscountResponse>,
+    pub total_amount: MoneyAmount,
+    pub base_amount: MoneyAmount,
+    pub tax_amount: MoneyAmount,
+    pub taxes: Vec<TaxBreakdownResponse>,
+    pub payments: Vec<PaymentResponse>,
+    pub change: MoneyAmount,
+}
+impl From<crate::application::dtos::SalePricingDTO> for SalePricingResponse {
+    fn from(value: crate::application::dtos::SalePricingDTO) -> Self {
+        SalePricingResponse {
+            customer: CustomerResponse::from(value.customer),
+            products: value
+                .products
+                .into_iter()
+                .map(ProductSaleResponse::from)
+                .collect(),
+            subtotal: value.subtotal.into(),
+            discount_amount: value.discount_amount.into(),
+            discounts: value
+                .discounts
+                .into_iter()
+                .map(AppliedDiscountResponse::from)
+                .collect(),
+            total_amount: value.total_amount.into(),
+            base_amount: value.base_amount.into(),
+            tax_amount: value.tax_amount.into(),
+            taxes: value
+                .taxes
+                .into_iter()
+                .map(TaxBreakdownResponse::from)
+                .collect(),
+            payments: value
+                .payments
+                .into_iter()
+                .map(PaymentResponse::from)
+                .collect(),
+            change: value.change.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SalePreviewResponse {
+    /// Whether the sale can be registered as it is
+    pub valid: bool,
+    pub problems: Vec<String>,
+    pub pricing: Option<SalePricingResponse>,
+}
+impl From<crate::application::dtos::SalePreviewDTO> for SalePreviewResponse {
+    fn from(value: crate::application::dtos::SalePreviewDTO) -> Self {
+        SalePreviewResponse {
+            valid: value.problems.is_empty(),
+            problems: value.problems,
+            pricing: value.pricing.map(SalePricingResponse::from),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DraftLineResponse {
+    pub product_sku: String,
+    pub product_name: String,
+    pub unit_price: MoneyAmount,
+    pub quantity: u32,
+    pub amount: MoneyAmount,
+    pub stock: u32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DraftSaleResponse {
+    pub id: u32,
+    pub customer: CustomerResponse,
+    pub status: String,
+    pub lines: Vec<DraftLineResponse>,
+    pub subtotal: MoneyAmount,
+    pub discount_amount: MoneyAmount,
+    pub discounts: Vec<AppliedDiscountResponse>,
+    pub total_amount: MoneyAmount,
+    pub sale_id: Option<u32>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+impl From<crate::application::dtos::DraftSaleDTO> for DraftSaleResponse {
+    fn from(value: crate::application::dtos::DraftSaleDTO) -> Self {
+        DraftSaleResponse {
+            id: value.id,
+            customer: CustomerResponse::from(value.customer),
+            status: value.status,
+            lines: value
+                .lines
+                .into_iter()
+                .map(|line| DraftLineResponse {
+                    product_sku: line.product_sku,
+                    product_name: line.product_name,
+                    unit_price: line.unit_price.into(),
+                    quantity: line.quantity,
+                    amount: line.amount.into(),
+                    stock: line.stock,
+                })
+                .collect(),
+            subtotal: value.subtotal.into(),
+            discount_amount: value.discount_amount.into(),
+            discounts: value
+                .discounts
+                .into_iter()
+                .map(AppliedDiscountResponse::from)
+                .collect(),
+            total_amount: value.total_amount.into(),
+            sale_id: value.sale_id,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PromotionResponse {
+    pub id: u32,
+    pub name: String,
+    pub code: Option<String>,
+    pub scope: String,
+    pub kind: String,
+    pub percentage: Option<f64>,
+    pub amount: Option<MoneyAmount>,
+    pub buy_quantity: Option<u32>,
+    pub pay_quantity: Option<u32>,
+    pub category_id: Option<u32>,
+    pub starts_at: Option<String>,
+    pub ends_at: Option<String>,
+    pub active: bool,
+    pub created_at: String,
+}
+impl From<crate::application::dtos::PromotionDTO> for PromotionResponse {
+    fn from(value: crate::application::dtos::PromotionDTO) -> Self {
+        PromotionResponse {
+            id: value.id,
+            name: value.name,
+            code: value.code,
+            scope: value.scope,
+            kind: value.kind,
+            percentage: value.percentage,
+            amount: value.amount.map(Into::into),
+            buy_quantity: value.buy_quantity,
+            pay_quantity: value.pay_quantity,
+            category_id: value.category_id,
+            starts_at: value.starts_at,
+            ends_at: value.ends_at,
+            active: value.active,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct InvoiceSeriesResponse {
+    pub id: u32,
+    pub prefix: String,
+    pub range_from: u64,
+    pub range_to: u64,
+    pub next_number: u64,
+    pub remaining: u64,
+    pub warning_threshold: u64,
+    /// Whether the series is about to run out of authorized numbers
+    pub near_end: bool,
+    pub active: bool,
+    pub created_at: String,
+}
+impl From<crate::application::dtos::InvoiceSeriesDTO> for InvoiceSeriesResponse {
+    fn from(value: crate::application::dtos::InvoiceSeriesDTO) -> Self {
+        InvoiceSeriesResponse {
+            id: value.id,
+            prefix: value.prefix,
+            range_from: value.range_from,
+            range_to: value.range_to,
+            next_number: value.next_number,
+            remaining: value.remaining,
+            warning_threshold: value.warning_threshold,
+            near_end: value.near_end,
+            active: value.active,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ElectronicInvoiceResponse {
+    pub sale_id: u32,
+    pub invoice_number: String,
+    pub cufe: String,
+    pub status: String,
+    pub created_at: String,
+    pub submitted_at: Option<String>,
+}
+impl From<crate::application::dtos::ElectronicInvoiceDTO> for ElectronicInvoiceResponse {
+    fn from(value: crate::application::dtos::ElectronicInvoiceDTO) -> Self {
+        ElectronicInvoiceResponse {
+            sale_id: value.sale_id,
+            invoice_number: value.invoice_number,
+            cufe: value.cufe,
+            status: value.status,
+            created_at: value.created_at,
+            submitted_at: value.submitted_at,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct StockMovementResponse {
+    pub id: u32,
+    pub product_id: u32,
+    pub delta: i64,
+    pub resulting_stock: u32,
+    pub reason: String,
+    pub adjustment_reason: Option<String>,
+    pub reference_id: Option<u32>,
+    pub user: String,
+    pub created_at: String,
+}
+impl From<crate::application::dtos::StockMovementDTO> for StockMovementResponse {
+    fn from(value: crate::application::dtos::StockMovementDTO) -> Self {
+        StockMovementResponse {
+            id: value.id,
+            product_id: value.product_id,
+            delta: value.delta,
+            resulting_stock: value.resulting_stock,
+            reason: value.reason,
+            adjustment_reason: value.adjustment_reason,
+            reference_id: value.reference_id,
+            user: value.user,
+            created_at: value.created_at,
         }
     }
 }
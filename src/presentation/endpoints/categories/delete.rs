@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+
+use crate::{
+    application::commands::DeleteCategoryCommand, infrastructure::Mediator,
+    shared::input_handler::Sender,
+};
+
+#[route("/{id}", method = "DELETE")]
+pub async fn delete_category(
+    id: web::Path<u32>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let command = DeleteCategoryCommand {
+        category_id: id.into_inner(),
+    };
+    let result = mediator.send(command).await;
+    match result {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(err) => HttpResponse::from(err),
+    }
+}
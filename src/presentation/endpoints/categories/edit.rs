@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+use serde::Deserialize;
+
+use crate::{
+    application::commands::EditCategoryCommand, infrastructure::Mediator,
+    shared::input_handler::Sender,
+};
+
+#[derive(Debug, Deserialize)]
+struct EditCategoryRequest {
+    name: String,
+    parent_id: Option<u32>,
+}
+
+#[route("/{id}", method = "PUT")]
+pub async fn edit_category(
+    id: web::Path<u32>,
+    data: web::Json<EditCategoryRequest>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let command = EditCategoryCommand {
+        category_id: id.into_inner(),
+        name: data.name.clone(),
+        parent_id: data.parent_id,
+    };
+    let result = mediator.send(command).await;
+    match result {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(err) => HttpResponse::from(err),
+    }
+}
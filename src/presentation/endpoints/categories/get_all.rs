@@ -0,0 +1,19 @@
+use actix_web::{HttpResponse, Responder, route, web};
+use std::sync::Arc;
+
+use crate::{
+    application::queries::GetCategoriesQuery, infrastructure::Mediator,
+    presentation::common::CategoryResponse, shared::input_handler::Sender,
+};
+
+#[route("", method = "GET")]
+pub async fn get_categories(mediator: web::Data<Arc<Mediator>>) -> impl Responder {
+    let result = mediator.send(GetCategoriesQuery {}).await;
+    match result {
+        Ok(o) => {
+            let data: Vec<CategoryResponse> = o.into_iter().map(CategoryResponse::from).collect();
+            HttpResponse::Ok().json(data)
+        }
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,13 @@
+use actix_web::web::ServiceConfig;
+
+mod delete;
+mod edit;
+mod get_all;
+mod register;
+
+pub fn cfg(cfg: &mut ServiceConfig) {
+    cfg.service(register::register_category);
+    cfg.service(get_all::get_categories);
+    cfg.service(edit::edit_category);
+    cfg.service(delete::delete_category);
+}
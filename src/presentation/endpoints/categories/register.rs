@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::commands::RegisterCategoryCommand, infrastructure::Mediator,
+    shared::input_handler::Sender,
+};
+
+#[derive(Debug, Deserialize)]
+struct RegisterCategoryRequest {
+    name: String,
+    parent_id: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+struct RegisterCategoryResponse {
+    category_id: u32,
+}
+
+#[route("", method = "POST")]
+pub async fn register_category(
+    data: web::Json<RegisterCategoryRequest>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let command = RegisterCategoryCommand {
+        name: data.name.clone(),
+        parent_id: data.parent_id,
+    };
+    let result = mediator.send(command).await;
+    match result {
+        Ok(output) => HttpResponse::Created().json(RegisterCategoryResponse {
+            category_id: output.category_id,
+        }),
+        Err(err) => HttpResponse::from(err),
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+use serde::Deserialize;
+
+use crate::{
+    application::commands::AddDraftItemCommand, infrastructure::Mediator,
+    shared::input_handler::Sender,
+};
+
+#[derive(Debug, Deserialize)]
+struct AddDraftItemRequest {
+    sku: String,
+    quantity: u32,
+}
+
+#[route("/{id}/items", method = "POST")]
+pub async fn add_draft_item(
+    id: web::Path<u32>,
+    data: web::Json<AddDraftItemRequest>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let command = AddDraftItemCommand {
+        draft_sale_id: id.into_inner(),
+        sku: data.sku.clone(),
+        quantity: data.quantity,
+    };
+    let result = mediator.send(command).await;
+    match result {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(err) => HttpResponse::from(err),
+    }
+}
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::commands::{CheckoutDraftSaleCommand, PaymentInput},
+    infrastructure::Mediator,
+    presentation::{common::MoneyAmount, middlewares::AuthenticatedUser},
+    shared::input_handler::Sender,
+};
+
+#[derive(Debug, Deserialize, Clone)]
+struct PaymentRequest {
+    method: String,
+    amount: MoneyAmount,
+    tendered: Option<MoneyAmount>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckoutDraftSaleRequest {
+    #[serde(default)]
+    coupon_codes: Vec<String>,
+    payments: Vec<PaymentRequest>,
+}
+
+#[derive(Debug, Serialize)]
+struct CheckoutDraftSaleResponse {
+    sale_id: u32,
+    invoice_number: String,
+    subtotal: MoneyAmount,
+    discount_amount: MoneyAmount,
+    total_amount: MoneyAmount,
+    change: MoneyAmount,
+}
+
+#[route("/{id}/checkout", method = "POST")]
+pub async fn checkout_draft_sale(
+    id: web::Path<u32>,
+    request: web::Json<CheckoutDraftSaleRequest>,
+    user: web::ReqData<AuthenticatedUser>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let command = CheckoutDraftSaleCommand {
+        draft_sale_id: id.into_inner(),
+        coupon_codes: request.coupon_codes.clone(),
+        payments: request
+            .payments
+            .iter()
+            .map(|p| PaymentInput {
+                method: p.method.clone(),
+                amount: p.amount.into(),
+                tendered: p.tendered.map(Into::into),
+            })
+            .collect(),
+        user: user.into_inner().0,
+    };
+    let result = mediator.send(command).await;
+    match result {
+        Ok(output) => HttpResponse::Created().json(CheckoutDraftSaleResponse {
+            sale_id: output.sale_id,
+            invoice_number: output.invoice_number,
+            subtotal: output.subtotal.into(),
+            discount_amount: output.discount_amount.into(),
+            total_amount: output.total_amount.into(),
+            change: output.change.into(),
+        }),
+        Err(err) => HttpResponse::from(err),
+    }
+}
@@ -0,0 +1,32 @@
+use actix_web::{HttpResponse, Responder, route, web};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::{
+    application::queries::GetDraftSalesQuery, infrastructure::Mediator,
+    presentation::common::DraftSaleResponse, shared::input_handler::Sender,
+};
+
+#[derive(Debug, Deserialize)]
+struct GetDraftSalesParams {
+    status: Option<String>,
+}
+
+#[route("", method = "GET")]
+pub async fn get_draft_sales(
+    params: web::Query<GetDraftSalesParams>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let query = GetDraftSalesQuery {
+        status: params.into_inner().status,
+    };
+    let result = mediator.send(query).await;
+    match result {
+        Ok(drafts) => {
+            let data: Vec<DraftSaleResponse> =
+                drafts.into_iter().map(DraftSaleResponse::from).collect();
+            HttpResponse::Ok().json(data)
+        }
+        Err(err) => HttpResponse::from(err),
+    }
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+
+use crate::{
+    application::queries::GetDraftSaleByIdQuery, infrastructure::Mediator,
+    presentation::common::DraftSaleResponse, shared::input_handler::Sender,
+};
+
+#[route("/{id}", method = "GET")]
+pub async fn get_draft_sale_by_id(
+    id: web::Path<u32>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let query = GetDraftSaleByIdQuery {
+        draft_sale_id: id.into_inner(),
+    };
+    let result = mediator.send(query).await;
+    match result {
+        Ok(o) => {
+            let data: DraftSaleResponse = DraftSaleResponse::from(o);
+            HttpResponse::Ok().json(data)
+        }
+        Err(err) => HttpResponse::from(err),
+    }
+}
@@ -0,0 +1,19 @@
+use actix_web::web::ServiceConfig;
+
+mod add_item;
+mod checkout;
+mod get_all;
+mod get_by_id;
+mod open;
+mod remove_item;
+mod set_parked;
+
+pub fn cfg(cfg: &mut ServiceConfig) {
+    cfg.service(open::open_draft_sale);
+    cfg.service(get_all::get_draft_sales);
+    cfg.service(get_by_id::get_draft_sale_by_id);
+    cfg.service(add_item::add_draft_item);
+    cfg.service(remove_item::remove_draft_item);
+    cfg.service(set_parked::set_draft_sale_parked);
+    cfg.service(checkout::checkout_draft_sale);
+}
@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::commands::OpenDraftSaleCommand, infrastructure::Mediator,
+    shared::input_handler::Sender,
+};
+
+#[derive(Debug, Deserialize)]
+struct OpenDraftSaleRequest {
+    customer_cc: String,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenDraftSaleResponse {
+    draft_sale_id: u32,
+}
+
+#[route("", method = "POST")]
+pub async fn open_draft_sale(
+    data: web::Json<OpenDraftSaleRequest>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let command = OpenDraftSaleCommand {
+        customer_cc: data.customer_cc.clone(),
+    };
+    let result = mediator.send(command).await;
+    match result {
+        Ok(output) => HttpResponse::Created().json(OpenDraftSaleResponse {
+            draft_sale_id: output.draft_sale_id,
+        }),
+        Err(err) => HttpResponse::from(err),
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+use serde::Deserialize;
+
+use crate::{
+    application::commands::RemoveDraftItemCommand, infrastructure::Mediator,
+    shared::input_handler::Sender,
+};
+
+#[derive(Debug, Deserialize)]
+struct RemoveDraftItemParams {
+    quantity: Option<u32>,
+}
+
+#[route("/{id}/items/{sku}", method = "DELETE")]
+pub async fn remove_draft_item(
+    path: web::Path<(u32, String)>,
+    params: web::Query<RemoveDraftItemParams>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let (draft_sale_id, sku) = path.into_inner();
+    let command = RemoveDraftItemCommand {
+        draft_sale_id,
+        sku,
+        quantity: params.quantity,
+    };
+    let result = mediator.send(command).await;
+    match result {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(err) => HttpResponse::from(err),
+    }
+}
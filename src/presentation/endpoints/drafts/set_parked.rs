@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+use serde::Deserialize;
+
+use crate::{
+    application::commands::SetDraftSaleParkedCommand, infrastructure::Mediator,
+    shared::input_handler::Sender,
+};
+
+#[derive(Debug, Deserialize)]
+struct SetDraftSaleParkedRequest {
+    parked: bool,
+}
+
+#[route("/{id}/parked", method = "PUT")]
+pub async fn set_draft_sale_parked(
+    id: web::Path<u32>,
+    data: web::Json<SetDraftSaleParkedRequest>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let command = SetDraftSaleParkedCommand {
+        draft_sale_id: id.into_inner(),
+        parked: data.parked,
+    };
+    let result = mediator.send(command).await;
+    match result {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(err) => HttpResponse::from(err),
+    }
+}
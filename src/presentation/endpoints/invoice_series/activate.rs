@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+
+use crate::{
+    application::commands::ActivateInvoiceSeriesCommand, infrastructure::Mediator,
+    shared::input_handler::Sender,
+};
+
+#[route("/{id}/activate", method = "POST")]
+pub async fn activate_invoice_series(
+    id: web::Path<u32>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let command = ActivateInvoiceSeriesCommand {
+        invoice_series_id: id.into_inner(),
+    };
+    let result = mediator.send(command).await;
+    match result {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(err) => HttpResponse::from(err),
+    }
+}
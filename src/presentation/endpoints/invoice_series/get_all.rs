@@ -0,0 +1,20 @@
+use actix_web::{HttpResponse, Responder, route, web};
+use std::sync::Arc;
+
+use crate::{
+    application::queries::GetInvoiceSeriesQuery, infrastructure::Mediator,
+    presentation::common::InvoiceSeriesResponse, shared::input_handler::Sender,
+};
+
+#[route("", method = "GET")]
+pub async fn get_invoice_series(mediator: web::Data<Arc<Mediator>>) -> impl Responder {
+    let result = mediator.send(GetInvoiceSeriesQuery {}).await;
+    match result {
+        Ok(o) => {
+            let data: Vec<InvoiceSeriesResponse> =
+                o.into_iter().map(InvoiceSeriesResponse::from).collect();
+            HttpResponse::Ok().json(data)
+        }
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,11 @@
+use actix_web::web::ServiceConfig;
+
+mod activate;
+mod get_all;
+mod register;
+
+pub fn cfg(cfg: &mut ServiceConfig) {
+    cfg.service(register::register_invoice_series);
+    cfg.service(get_all::get_invoice_series);
+    cfg.service(activate::activate_invoice_series);
+}
@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::commands::RegisterInvoiceSeriesCommand, infrastructure::Mediator,
+    shared::input_handler::Sender,
+};
+
+#[derive(Debug, Deserialize)]
+struct RegisterInvoiceSeriesRequest {
+    prefix: String,
+    range_from: u64,
+    range_to: u64,
+    warning_threshold: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct RegisterInvoiceSeriesResponse {
+    invoice_series_id: u32,
+}
+
+#[route("", method = "POST")]
+pub async fn register_invoice_series(
+    data: web::Json<RegisterInvoiceSeriesRequest>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let command = RegisterInvoiceSeriesCommand {
+        prefix: data.prefix.clone(),
+        range_from: data.range_from,
+        range_to: data.range_to,
+        warning_threshold: data.warning_threshold,
+    };
+    let result = mediator.send(command).await;
+    match result {
+        Ok(output) => HttpResponse::Created().json(RegisterInvoiceSeriesResponse {
+            invoice_series_id: output.invoice_series_id,
+        }),
+        Err(err) => HttpResponse::from(err),
+    }
+}
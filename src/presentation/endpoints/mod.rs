@@ -3,7 +3,11 @@ pub mod api;
 pub mod auth;
 
 // Modules for different domain entities
+pub mod categories;
 pub mod customers;
+pub mod drafts;
+pub mod invoice_series;
 pub mod products;
+pub mod promotions;
 pub mod sales;
 pub mod reports;
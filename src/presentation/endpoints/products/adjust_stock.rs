@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::commands::AdjustStockCommand, infrastructure::Mediator,
+    presentation::middlewares::AuthenticatedUser, shared::input_handler::Sender,
+};
+
+#[derive(Debug, Deserialize)]
+struct AdjustStockRequest {
+    delta: i64,
+    reason: String,
+}
+
+#[derive(Debug, Serialize)]
+struct AdjustStockResponse {
+    product_id: u32,
+    stock: u32,
+}
+
+#[route("/{sku}/stock-adjustments", method = "POST")]
+pub async fn adjust_stock(
+    sku: web::Path<String>,
+    data: web::Json<AdjustStockRequest>,
+    user: web::ReqData<AuthenticatedUser>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let command = AdjustStockCommand {
+        sku: sku.into_inner(),
+        delta: data.delta,
+        reason: data.reason.clone(),
+        user: user.into_inner().0,
+    };
+    let result = mediator.send(command).await;
+    match result {
+        Ok(output) => HttpResponse::Ok().json(AdjustStockResponse {
+            product_id: output.product_id,
+            stock: output.stock,
+        }),
+        Err(err) => HttpResponse::from(err),
+    }
+}
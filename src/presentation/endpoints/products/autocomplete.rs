@@ -0,0 +1,35 @@
+use actix_web::{HttpResponse, Responder, route, web};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::{
+    application::queries::AutocompleteProductsQuery, infrastructure::Mediator,
+    presentation::common::ProductSuggestionResponse, shared::input_handler::Sender,
+};
+
+#[derive(Debug, Deserialize)]
+struct AutocompleteProductsParams {
+    q: String,
+    limit: Option<u32>,
+}
+
+#[route("/autocomplete", method = "GET")]
+pub async fn autocomplete_products(
+    params: web::Query<AutocompleteProductsParams>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let params = params.into_inner();
+    let query = AutocompleteProductsQuery {
+        term: params.q,
+        limit: params.limit,
+    };
+    let result = mediator.send(query).await;
+    match result {
+        Ok(o) => {
+            let data: Vec<ProductSuggestionResponse> =
+                o.into_iter().map(ProductSuggestionResponse::from).collect();
+            HttpResponse::Ok().json(data)
+        }
+        Err(e) => HttpResponse::from(e),
+    }
+}
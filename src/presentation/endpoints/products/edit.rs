@@ -5,17 +5,19 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     application::commands::EditProductCommand, infrastructure::Mediator,
-    shared::input_handler::Sender,
+    presentation::common::MoneyAmount, shared::input_handler::Sender,
 };
 
 #[derive(Debug, Deserialize)]
 struct EditProductRequest {
     name: Option<String>,
     description: Option<String>,
-    price: Option<f64>,
-    stock: Option<u32>,
+    price: Option<MoneyAmount>,
+    tax_class: Option<String>,
+    reorder_point: Option<u32>,
+    reorder_quantity: Option<u32>,
     img_url: Option<String>,
-    flags: Option<Vec<String>>,
+    category_ids: Option<Vec<u32>>,
 }
 
 #[route("/{id}", method = "PUT")]
@@ -28,10 +30,12 @@ pub async fn edit_product(
         product_id: id.into_inner(),
         name: data.name.clone(),
         description: data.description.clone(),
-        price: data.price,
-        stock: data.stock,
+        price: data.price.map(Into::into),
+        tax_class: data.tax_class.clone(),
+        reorder_point: data.reorder_point,
+        reorder_quantity: data.reorder_quantity,
         img_url: data.img_url.clone(),
-        flags: data.flags.clone(),
+        category_ids: data.category_ids.clone(),
     };
     let result = mediator.send(command).await;
     if let Err(err) = result {
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+use serde::Deserialize;
+
+use crate::{
+    application::commands::GenerateProductVariantsCommand, infrastructure::Mediator,
+    presentation::common::ProductResponse, presentation::middlewares::AuthenticatedUser,
+    shared::input_handler::Sender,
+};
+
+#[derive(Debug, Deserialize)]
+struct GenerateProductVariantsRequest {
+    #[serde(default)]
+    sizes: Vec<String>,
+    #[serde(default)]
+    colors: Vec<String>,
+    stock: Option<u32>,
+}
+
+#[route("/{sku}/variants", method = "POST")]
+pub async fn generate_product_variants(
+    sku: web::Path<String>,
+    data: web::Json<GenerateProductVariantsRequest>,
+    user: web::ReqData<AuthenticatedUser>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let command = GenerateProductVariantsCommand {
+        sku: sku.into_inner(),
+        sizes: data.sizes.clone(),
+        colors: data.colors.clone(),
+        stock: data.stock,
+        user: user.into_inner().0,
+    };
+    let result = mediator.send(command).await;
+    match result {
+        Ok(o) => {
+            let data: Vec<ProductResponse> = o.into_iter().map(ProductResponse::from).collect();
+            HttpResponse::Created().json(data)
+        }
+        Err(err) => HttpResponse::from(err),
+    }
+}
@@ -0,0 +1,22 @@
+use actix_web::{HttpResponse, Responder, route, web};
+use std::sync::Arc;
+
+use crate::{
+    application::queries::GetProductDetailQuery, infrastructure::Mediator,
+    presentation::common::ProductDetailResponse, shared::input_handler::Sender,
+};
+
+#[route("/{sku}", method = "GET")]
+pub async fn get_product_detail(
+    sku: web::Path<String>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let query = GetProductDetailQuery {
+        sku: sku.into_inner(),
+    };
+    let result = mediator.send(query).await;
+    match result {
+        Ok(o) => HttpResponse::Ok().json(ProductDetailResponse::from(o)),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,19 @@
+use actix_web::{HttpResponse, Responder, route, web};
+use std::sync::Arc;
+
+use crate::{
+    application::queries::GetLowStockProductsQuery, infrastructure::Mediator,
+    presentation::common::ProductResponse, shared::input_handler::Sender,
+};
+
+#[route("/low-stock", method = "GET")]
+pub async fn get_low_stock_products(mediator: web::Data<Arc<Mediator>>) -> impl Responder {
+    let result = mediator.send(GetLowStockProductsQuery {}).await;
+    match result {
+        Ok(o) => {
+            let data: Vec<ProductResponse> = o.into_iter().map(ProductResponse::from).collect();
+            HttpResponse::Ok().json(data)
+        }
+        Err(e) => HttpResponse::from(e),
+    }
+}
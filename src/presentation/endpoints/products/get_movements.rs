@@ -0,0 +1,30 @@
+use actix_web::{HttpResponse, Responder, route, web};
+use std::sync::Arc;
+
+use crate::{
+    application::queries::GetStockMovementsQuery,
+    infrastructure::Mediator,
+    presentation::common::{Pagination, PaginationResult, StockMovementResponse},
+    shared::input_handler::Sender,
+};
+
+#[route("/{sku}/movements", method = "GET")]
+pub async fn get_stock_movements(
+    sku: web::Path<String>,
+    pagination: web::Query<Pagination>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let query = GetStockMovementsQuery {
+        sku: sku.into_inner(),
+        pagination: pagination.into_inner().into(),
+    };
+    let result = mediator.send(query).await;
+    match result {
+        Ok(o) => {
+            let data: PaginationResult<StockMovementResponse> =
+                PaginationResult::from(o.pagination_result);
+            HttpResponse::Ok().json(data)
+        }
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -1,20 +1,44 @@
 use actix_web::{HttpResponse, Responder, route, web};
+use serde::Deserialize;
 use std::sync::Arc;
 
 use crate::{
     application::queries::GetProductsQuery,
     infrastructure::Mediator,
+    presentation::common::MoneyAmount,
     presentation::common::{Pagination, PaginationResult, ProductResponse},
     shared::input_handler::Sender,
 };
 
+#[derive(Debug, Deserialize)]
+struct GetProductsParams {
+    category: Option<u32>,
+    search: Option<String>,
+    min_price: Option<MoneyAmount>,
+    max_price: Option<MoneyAmount>,
+    #[serde(default)]
+    in_stock: bool,
+    sort: Option<String>,
+    #[serde(default)]
+    archived: bool,
+}
+
 #[route("", method = "GET")]
 pub async fn get_paginated_products(
     pagination: web::Query<Pagination>,
+    params: web::Query<GetProductsParams>,
     mediator: web::Data<Arc<Mediator>>,
 ) -> impl Responder {
+    let params = params.into_inner();
     let query = GetProductsQuery {
         pagination: pagination.into_inner().into(),
+        category: params.category,
+        search: params.search,
+        min_price: params.min_price.map(Into::into),
+        max_price: params.max_price.map(Into::into),
+        in_stock: params.in_stock,
+        sort: params.sort,
+        archived: params.archived,
     };
     let result = mediator.send(query).await;
     match result {
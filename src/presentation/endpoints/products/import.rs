@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::commands::{ImportProductsCommand, ImportRowResult},
+    infrastructure::Mediator,
+    presentation::middlewares::AuthenticatedUser,
+    shared::input_handler::Sender,
+};
+
+#[derive(Debug, Deserialize)]
+struct ImportProductsParams {
+    mode: Option<String>,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ImportRowResponse {
+    row: u32,
+    sku: String,
+    status: String,
+    errors: Vec<String>,
+}
+impl From<ImportRowResult> for ImportRowResponse {
+    fn from(value: ImportRowResult) -> Self {
+        ImportRowResponse {
+            row: value.row,
+            sku: value.sku,
+            status: value.status,
+            errors: value.errors,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ImportProductsResponse {
+    dry_run: bool,
+    created: u32,
+    updated: u32,
+    failed: u32,
+    rows: Vec<ImportRowResponse>,
+}
+
+/// The body is the CSV file itself
+#[route("/import", method = "POST")]
+pub async fn import_products(
+    csv: String,
+    params: web::Query<ImportProductsParams>,
+    user: web::ReqData<AuthenticatedUser>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let params = params.into_inner();
+    let command = ImportProductsCommand {
+        csv,
+        mode: params.mode,
+        dry_run: params.dry_run,
+        user: user.into_inner().0,
+    };
+    let result = mediator.send(command).await;
+    match result {
+        Ok(output) => HttpResponse::Ok().json(ImportProductsResponse {
+            dry_run: output.dry_run,
+            created: output.created,
+            updated: output.updated,
+            failed: output.failed,
+            rows: output
+                .rows
+                .into_iter()
+                .map(ImportRowResponse::from)
+                .collect(),
+        }),
+        Err(err) => HttpResponse::from(err),
+    }
+}
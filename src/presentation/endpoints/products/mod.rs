@@ -4,10 +4,29 @@ mod edit;
 mod get_paginated;
 mod register;
 mod get_by_sku;
+mod get_low_stock;
+mod get_movements;
+mod adjust_stock;
+mod archive;
+mod import;
+mod unarchive;
+mod autocomplete;
+mod get_detail;
+mod generate_variants;
 
 pub fn cfg(cfg: &mut ServiceConfig) {
     cfg.service(register::register_product);
+    cfg.service(import::import_products);
     cfg.service(get_paginated::get_paginated_products);
     cfg.service(edit::edit_product);
     cfg.service(get_by_sku::get_product_by_sku);
+    cfg.service(get_movements::get_stock_movements);
+    // Registered before "/{sku}", which would otherwise match them
+    cfg.service(get_low_stock::get_low_stock_products);
+    cfg.service(autocomplete::autocomplete_products);
+    cfg.service(get_detail::get_product_detail);
+    cfg.service(generate_variants::generate_product_variants);
+    cfg.service(adjust_stock::adjust_stock);
+    cfg.service(archive::archive_product);
+    cfg.service(unarchive::unarchive_product);
 }
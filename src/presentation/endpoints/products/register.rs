@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     application::commands::RegisterProductCommand, infrastructure::Mediator,
+    presentation::common::MoneyAmount, presentation::middlewares::AuthenticatedUser,
     shared::input_handler::Sender,
 };
 
@@ -14,9 +15,13 @@ struct RegisterProductRequest {
     name: String,
     description: Option<String>,
     stock: Option<u32>,
-    price: f64,
+    price: MoneyAmount,
+    tax_class: Option<String>,
+    reorder_point: Option<u32>,
+    reorder_quantity: Option<u32>,
     img_url: Option<String>,
-    flags: Vec<String>,
+    #[serde(default)]
+    category_ids: Vec<u32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -27,6 +32,7 @@ struct RegisterProductResponse {
 #[route("", method = "POST")]
 pub async fn register_product(
     data: web::Json<RegisterProductRequest>,
+    user: web::ReqData<AuthenticatedUser>,
     mediator: web::Data<Arc<Mediator>>,
 ) -> impl Responder {
     let command = RegisterProductCommand {
@@ -34,9 +40,13 @@ pub async fn register_product(
         name: data.name.clone(),
         description: data.description.clone(),
         stock: data.stock,
-        price: data.price,
+        price: data.price.into(),
+        tax_class: data.tax_class.clone(),
+        reorder_point: data.reorder_point,
+        reorder_quantity: data.reorder_quantity,
         img_url: data.img_url.clone(),
-        flags: data.flags.clone(),
+        category_ids: data.category_ids.clone(),
+        user: user.into_inner().0,
     };
     let result = mediator.send(command).await;
     match result {
@@ -0,0 +1,24 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+
+use crate::{
+    application::commands::SetProductArchivedCommand, infrastructure::Mediator,
+    shared::input_handler::Sender,
+};
+
+#[route("/{sku}/unarchive", method = "POST")]
+pub async fn unarchive_product(
+    sku: web::Path<String>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let command = SetProductArchivedCommand {
+        sku: sku.into_inner(),
+        archived: false,
+    };
+    let result = mediator.send(command).await;
+    match result {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(err) => HttpResponse::from(err),
+    }
+}
@@ -0,0 +1,11 @@
+use actix_web::web::ServiceConfig;
+
+mod get_paginated;
+mod register;
+mod set_active;
+
+pub fn cfg(cfg: &mut ServiceConfig) {
+    cfg.service(register::register_promotion);
+    cfg.service(get_paginated::get_paginated_promotions);
+    cfg.service(set_active::set_promotion_active);
+}
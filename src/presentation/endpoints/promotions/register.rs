@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::commands::RegisterPromotionCommand, infrastructure::Mediator,
+    presentation::common::MoneyAmount, shared::input_handler::Sender,
+};
+
+#[derive(Debug, Deserialize)]
+struct RegisterPromotionRequest {
+    name: String,
+    code: Option<String>,
+    scope: String,
+    kind: String,
+    percentage: Option<f64>,
+    amount: Option<MoneyAmount>,
+    buy_quantity: Option<u32>,
+    pay_quantity: Option<u32>,
+    category_id: Option<u32>,
+    starts_at: Option<DateTime<Utc>>,
+    ends_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+struct RegisterPromotionResponse {
+    promotion_id: u32,
+}
+
+#[route("", method = "POST")]
+pub async fn register_promotion(
+    data: web::Json<RegisterPromotionRequest>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let command = RegisterPromotionCommand {
+        name: data.name.clone(),
+        code: data.code.clone(),
+        scope: data.scope.clone(),
+        kind: data.kind.clone(),
+        percentage: data.percentage,
+        amount: data.amount.map(Into::into),
+        buy_quantity: data.buy_quantity,
+        pay_quantity: data.pay_quantity,
+        category_id: data.category_id,
+        starts_at: data.starts_at,
+        ends_at: data.ends_at,
+    };
+    let result = mediator.send(command).await;
+    match result {
+        Ok(output) => HttpResponse::Created().json(RegisterPromotionResponse {
+            promotion_id: output.promotion_id,
+        }),
+        Err(err) => HttpResponse::from(err),
+    }
+}
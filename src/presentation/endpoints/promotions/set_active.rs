@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+use serde::Deserialize;
+
+use crate::{
+    application::commands::SetPromotionActiveCommand, infrastructure::Mediator,
+    shared::input_handler::Sender,
+};
+
+#[derive(Debug, Deserialize)]
+struct SetPromotionActiveRequest {
+    active: bool,
+}
+
+#[route("/{id}/active", method = "PUT")]
+pub async fn set_promotion_active(
+    id: web::Path<u32>,
+    data: web::Json<SetPromotionActiveRequest>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let command = SetPromotionActiveCommand {
+        promotion_id: id.into_inner(),
+        active: data.active,
+    };
+    let result = mediator.send(command).await;
+    match result {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(err) => HttpResponse::from(err),
+    }
+}
@@ -5,11 +5,27 @@ use actix_web::{
     web::{self, ServiceConfig},
 };
 
+use serde::Serialize;
+
 use crate::{
-    application::queries::GenerateCsvReportQuery, infrastructure::Mediator,
+    application::queries::{GenerateCsvReportQuery, GetPaymentTotalsQuery},
+    infrastructure::Mediator,
+    presentation::common::MoneyAmount,
     shared::input_handler::Sender,
 };
 
+#[derive(Debug, Serialize)]
+struct PaymentMethodTotalResponse {
+    method: String,
+    amount: MoneyAmount,
+}
+
+#[derive(Debug, Serialize)]
+struct PaymentTotalsResponse {
+    totals: Vec<PaymentMethodTotalResponse>,
+    change: MoneyAmount,
+}
+
 #[route("/csv", method = "GET")]
 async fn generate_csv_report(mediator: web::Data<Arc<Mediator>>) -> impl Responder {
     let query = GenerateCsvReportQuery {};
@@ -25,6 +41,26 @@ async fn generate_csv_report(mediator: web::Data<Arc<Mediator>>) -> impl Respond
     }
 }
 
+#[route("/payments", method = "GET")]
+async fn get_payment_totals(mediator: web::Data<Arc<Mediator>>) -> impl Responder {
+    let query = GetPaymentTotalsQuery {};
+    match mediator.send(query).await {
+        Ok(output) => HttpResponse::Ok().json(PaymentTotalsResponse {
+            totals: output
+                .totals
+                .into_iter()
+                .map(|(method, amount)| PaymentMethodTotalResponse {
+                    method,
+                    amount: amount.into(),
+                })
+                .collect(),
+            change: output.change.into(),
+        }),
+        Err(err) => HttpResponse::from(err),
+    }
+}
+
 pub fn cfg(cfg: &mut ServiceConfig) {
     cfg.service(generate_csv_report);
+    cfg.service(get_payment_totals);
 }
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+use serde::Deserialize;
+
+use crate::{
+    application::commands::CancelSaleCommand, infrastructure::Mediator,
+    presentation::middlewares::AuthenticatedUser, shared::input_handler::Sender,
+};
+
+#[derive(Debug, Deserialize)]
+struct CancelSaleRequest {
+    reason: String,
+}
+
+#[route("/{id}/cancel", method = "POST")]
+pub async fn cancel_sale(
+    id: web::Path<u32>,
+    data: web::Json<CancelSaleRequest>,
+    user: web::ReqData<AuthenticatedUser>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let command = CancelSaleCommand {
+        sale_id: id.into_inner(),
+        reason: data.reason.clone(),
+        user: user.into_inner().0,
+    };
+    let result = mediator.send(command).await;
+    match result {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(err) => HttpResponse::from(err),
+    }
+}
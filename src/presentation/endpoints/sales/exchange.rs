@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::commands::{ExchangeCommand, PaymentInput},
+    infrastructure::Mediator,
+    presentation::{common::MoneyAmount, middlewares::AuthenticatedUser},
+    shared::input_handler::Sender,
+};
+
+#[derive(Debug, Deserialize, Clone)]
+struct ProductSkuQuantity {
+    sku: String,
+    quantity: u32,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+struct PaymentRequest {
+    method: String,
+    amount: MoneyAmount,
+    tendered: Option<MoneyAmount>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExchangeRequest {
+    returned: Vec<ProductSkuQuantity>,
+    replacements: Vec<ProductSkuQuantity>,
+    reason: Option<String>,
+    #[serde(default)]
+    payments: Vec<PaymentRequest>,
+}
+
+#[derive(Debug, Serialize)]
+struct ExchangeResponse {
+    exchange_id: u32,
+    original_sale_id: u32,
+    return_id: u32,
+    replacement_sale_id: u32,
+    refunded_amount: MoneyAmount,
+    replacement_amount: MoneyAmount,
+    price_difference: MoneyAmount,
+    created_at: String,
+}
+
+#[route("/{id}/exchanges", method = "POST")]
+pub async fn exchange(
+    id: web::Path<u32>,
+    request: web::Json<ExchangeRequest>,
+    user: web::ReqData<AuthenticatedUser>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let command = ExchangeCommand {
+        sale_id: id.into_inner(),
+        returned_skus_quantity: request
+            .returned
+            .iter()
+            .map(|psq| (psq.sku.clone(), psq.quantity))
+            .collect(),
+        replacement_skus_quantity: request
+            .replacements
+            .iter()
+            .map(|psq| (psq.sku.clone(), psq.quantity))
+            .collect(),
+        reason: request.reason.clone(),
+        payments: request
+            .payments
+            .iter()
+            .map(|p| PaymentInput {
+                method: p.method.clone(),
+                amount: p.amount.into(),
+                tendered: p.tendered.map(Into::into),
+            })
+            .collect(),
+        user: user.into_inner().0,
+    };
+    let result = mediator.send(command).await;
+    match result {
+        Ok(output) => HttpResponse::Created().json(ExchangeResponse {
+            exchange_id: output.exchange_id,
+            original_sale_id: output.original_sale_id,
+            return_id: output.return_id,
+            replacement_sale_id: output.replacement_sale_id,
+            refunded_amount: output.refunded_amount.into(),
+            replacement_amount: output.replacement_amount.into(),
+            price_difference: output.price_difference.into(),
+            created_at: output.created_at,
+        }),
+        Err(err) => HttpResponse::from(err),
+    }
+}
@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+
+use crate::{
+    application::commands::GenerateElectronicInvoiceCommand, infrastructure::Mediator,
+    presentation::common::ElectronicInvoiceResponse, shared::input_handler::Sender,
+};
+
+#[route("/{id}/einvoice", method = "POST")]
+pub async fn generate_electronic_invoice(
+    id: web::Path<u32>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let command = GenerateElectronicInvoiceCommand {
+        sale_id: id.into_inner(),
+    };
+    let result = mediator.send(command).await;
+    match result {
+        Ok(o) => HttpResponse::Ok().json(ElectronicInvoiceResponse::from(o)),
+        Err(err) => HttpResponse::from(err),
+    }
+}
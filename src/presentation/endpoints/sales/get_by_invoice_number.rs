@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+
+use crate::{
+    application::queries::GetSaleByInvoiceNumberQuery, infrastructure::Mediator,
+    presentation::common::SaleResponse, shared::input_handler::Sender,
+};
+
+#[route("/invoice/{invoice_number}", method = "GET")]
+pub async fn get_sale_by_invoice_number(
+    invoice_number: web::Path<String>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let query = GetSaleByInvoiceNumberQuery {
+        invoice_number: invoice_number.into_inner(),
+    };
+    let result = mediator.send(query).await;
+    match result {
+        Ok(o) => {
+            let data: SaleResponse = SaleResponse::from(o);
+            HttpResponse::Ok().json(data)
+        }
+        Err(err) => HttpResponse::from(err),
+    }
+}
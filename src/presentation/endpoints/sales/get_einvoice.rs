@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+
+use crate::{
+    application::queries::GetElectronicInvoiceQuery, infrastructure::Mediator,
+    shared::input_handler::Sender,
+};
+
+#[route("/{id}/einvoice", method = "GET")]
+pub async fn get_electronic_invoice(
+    id: web::Path<u32>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let query = GetElectronicInvoiceQuery {
+        sale_id: id.into_inner(),
+    };
+    let result = mediator.send(query).await;
+    match result {
+        Ok(output) => HttpResponse::Ok()
+            .content_type("application/xml")
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"{}.xml\"", output.invoice_number),
+            ))
+            .body(output.xml),
+        Err(err) => HttpResponse::from(err),
+    }
+}
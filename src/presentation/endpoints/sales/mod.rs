@@ -1,11 +1,29 @@
 use actix_web::web::ServiceConfig;
 
+mod cancel;
+mod exchange;
+mod generate_einvoice;
+mod get_einvoice;
 mod get_paginated;
 mod register;
+mod register_return;
+mod send_receipt;
 mod get_by_id;
+mod get_by_invoice_number;
+mod preview;
+mod receipt;
 
 pub fn cfg(cfg: &mut ServiceConfig) {
     cfg.service(register::register_sale);
+    cfg.service(preview::preview_sale);
     cfg.service(get_paginated::get_sales_paginated);
     cfg.service(get_by_id::get_sale_by_id);
+    cfg.service(get_by_invoice_number::get_sale_by_invoice_number);
+    cfg.service(receipt::get_sale_receipt);
+    cfg.service(send_receipt::send_sale_receipt);
+    cfg.service(generate_einvoice::generate_electronic_invoice);
+    cfg.service(get_einvoice::get_electronic_invoice);
+    cfg.service(cancel::cancel_sale);
+    cfg.service(register_return::register_return);
+    cfg.service(exchange::exchange);
 }
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+use serde::Deserialize;
+
+use crate::{
+    application::{commands::PaymentInput, queries::PreviewSaleQuery},
+    infrastructure::Mediator,
+    presentation::common::MoneyAmount,
+    presentation::common::SalePreviewResponse,
+    shared::input_handler::Sender,
+};
+
+#[derive(Debug, Deserialize, Clone)]
+struct ProductSkuQuantity {
+    sku: String,
+    quantity: u32,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+struct PaymentRequest {
+    method: String,
+    amount: MoneyAmount,
+    tendered: Option<MoneyAmount>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PreviewSaleRequest {
+    customer_cc: String,
+    product_skus_quantity: Vec<ProductSkuQuantity>,
+    #[serde(default)]
+    coupon_codes: Vec<String>,
+    #[serde(default)]
+    payments: Vec<PaymentRequest>,
+}
+
+#[route("/preview", method = "POST")]
+pub async fn preview_sale(
+    request: web::Json<PreviewSaleRequest>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let query = PreviewSaleQuery {
+        customer_cc: request.customer_cc.clone(),
+        product_skus_quantity: request
+            .product_skus_quantity
+            .iter()
+            .map(|psq| (psq.sku.clone(), psq.quantity))
+            .collect(),
+        coupon_codes: request.coupon_codes.clone(),
+        payments: request
+            .payments
+            .iter()
+            .map(|p| PaymentInput {
+                method: p.method.clone(),
+                amount: p.amount.into(),
+                tendered: p.tendered.map(Into::into),
+            })
+            .collect(),
+    };
+    let result = mediator.send(query).await;
+    match result {
+        Ok(o) => {
+            let data: SalePreviewResponse = SalePreviewResponse::from(o);
+            HttpResponse::Ok().json(data)
+        }
+        Err(err) => HttpResponse::from(err),
+    }
+}
@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+use serde::Deserialize;
+
+use crate::{
+    application::queries::GetSaleReceiptQuery, infrastructure::Mediator,
+    shared::input_handler::Sender,
+};
+
+#[derive(Debug, Deserialize)]
+struct ReceiptParams {
+    format: Option<String>,
+    gift: Option<bool>,
+}
+
+#[route("/{id}/receipt", method = "GET")]
+pub async fn get_sale_receipt(
+    id: web::Path<u32>,
+    params: web::Query<ReceiptParams>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let query = GetSaleReceiptQuery {
+        sale_id: id.into_inner(),
+        format: params.format.clone().unwrap_or("text".to_string()),
+        gift: params.gift.unwrap_or(false),
+    };
+    let result = mediator.send(query).await;
+    match result {
+        Ok(output) => HttpResponse::Ok()
+            .content_type(output.content_type)
+            .body(output.content),
+        Err(err) => HttpResponse::from(err),
+    }
+}
@@ -1,10 +1,12 @@
 use std::sync::Arc;
 
-use actix_web::{HttpResponse, Responder, route, web};
+use actix_web::{HttpRequest, HttpResponse, Responder, route, web};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    application::commands::RegisterSaleCommand, infrastructure::Mediator,
+    application::commands::{PaymentInput, RegisterSaleCommand},
+    infrastructure::Mediator,
+    presentation::{common::MoneyAmount, middlewares::AuthenticatedUser},
     shared::input_handler::Sender,
 };
 
@@ -14,21 +16,37 @@ struct ProductSkuQuantity {
     quantity: u32,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+struct PaymentRequest {
+    method: String,
+    amount: MoneyAmount,
+    tendered: Option<MoneyAmount>,
+}
+
 #[derive(Debug, Deserialize)]
 struct RegisterSaleRequest {
     customer_cc: String,
     product_skus_quantity: Vec<ProductSkuQuantity>,
+    #[serde(default)]
+    coupon_codes: Vec<String>,
+    payments: Vec<PaymentRequest>,
 }
 
 #[derive(Debug, Serialize)]
 struct RegisterSaleResponse {
     sale_id: u32,
-    total_amount: f64,
+    invoice_number: String,
+    subtotal: MoneyAmount,
+    discount_amount: MoneyAmount,
+    total_amount: MoneyAmount,
+    change: MoneyAmount,
 }
 
 #[route("", method = "POST")]
 pub async fn register_sale(
+    http_request: HttpRequest,
     request: web::Json<RegisterSaleRequest>,
+    user: web::ReqData<AuthenticatedUser>,
     mediator: web::Data<Arc<Mediator>>,
 ) -> impl Responder {
     let command = RegisterSaleCommand {
@@ -38,6 +56,22 @@ pub async fn register_sale(
             .iter()
             .map(|psq| (psq.sku.clone(), psq.quantity))
             .collect(),
+        coupon_codes: request.coupon_codes.clone(),
+        payments: request
+            .payments
+            .iter()
+            .map(|p| PaymentInput {
+                method: p.method.clone(),
+                amount: p.amount.into(),
+                tendered: p.tendered.map(Into::into),
+            })
+            .collect(),
+        idempotency_key: http_request
+            .headers()
+            .get("Idempotency-Key")
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string()),
+        user: user.into_inner().0,
     };
     let output = mediator.send(command).await;
     if let Err(err) = output {
@@ -46,7 +80,11 @@ pub async fn register_sale(
         let output = output.unwrap();
         HttpResponse::Created().json(RegisterSaleResponse {
             sale_id: output.sale_id,
-            total_amount: output.total_amount,
+            invoice_number: output.invoice_number,
+            subtotal: output.subtotal.into(),
+            discount_amount: output.discount_amount.into(),
+            total_amount: output.total_amount.into(),
+            change: output.change.into(),
         })
     }
 }
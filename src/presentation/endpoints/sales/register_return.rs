@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, route, web};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::commands::RegisterReturnCommand, infrastructure::Mediator,
+    presentation::common::MoneyAmount, presentation::middlewares::AuthenticatedUser,
+    shared::input_handler::Sender,
+};
+
+#[derive(Debug, Deserialize, Clone)]
+struct ProductSkuQuantity {
+    sku: String,
+    quantity: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct RegisterReturnRequest {
+    product_skus_quantity: Vec<ProductSkuQuantity>,
+    reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct RegisterReturnResponse {
+    return_id: u32,
+    refunded_amount: MoneyAmount,
+}
+
+#[route("/{id}/returns", method = "POST")]
+pub async fn register_return(
+    id: web::Path<u32>,
+    request: web::Json<RegisterReturnRequest>,
+    user: web::ReqData<AuthenticatedUser>,
+    mediator: web::Data<Arc<Mediator>>,
+) -> impl Responder {
+    let command = RegisterReturnCommand {
+        sale_id: id.into_inner(),
+        product_skus_quantity: request
+            .product_skus_quantity
+            .iter()
+            .map(|psq| (psq.sku.clone(), psq.quantity))
+            .collect(),
+        reason: request.reason.clone(),
+        user: user.into_inner().0,
+    };
+    let result = mediator.send(command).await;
+    match result {
+        Ok(output) => HttpResponse::Created().json(RegisterReturnResponse {
+            return_id: output.return_id,
+            refunded_amount: output.refunded_amount.into(),
+        }),
+        Err(err) => HttpResponse::from(err),
+    }
+}
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use actix_web::{
-    Error, HttpResponse,
+    Error, HttpMessage, HttpResponse,
     body::{BoxBody, MessageBody},
     dev::{ServiceRequest, ServiceResponse},
     middleware::Next,
@@ -41,6 +41,10 @@ pub async fn loggin_middleware(
     result
 }
 
+/// User owning the API key of the request, available to the endpoints behind `auth_middleware`
+#[derive(Clone)]
+pub struct AuthenticatedUser(pub String);
+
 pub async fn auth_middleware(
     req: ServiceRequest,
     next: Next<BoxBody>,
@@ -57,8 +61,11 @@ pub async fn auth_middleware(
         .map(|d| d.as_ref())
         .unwrap()
         .resolve();
-    if let Err(e) = credentials_validator.has_access(&key.to_string()) {
-        return Ok(req.into_response(HttpResponse::from(e)));
+    match credentials_validator.has_access(&key.to_string()) {
+        Ok(user) => {
+            req.extensions_mut().insert(AuthenticatedUser(user));
+        }
+        Err(e) => return Ok(req.into_response(HttpResponse::from(e))),
     }
     next.call(req).await
 }
@@ -18,6 +18,11 @@ pub fn configure(cfg: &mut ServiceConfig) {
                     .wrap(from_fn(middlewares::auth_middleware))
                     .configure(endpoints::sales::cfg),
             )
+            .service(
+                scope("/drafts")
+                    .wrap(from_fn(middlewares::auth_middleware))
+                    .configure(endpoints::drafts::cfg),
+            )
             .service(
                 scope("/customers")
                     .wrap(from_fn(middlewares::auth_middleware))
@@ -28,6 +33,21 @@ pub fn configure(cfg: &mut ServiceConfig) {
                     .wrap(from_fn(middlewares::auth_middleware))
                     .configure(endpoints::products::cfg),
             )
+            .service(
+                scope("/categories")
+                    .wrap(from_fn(middlewares::auth_middleware))
+                    .configure(endpoints::categories::cfg),
+            )
+            .service(
+                scope("/promotions")
+                    .wrap(from_fn(middlewares::auth_middleware))
+                    .configure(endpoints::promotions::cfg),
+            )
+            .service(
+                scope("/invoice-series")
+                    .wrap(from_fn(middlewares::auth_middleware))
+                    .configure(endpoints::invoice_series::cfg),
+            )
             .service(
                 scope("/reports")
                     .wrap(from_fn(middlewares::auth_middleware))